@@ -0,0 +1,384 @@
+//! Compute units used by every instruction, run through Mollusk against the SBF build
+//! with the accounts a real transaction would carry.
+//!
+//! The bencher writes `benches/compute_units.md`. Each instruction is then checked
+//! against `benches/baseline.txt` and the run fails if any of them got more expensive,
+//! or if there is no baseline. Set `BENCH_UPDATE_BASELINE=1` to write the new numbers.
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use bondr_pinocchio::{
+    client::{
+        self, MigrateAccountAccounts, TransferAdminAccounts, UpdateFreelancerBadgeAccounts,
+        WithdrawTreasuryAccounts, PROGRAM_ID,
+    },
+    instructions::{BondrInstruction, UpdateFreelancerBadge, WithdrawTreasury},
+    states::{DataLen, FreelancerBadge, ReputationTier},
+};
+use common::*;
+use mollusk_svm::{
+    program::{create_program_account_loader_v3, keyed_account_for_system_program},
+    result::ProgramResult,
+    Mollusk,
+};
+use mollusk_svm_bencher::MolluskComputeUnitBencher;
+use solana_sdk::{account::Account, instruction::Instruction, pubkey::Pubkey};
+
+struct Case {
+    name: &'static str,
+    instruction: Instruction,
+    accounts: Vec<(Pubkey, Account)>,
+}
+
+/// The accounts `instruction` sees in `env` right now, program and sysvar accounts included.
+fn accounts_for(env: &Env, instruction: &Instruction) -> Vec<(Pubkey, Account)> {
+    let mollusk = &env.ctx.mollusk;
+    let mut accounts: Vec<(Pubkey, Account)> = Vec::new();
+
+    for meta in &instruction.accounts {
+        let key = meta.pubkey;
+        if accounts.iter().any(|(seen, _)| *seen == key) {
+            continue;
+        }
+
+        let account = if key == SYSTEM_PROGRAM {
+            keyed_account_for_system_program().1
+        } else if [PROGRAM_ID, TOKEN_PROGRAM, MPL_CORE].contains(&key) {
+            create_program_account_loader_v3(&key)
+        } else if key == RENT_SYSVAR {
+            mollusk.sysvars.keyed_account_for_rent_sysvar().1
+        } else {
+            env.account(&key)
+        };
+        accounts.push((key, account));
+    }
+    accounts
+}
+
+fn case(env: &Env, name: &'static str, instruction: Instruction) -> Case {
+    Case {
+        name,
+        accounts: accounts_for(env, &instruction),
+        instruction,
+    }
+}
+
+/// One case per instruction, each set up in `env` without running the instruction itself.
+fn cases(env: &Env) -> Vec<Case> {
+    let mut cases = Vec::new();
+
+    let (_, create) = env.escrow_ix(&env.party(), &env.party(), EscrowArgs::default());
+    cases.push(case(env, "initialize_escrow", create));
+
+    let freelancer = env.party().wallet;
+    cases.push(case(
+        env,
+        "initialize_freelancer_badge",
+        env.badge_ix(&freelancer),
+    ));
+
+    let client = env.party().wallet;
+    let members = [client, Pubkey::new_unique(), Pubkey::new_unique()];
+    cases.push(case(
+        env,
+        "initialize_multisig_client",
+        env.multisig_ix(&client, &members, 2),
+    ));
+
+    let escrow = env.create_escrow(EscrowArgs::default());
+    cases.push(case(env, "release_payment", env.release(&escrow)));
+
+    let escrow = env.create_escrow(EscrowArgs::default());
+    env.create_badge(&escrow.freelancer.wallet);
+    env.ok(&env.release(&escrow));
+    cases.push(case(env, "claim_payment", env.claim(&escrow)));
+
+    let team = env.party();
+    let members = [team.wallet, Pubkey::new_unique(), Pubkey::new_unique()];
+    let multisig = env.create_multisig(&team.wallet, &members, 2);
+    let escrow = env.create_escrow_between(
+        &team,
+        &env.party(),
+        EscrowArgs {
+            multisig: Some(multisig),
+            ..EscrowArgs::default()
+        },
+    );
+    cases.push(case(
+        env,
+        "approve_multisig_release",
+        env.approve(&escrow, &members[1]),
+    ));
+
+    let freelancer = env.party().wallet;
+    let badge = env.create_badge(&freelancer);
+    cases.push(case(
+        env,
+        "update_freelancer_badge",
+        client::update_freelancer_badge(
+            &UpdateFreelancerBadgeAccounts {
+                admin: env.admin,
+                badge,
+                config: env.config,
+            },
+            &UpdateFreelancerBadge {
+                value: ESCROW_AMOUNT,
+            },
+        ),
+    ));
+
+    let freelancer = env.party().wallet;
+    env.create_badge(&freelancer);
+    env.set_completed_escrows(&freelancer, 3);
+    cases.push(case(
+        env,
+        "mint_reputation_nft",
+        env.mint_nft(&freelancer, &Pubkey::new_unique()),
+    ));
+
+    let escrow = env.create_escrow(EscrowArgs::default());
+    cases.push(case(env, "cancel_escrow", env.cancel(&escrow, true)));
+
+    let milestones = EscrowArgs {
+        milestones: vec![ESCROW_AMOUNT / 2; 2],
+        ..EscrowArgs::default()
+    };
+    let escrow = env.create_escrow(milestones.clone());
+    cases.push(case(
+        env,
+        "release_milestone",
+        env.release_milestone(&escrow, 0),
+    ));
+
+    let escrow = env.create_escrow(milestones);
+    env.create_badge(&escrow.freelancer.wallet);
+    env.ok(&env.release_milestone(&escrow, 0));
+    cases.push(case(
+        env,
+        "claim_milestone",
+        env.claim_milestone(&escrow, 0),
+    ));
+
+    let disputable = EscrowArgs {
+        arbiter: Pubkey::new_unique(),
+        ..EscrowArgs::default()
+    };
+    let escrow = env.create_escrow(disputable.clone());
+    cases.push(case(
+        env,
+        "open_dispute",
+        env.open_dispute(&escrow, &escrow.client.wallet),
+    ));
+
+    let escrow = env.create_escrow(disputable);
+    env.create_badge(&escrow.freelancer.wallet);
+    env.ok(&env.open_dispute(&escrow, &escrow.client.wallet));
+    cases.push(case(
+        env,
+        "resolve_dispute",
+        env.resolve_dispute(&escrow, 5_000),
+    ));
+
+    let uninitialized = Env::uninitialized();
+    cases.push(case(
+        &uninitialized,
+        "initialize_config",
+        uninitialized.initialize_config(&uninitialized.admin, &uninitialized.settings()),
+    ));
+
+    let escrow = env.create_escrow(EscrowArgs::default());
+    env.ok(&env.release(&escrow));
+    env.ok(&env.claim(&escrow));
+    let destination = env.party();
+    cases.push(case(
+        env,
+        "withdraw_treasury",
+        client::withdraw_treasury(
+            &WithdrawTreasuryAccounts {
+                admin: env.admin,
+                config: env.config,
+                treasury: env.treasury,
+                destination: destination.wallet,
+                treasury_token_account: env.treasury_token,
+                destination_token_account: destination.token,
+                token_mint: env.mint,
+                token_program: TOKEN_PROGRAM,
+                system_program: SYSTEM_PROGRAM,
+            },
+            &WithdrawTreasury {
+                amount: protocol_fee(ESCROW_AMOUNT, ReputationTier::Unranked as usize),
+            },
+        ),
+    ));
+
+    cases.push(case(
+        env,
+        "update_config",
+        env.update_config(&env.settings()),
+    ));
+
+    cases.push(case(
+        env,
+        "transfer_admin",
+        client::transfer_admin(&TransferAdminAccounts {
+            admin: env.admin,
+            new_admin: Pubkey::new_unique(),
+            config: env.config,
+        }),
+    ));
+
+    cases.push(case(env, "set_pause", env.set_pause(&env.admin, 0, true)));
+
+    // a badge from before its trailing fields existed, so the migration has work to do
+    let freelancer = env.party().wallet;
+    let badge = env.create_badge(&freelancer);
+    let mut account = env.account(&badge);
+    account.data.truncate(FreelancerBadge::LEN - 12);
+    env.set_account(&badge, account);
+    cases.push(case(
+        env,
+        "migrate_account",
+        client::migrate_account(&MigrateAccountAccounts {
+            payer: env.party().wallet,
+            account: badge,
+            system_program: SYSTEM_PROGRAM,
+            config: env.config,
+        }),
+    ));
+
+    // membership changes approved by two of three members
+    let team = || {
+        let client = env.party().wallet;
+        let members = [client, Pubkey::new_unique(), Pubkey::new_unique()];
+        (env.create_multisig(&client, &members, 2), members)
+    };
+
+    let (multisig, members) = team();
+    cases.push(case(
+        env,
+        "add_multisig_member",
+        env.add_member(&multisig, &Pubkey::new_unique(), &members[..2]),
+    ));
+
+    let (multisig, members) = team();
+    cases.push(case(
+        env,
+        "remove_multisig_member",
+        env.remove_member(&multisig, &members[2], &members[..2]),
+    ));
+
+    let (multisig, members) = team();
+    cases.push(case(
+        env,
+        "rotate_multisig_member",
+        env.rotate_member(&multisig, &members[2], &Pubkey::new_unique(), &members[..2]),
+    ));
+
+    let (multisig, members) = team();
+    cases.push(case(
+        env,
+        "change_threshold",
+        env.change_threshold(&multisig, 3, &members[..2]),
+    ));
+
+    cases
+}
+
+fn baseline_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("benches/baseline.txt")
+}
+
+/// `name units` per line; `#` starts a comment.
+fn read_baseline() -> Option<BTreeMap<String, u64>> {
+    let content = fs::read_to_string(baseline_path()).ok()?;
+
+    let baseline = content
+        .lines()
+        .map(|line| line.split('#').next().unwrap().trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (name, units) = line
+                .split_once(char::is_whitespace)
+                .unwrap_or_else(|| panic!("malformed baseline line: {line}"));
+            (name.to_string(), units.trim().parse().unwrap())
+        })
+        .collect();
+    Some(baseline)
+}
+
+fn write_baseline(measured: &[(&str, u64)]) {
+    let mut content =
+        String::from("# compute units per instruction, see benches/compute_units.rs\n");
+    for (name, units) in measured {
+        content.push_str(&format!("{name} {units}\n"));
+    }
+    fs::write(baseline_path(), content).unwrap();
+}
+
+fn main() {
+    let env = Env::new();
+    let cases = cases(&env);
+
+    // every instruction is benched exactly once
+    let mut benched: Vec<u8> = cases.iter().map(|case| case.instruction.data[0]).collect();
+    benched.sort_unstable();
+    let all: Vec<u8> = (0..=u8::MAX)
+        .take_while(|&discriminator| BondrInstruction::try_from(&discriminator).is_ok())
+        .collect();
+    assert_eq!(benched, all, "benches don't match BondrInstruction");
+
+    let Env { ctx, .. } = env;
+    let mollusk: Mollusk = ctx.mollusk;
+
+    let measured: Vec<(&str, u64)> = cases
+        .iter()
+        .map(|case| {
+            let result = mollusk.process_instruction(&case.instruction, &case.accounts);
+            assert!(
+                matches!(result.program_result, ProgramResult::Success),
+                "{} failed: {:?}",
+                case.name,
+                result.program_result
+            );
+            (case.name, result.compute_units_consumed)
+        })
+        .collect();
+
+    let mut bencher = MolluskComputeUnitBencher::new(mollusk).must_pass(true);
+    for case in &cases {
+        bencher = bencher.bench((case.name, &case.instruction, &case.accounts));
+    }
+    bencher.execute();
+
+    if std::env::var_os("BENCH_UPDATE_BASELINE").is_some() {
+        write_baseline(&measured);
+        println!("wrote {}", baseline_path().display());
+        return;
+    }
+    let baseline = read_baseline().unwrap_or_else(|| {
+        panic!(
+            "{} is missing (run with BENCH_UPDATE_BASELINE=1 to create it)",
+            baseline_path().display()
+        )
+    });
+
+    let regressions: Vec<String> = measured
+        .iter()
+        .filter_map(|(name, units)| match baseline.get(*name) {
+            Some(&allowed) if *units > allowed => {
+                Some(format!("{name}: {units} CUs, baseline {allowed}"))
+            }
+            Some(_) => None,
+            None => Some(format!("{name}: {units} CUs, not in the baseline")),
+        })
+        .collect();
+
+    assert!(
+        regressions.is_empty(),
+        "compute units regressed (rerun with BENCH_UPDATE_BASELINE=1 to accept):\n{}",
+        regressions.join("\n")
+    );
+}
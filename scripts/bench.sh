@@ -0,0 +1,8 @@
+#!/usr/bin/env bash
+# Measures compute units per instruction and checks them against benches/baseline.txt.
+set -euo pipefail
+
+cd "$(dirname "$0")/.."
+
+scripts/build-sbf.sh
+SBF_OUT_DIR=target/deploy cargo bench --features test-sbf --bench compute_units
@@ -0,0 +1,17 @@
+#!/usr/bin/env bash
+# Builds the program and the mock mpl-core into target/deploy and fetches both token programs.
+set -euo pipefail
+
+cd "$(dirname "$0")/.."
+
+cargo build-sbf
+cargo build-sbf --manifest-path tests/programs/mock-mpl-core/Cargo.toml --sbf-out-dir target/deploy
+
+# the token programs aren't built here; Mollusk loads the mainnet programs from tests/fixtures
+mkdir -p tests/fixtures
+if [ ! -f tests/fixtures/spl_token.so ]; then
+    solana program dump -u m TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA tests/fixtures/spl_token.so
+fi
+if [ ! -f tests/fixtures/spl_token_2022.so ]; then
+    solana program dump -u m TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb tests/fixtures/spl_token_2022.so
+fi
@@ -0,0 +1,13 @@
+#!/usr/bin/env bash
+# Writes the shank IDL to idl/bondr_pinocchio.json.
+set -euo pipefail
+
+SHANK_VERSION=0.4.5
+cd "$(dirname "$0")/.."
+
+if [ "$(shank --version 2>/dev/null | awk '{print $2}')" != "$SHANK_VERSION" ]; then
+    cargo install shank-cli --version "$SHANK_VERSION" --locked
+fi
+
+shank idl --crate-root . --out-dir idl \
+    --program-id DJLjv6uBYyvx9tWCg18fSKpE3eNc3YaXSsAZ691eFMd4
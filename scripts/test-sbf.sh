@@ -0,0 +1,8 @@
+#!/usr/bin/env bash
+# Builds the program and the mock mpl-core, then runs the Mollusk suite against them.
+set -euo pipefail
+
+cd "$(dirname "$0")/.."
+
+scripts/build-sbf.sh
+SBF_OUT_DIR=target/deploy cargo test --features test-sbf "$@"
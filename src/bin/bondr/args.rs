@@ -0,0 +1,109 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    str::FromStr,
+};
+
+use crate::Result;
+
+/// Options that take no value.
+const FLAGS: &[&str] = &["help"];
+
+/// `<command> [positional].. [--option value].. [--flag]..`, consumed as commands
+/// read what they need; `finish` rejects anything left over.
+pub struct Args {
+    pub command: Option<String>,
+    positional: VecDeque<String>,
+    options: HashMap<String, String>,
+}
+
+impl Args {
+    pub fn parse(raw: impl Iterator<Item = String>) -> Result<Self> {
+        let mut raw = raw.peekable();
+        let mut positional = VecDeque::new();
+        let mut options = HashMap::new();
+
+        while let Some(arg) = raw.next() {
+            match arg.strip_prefix("--") {
+                Some(name) if FLAGS.contains(&name) => {
+                    options.insert(name.to_string(), String::new());
+                }
+                Some(name) => {
+                    let value = raw
+                        .next()
+                        .ok_or_else(|| format!("--{name} needs a value"))?;
+                    options.insert(name.to_string(), value);
+                }
+                None => positional.push_back(arg),
+            }
+        }
+
+        Ok(Args {
+            command: positional.pop_front(),
+            positional,
+            options,
+        })
+    }
+
+    pub fn peek(&self) -> Option<&str> {
+        self.positional.front().map(String::as_str)
+    }
+
+    /// The next positional argument, parsed.
+    pub fn next<T: FromStr>(&mut self, name: &str) -> Result<T>
+    where
+        T::Err: std::fmt::Display,
+    {
+        let value = self
+            .positional
+            .pop_front()
+            .ok_or_else(|| format!("missing <{name}>"))?;
+        value
+            .parse()
+            .map_err(|err| format!("invalid <{name}> `{value}`: {err}").into())
+    }
+
+    /// Every remaining positional argument, parsed.
+    pub fn rest<T: FromStr>(&mut self, name: &str) -> Result<Vec<T>>
+    where
+        T::Err: std::fmt::Display,
+    {
+        let mut values = Vec::new();
+        while !self.positional.is_empty() {
+            values.push(self.next(name)?);
+        }
+        Ok(values)
+    }
+
+    pub fn option<T: FromStr>(&mut self, name: &str) -> Result<Option<T>>
+    where
+        T::Err: std::fmt::Display,
+    {
+        self.options
+            .remove(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|err| format!("invalid --{name} `{value}`: {err}").into())
+            })
+            .transpose()
+    }
+
+    /// Drops an option the command has no use for, such as a global one.
+    pub fn ignore(&mut self, name: &str) {
+        self.options.remove(name);
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.options.contains_key(name)
+    }
+
+    pub fn finish(self) -> Result<()> {
+        if let Some(extra) = self.positional.front() {
+            return Err(format!("unexpected argument `{extra}`").into());
+        }
+        if let Some(extra) = self.options.keys().next() {
+            return Err(format!("unexpected option --{extra}").into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,685 @@
+use std::env;
+
+use bondr_pinocchio::{
+    client::{self, *},
+    constants::{MAX_MILESTONES, MAX_MULTISIG_MEMBERS},
+    decoder::{decode_account, decode_program_data, DecodedAccount, EscrowAccount},
+    instructions::{
+        AddMultisigMember, ChangeThreshold, ClaimMilestone, ClaimPayment, InitializeEscrow,
+        InitializeFreelancerBadge, InitializeMultisigClient, ReleaseMilestone,
+        RemoveMultisigMember, RotateMultisigMember,
+    },
+    states::MPL_CORE_ID,
+};
+use solana_instruction::{AccountMeta, Instruction};
+use solana_keypair::{read_keypair_file, Keypair};
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::{args::Args, rpc::RpcClient, Result};
+
+const DEFAULT_URL: &str = "http://127.0.0.1:8899";
+
+const SYSVAR_RENT_ID: Pubkey =
+    Pubkey::from_str_const("SysvarRent111111111111111111111111111111111");
+const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// The RPC endpoint and the keypair that signs and pays for every transaction.
+pub struct Context {
+    rpc: RpcClient,
+    payer: Keypair,
+}
+
+/// A command's transaction, built but not sent yet so that every argument can be
+/// checked before anything goes out.
+pub struct Outgoing {
+    instructions: Vec<Instruction>,
+    signers: Vec<Keypair>, // besides the payer
+}
+
+impl Outgoing {
+    fn new(instructions: Vec<Instruction>) -> Self {
+        Outgoing {
+            instructions,
+            signers: Vec::new(),
+        }
+    }
+
+    fn signed_by(mut self, signers: impl IntoIterator<Item = Keypair>) -> Self {
+        self.signers.extend(signers);
+        self
+    }
+}
+
+pub fn connect(args: &mut Args) -> Result<RpcClient> {
+    let url = args
+        .option::<String>("url")?
+        .unwrap_or_else(|| DEFAULT_URL.to_string());
+    RpcClient::new(&url)
+}
+
+impl Context {
+    pub fn new(rpc: RpcClient, args: &mut Args) -> Result<Self> {
+        let path = match args.option::<String>("keypair")? {
+            Some(path) => path,
+            None => format!("{}/.config/solana/id.json", env::var("HOME")?),
+        };
+
+        Ok(Context {
+            rpc,
+            payer: read_keypair(&path)?,
+        })
+    }
+
+    fn payer(&self) -> Pubkey {
+        self.payer.pubkey()
+    }
+
+    /// Signs with the payer and `outgoing`'s signers, sends, and prints the signature
+    /// along with every event the program logged.
+    pub fn send(&self, outgoing: &Outgoing) -> Result<()> {
+        let mut all: Vec<&Keypair> = vec![&self.payer];
+        all.extend(&outgoing.signers);
+
+        let transaction = Transaction::new_signed_with_payer(
+            &outgoing.instructions,
+            Some(&self.payer()),
+            &all,
+            self.rpc.latest_blockhash()?,
+        );
+        let signature = self.rpc.send_and_confirm(&transaction)?;
+        println!("{signature}");
+
+        // `Program data:` lines belong to whichever program is on top of the stack
+        let program_id = PROGRAM_ID.to_string();
+        let mut stack: Vec<String> = Vec::new();
+        for line in self.rpc.transaction_logs(&signature)? {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                ["Program", id, "invoke", _] => stack.push(id.to_string()),
+                ["Program", _, "success"] | ["Program", _, "failed:", ..] => {
+                    stack.pop();
+                }
+                ["Program", "data:", ..] if stack.last() == Some(&program_id) => {
+                    if let Ok(event) = decode_program_data(&line) {
+                        println!("{}", serde_json::to_string_pretty(&event)?);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn account(&self, address: &Pubkey) -> Result<DecodedAccount> {
+        fetch(&self.rpc, address)
+    }
+
+    fn escrow(&self, address: &Pubkey) -> Result<EscrowAccount> {
+        match self.account(address)? {
+            DecodedAccount::Escrow(escrow) => Ok(escrow),
+            _ => Err(format!("{address} is not an escrow").into()),
+        }
+    }
+
+    /// The token program that owns `mint`.
+    fn token_program(&self, mint: &Pubkey) -> Result<Pubkey> {
+        Ok(self
+            .rpc
+            .get_account(mint)?
+            .ok_or_else(|| format!("mint {mint} does not exist"))?
+            .owner)
+    }
+}
+
+fn fetch(rpc: &RpcClient, address: &Pubkey) -> Result<DecodedAccount> {
+    let account = rpc
+        .get_account(address)?
+        .ok_or_else(|| format!("{address} does not exist"))?;
+
+    if account.owner != PROGRAM_ID {
+        return Err(format!("{address} is owned by {}, not Bondr", account.owner).into());
+    }
+    Ok(decode_account(&account.data)?)
+}
+
+fn read_keypair(path: &str) -> Result<Keypair> {
+    read_keypair_file(path).map_err(|err| format!("reading keypair {path}: {err}").into())
+}
+
+fn key(bytes: [u8; 32]) -> Pubkey {
+    Pubkey::new_from_array(bytes)
+}
+
+/// The token accounts an escrow moves funds through. Native escrows use the system
+/// program id for all of them.
+struct TokenAccounts {
+    mint: Pubkey,
+    program: Pubkey,
+}
+
+impl TokenAccounts {
+    fn native() -> Self {
+        TokenAccounts {
+            mint: Pubkey::default(),
+            program: Pubkey::default(),
+        }
+    }
+
+    fn of(escrow: &EscrowAccount) -> Self {
+        TokenAccounts {
+            mint: key(escrow.mint),
+            program: key(escrow.token_program),
+        }
+    }
+
+    fn is_native(&self) -> bool {
+        self.mint == Pubkey::default()
+    }
+
+    fn ata(&self, owner: &Pubkey) -> Pubkey {
+        if self.is_native() {
+            return Pubkey::default();
+        }
+        Pubkey::find_program_address(
+            &[owner.as_ref(), self.program.as_ref(), self.mint.as_ref()],
+            &ASSOCIATED_TOKEN_PROGRAM_ID,
+        )
+        .0
+    }
+
+    /// Creates `owner`'s associated token account unless it already exists.
+    fn create_ata(&self, payer: &Pubkey, owner: &Pubkey) -> Option<Instruction> {
+        if self.is_native() {
+            return None;
+        }
+        Some(Instruction {
+            program_id: ASSOCIATED_TOKEN_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(*payer, true),
+                AccountMeta::new(self.ata(owner), false),
+                AccountMeta::new_readonly(*owner, false),
+                AccountMeta::new_readonly(self.mint, false),
+                AccountMeta::new_readonly(Pubkey::default(), false),
+                AccountMeta::new_readonly(self.program, false),
+            ],
+            data: vec![1], // CreateIdempotent
+        })
+    }
+}
+
+const TARGETS: &[&str] = &["config", "treasury", "escrow", "badge", "multisig", "stats"];
+
+/// Reads a PDA target: `config`, `treasury`, `escrow <client> <freelancer> <reference>`,
+/// `badge <freelancer>`, `multisig <client>` or `stats <user>`.
+pub fn resolve(args: &mut Args) -> Result<(Pubkey, u8)> {
+    let target: String = args.next("target")?;
+
+    Ok(match target.as_str() {
+        "config" => find_config_pda(),
+        "treasury" => find_treasury_pda(),
+        "escrow" => find_escrow_pda(
+            &args.next("client")?,
+            &args.next("freelancer")?,
+            args.next("reference")?,
+        ),
+        "badge" => find_badge_pda(&args.next("freelancer")?),
+        "multisig" => find_multisig_pda(&args.next("client")?),
+        "stats" => find_user_stats_pda(&args.next("user")?),
+        other => return Err(format!("unknown target `{other}`").into()),
+    })
+}
+
+/// Reads what `show` prints: a PDA target or an address.
+pub fn show_address(args: &mut Args) -> Result<Pubkey> {
+    if args.peek().is_some_and(|arg| TARGETS.contains(&arg)) {
+        Ok(resolve(args)?.0)
+    } else {
+        args.next("address")
+    }
+}
+
+pub fn show(rpc: &RpcClient, address: &Pubkey) -> Result<()> {
+    println!("{address}");
+    println!("{}", serde_json::to_string_pretty(&fetch(rpc, address)?)?);
+    Ok(())
+}
+
+pub fn create_escrow(ctx: &Context, args: &mut Args) -> Result<Outgoing> {
+    let sender = ctx.payer();
+    let receiver: Pubkey = args.next("freelancer")?;
+    let amount: u64 = args.next("amount")?;
+    let reference_seed: u64 = args.next("reference")?;
+
+    let tokens = match args.option::<Pubkey>("mint")? {
+        Some(mint) => TokenAccounts {
+            program: ctx.token_program(&mint)?,
+            mint,
+        },
+        None => TokenAccounts::native(),
+    };
+
+    let milestones: Vec<u64> = match args.option::<String>("milestones")? {
+        Some(list) => list
+            .split(',')
+            .map(|amount| amount.trim().parse())
+            .collect::<std::result::Result<_, _>>()?,
+        None => Vec::new(),
+    };
+    if milestones.len() > MAX_MILESTONES {
+        return Err(format!("at most {MAX_MILESTONES} milestones").into());
+    }
+    let mut milestone_amounts = [0u64; MAX_MILESTONES];
+    milestone_amounts[..milestones.len()].copy_from_slice(&milestones);
+
+    let multisig = args.option::<Pubkey>("multisig")?;
+    let (escrow, bump) = find_escrow_pda(&sender, &receiver, reference_seed);
+    let (sender_stats, stats_bump) = find_user_stats_pda(&sender);
+    let (multisig_approval, approval_bump) = multisig
+        .map(|multisig| find_multisig_approval_pda(&multisig, &escrow))
+        .unwrap_or_default();
+
+    let accounts = InitializeEscrowAccounts {
+        sender,
+        receiver,
+        escrow,
+        sender_stats,
+        client_multisig: multisig.unwrap_or_default(),
+        multisig_approval,
+        sender_token_account: tokens.ata(&sender),
+        escrow_token_account: tokens.ata(&escrow),
+        token_mint: tokens.mint,
+        sysvar_rent: SYSVAR_RENT_ID,
+        token_program: tokens.program,
+        system_program: Pubkey::default(),
+        config: find_config_pda().0,
+    };
+    let data = InitializeEscrow {
+        amount,
+        reference_seed,
+        deadline: args.option("deadline")?.unwrap_or(0),
+        auto_release_at: args.option("auto-release")?.unwrap_or(0),
+        milestone_amounts,
+        arbiter: args
+            .option::<Pubkey>("arbiter")?
+            .unwrap_or_default()
+            .to_bytes(),
+        milestone_count: milestones.len() as u8,
+        bump,
+        stats_bump,
+        approval_bump,
+        is_multisig: multisig.is_some(),
+        is_native: tokens.is_native(),
+        _padding: [0; 2],
+    };
+
+    let mut instruction = client::initialize_escrow(&accounts, &data);
+    instruction.accounts.extend(extra_accounts(args)?);
+
+    println!("escrow {escrow}");
+    let instructions: Vec<Instruction> = tokens
+        .create_ata(&sender, &escrow)
+        .into_iter()
+        .chain([instruction])
+        .collect();
+    Ok(Outgoing::new(instructions))
+}
+
+pub fn release(ctx: &Context, args: &mut Args) -> Result<Outgoing> {
+    let client = ctx.payer();
+    let (escrow, _) = find_escrow_pda(&client, &args.next("freelancer")?, args.next("reference")?);
+    let sender_stats = find_user_stats_pda(&client).0;
+    let config = find_config_pda().0;
+
+    let instruction = match args.option::<u8>("milestone")? {
+        Some(index) => client::release_milestone(
+            &ReleaseMilestoneAccounts {
+                client,
+                escrow,
+                sender_stats,
+                system_program: Pubkey::default(),
+                config,
+            },
+            &ReleaseMilestone { index },
+        ),
+        None => client::release_payment(&ReleasePaymentAccounts {
+            client,
+            escrow,
+            sender_stats,
+            system_program: Pubkey::default(),
+            config,
+        }),
+    };
+    Ok(Outgoing::new(vec![instruction]))
+}
+
+pub fn approve(ctx: &Context, args: &mut Args) -> Result<Outgoing> {
+    let (escrow, _) = find_escrow_pda(
+        &args.next("client")?,
+        &args.next("freelancer")?,
+        args.next("reference")?,
+    );
+    let state = ctx.escrow(&escrow)?;
+    if !state.has_multisig {
+        return Err(format!("{escrow} has no multisig").into());
+    }
+
+    let multisig = key(state.client_multisig);
+    let instruction = client::approve_multisig_release(&ApproveMultisigReleaseAccounts {
+        member: ctx.payer(),
+        multisig,
+        escrow,
+        multisig_approval: find_multisig_approval_pda(&multisig, &escrow).0,
+        sender_stats: find_user_stats_pda(&key(state.sender)).0,
+        system_program: Pubkey::default(),
+        config: find_config_pda().0,
+    });
+    Ok(Outgoing::new(vec![instruction]))
+}
+
+pub fn claim(ctx: &Context, args: &mut Args) -> Result<Outgoing> {
+    let freelancer = ctx.payer();
+    let client: Pubkey = args.next("client")?;
+    let (escrow, _) = find_escrow_pda(&client, &freelancer, args.next("reference")?);
+    let state = ctx.escrow(&escrow)?;
+
+    let tokens = TokenAccounts::of(&state);
+    let treasury = find_treasury_pda().0;
+    let config = find_config_pda().0;
+    let receiver_badge = find_badge_pda(&freelancer).0;
+
+    let mut instructions: Vec<Instruction> = [
+        tokens.create_ata(&freelancer, &freelancer),
+        tokens.create_ata(&freelancer, &treasury),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let (receiver_stats, receiver_stats_bump) = find_user_stats_pda(&freelancer);
+    let mut instruction = match args.option::<u8>("milestone")? {
+        Some(index) => client::claim_milestone(
+            &ClaimMilestoneAccounts {
+                client,
+                freelancer,
+                escrow,
+                receiver_stats,
+                escrow_token_account: tokens.ata(&escrow),
+                receiver_token_account: tokens.ata(&freelancer),
+                token_mint: tokens.mint,
+                token_program: tokens.program,
+                system_program: Pubkey::default(),
+                config,
+                treasury,
+                treasury_token_account: tokens.ata(&treasury),
+                receiver_badge,
+            },
+            &ClaimMilestone {
+                index,
+                receiver_stats_bump,
+            },
+        ),
+        None => client::claim_payment(
+            &ClaimPaymentAccounts {
+                client,
+                freelancer,
+                escrow,
+                receiver_stats,
+                multisig: key(state.client_multisig),
+                multisig_approval: find_multisig_approval_pda(&key(state.client_multisig), &escrow)
+                    .0,
+                escrow_token_account: tokens.ata(&escrow),
+                receiver_token_account: tokens.ata(&freelancer),
+                token_mint: tokens.mint,
+                token_program: tokens.program,
+                system_program: Pubkey::default(),
+                config,
+                treasury,
+                treasury_token_account: tokens.ata(&treasury),
+                receiver_badge,
+            },
+            &ClaimPayment {
+                receiver_stats_bump,
+            },
+        ),
+    };
+    instruction.accounts.extend(extra_accounts(args)?);
+    instructions.push(instruction);
+    Ok(Outgoing::new(instructions))
+}
+
+pub fn cancel(ctx: &Context, args: &mut Args) -> Result<Outgoing> {
+    let sender = ctx.payer();
+    let receiver: Pubkey = args.next("freelancer")?;
+    let (escrow, _) = find_escrow_pda(&sender, &receiver, args.next("reference")?);
+    let state = ctx.escrow(&escrow)?;
+    let tokens = TokenAccounts::of(&state);
+
+    // a freelancer co-signing lets the client cancel before the deadline
+    let receiver_keypair = args
+        .option::<String>("receiver-keypair")?
+        .map(|path| read_keypair(&path))
+        .transpose()?;
+    if let Some(keypair) = &receiver_keypair {
+        if keypair.pubkey() != receiver {
+            return Err(format!("--receiver-keypair is not {receiver}").into());
+        }
+    }
+
+    let mut instruction = client::cancel_escrow(
+        &CancelEscrowAccounts {
+            sender,
+            receiver,
+            escrow,
+            sender_stats: find_user_stats_pda(&sender).0,
+            multisig: key(state.client_multisig),
+            multisig_approval: find_multisig_approval_pda(&key(state.client_multisig), &escrow).0,
+            escrow_token_account: tokens.ata(&escrow),
+            sender_token_account: tokens.ata(&sender),
+            token_mint: tokens.mint,
+            token_program: tokens.program,
+            system_program: Pubkey::default(),
+        },
+        receiver_keypair.is_some(),
+    );
+    instruction.accounts.extend(extra_accounts(args)?);
+    Ok(Outgoing::new(vec![instruction]).signed_by(receiver_keypair))
+}
+
+pub fn init_badge(ctx: &Context) -> Result<Outgoing> {
+    let freelancer = ctx.payer();
+    let (badge, bump) = find_badge_pda(&freelancer);
+
+    println!("badge {badge}");
+    let instruction = client::initialize_freelancer_badge(
+        &InitializeFreelancerBadgeAccounts {
+            freelancer,
+            badge,
+            system_program: Pubkey::default(),
+            sysvar_rent: SYSVAR_RENT_ID,
+            config: find_config_pda().0,
+        },
+        &InitializeFreelancerBadge { bump },
+    );
+    Ok(Outgoing::new(vec![instruction]))
+}
+
+pub fn mint_nft(ctx: &Context) -> Result<Outgoing> {
+    let config = find_config_pda().0;
+    let DecodedAccount::Config(config_state) = ctx.account(&config)? else {
+        return Err(format!("{config} is not the config").into());
+    };
+    let asset = Keypair::new();
+
+    println!("asset {}", asset.pubkey());
+    let instruction = client::mint_reputation_nft(&MintReputationNftAccounts {
+        freelancer: ctx.payer(),
+        badge: find_badge_pda(&ctx.payer()).0,
+        asset: asset.pubkey(),
+        collection: key(config_state.settings.badge_collection),
+        config,
+        mpl_core_program: key(MPL_CORE_ID),
+        system_program: Pubkey::default(),
+    });
+    Ok(Outgoing::new(vec![instruction]).signed_by([asset]))
+}
+
+pub fn init_multisig(ctx: &Context, args: &mut Args) -> Result<Outgoing> {
+    let client = ctx.payer();
+    let threshold: u8 = args.next("threshold")?;
+    let members: Vec<Pubkey> = args.rest("member")?;
+    if members.len() > MAX_MULTISIG_MEMBERS {
+        return Err(format!("at most {MAX_MULTISIG_MEMBERS} members").into());
+    }
+
+    let mut member_keys = [[0u8; 32]; MAX_MULTISIG_MEMBERS];
+    for (slot, member) in member_keys.iter_mut().zip(&members) {
+        *slot = member.to_bytes();
+    }
+    let (multisig, bump) = find_multisig_pda(&client);
+
+    println!("multisig {multisig}");
+    let instruction = client::initialize_multisig_client(
+        &InitializeMultisigClientAccounts {
+            client,
+            multisig,
+            system_program: Pubkey::default(),
+            sysvar_rent: SYSVAR_RENT_ID,
+            config: find_config_pda().0,
+        },
+        &InitializeMultisigClient {
+            members: member_keys,
+            member_count: members.len() as u8,
+            threshold,
+            bump,
+        },
+    );
+    Ok(Outgoing::new(vec![instruction]))
+}
+
+/// The keypairs in `--approvers PATH,..`, which co-sign a multisig change with the payer.
+fn approvers(args: &mut Args) -> Result<Vec<Keypair>> {
+    match args.option::<String>("approvers")? {
+        Some(paths) => paths.split(',').map(read_keypair).collect(),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// A multisig change signed by the payer and every `--approvers` keypair.
+fn multisig_change(
+    ctx: &Context,
+    approvers: Vec<Keypair>,
+    build: impl FnOnce(&[Pubkey]) -> Instruction,
+) -> Result<Outgoing> {
+    let mut keys = vec![ctx.payer()];
+    keys.extend(approvers.iter().map(Keypair::pubkey));
+
+    let instruction = build(&keys);
+    Ok(Outgoing::new(vec![instruction]).signed_by(approvers))
+}
+
+/// The accounts in `--extra-accounts ADDRESS[:w],..`, appended for a Token-2022
+/// transfer hook; `:w` marks an account writable.
+fn extra_accounts(args: &mut Args) -> Result<Vec<AccountMeta>> {
+    let Some(list) = args.option::<String>("extra-accounts")? else {
+        return Ok(Vec::new());
+    };
+
+    list.split(',')
+        .map(|entry| {
+            let (address, writable) = match entry.strip_suffix(":w") {
+                Some(address) => (address, true),
+                None => (entry, false),
+            };
+            let address: Pubkey = address
+                .trim()
+                .parse()
+                .map_err(|err| format!("invalid --extra-accounts `{entry}`: {err}"))?;
+
+            Ok(if writable {
+                AccountMeta::new(address, false)
+            } else {
+                AccountMeta::new_readonly(address, false)
+            })
+        })
+        .collect()
+}
+
+pub fn multisig_add(ctx: &Context, args: &mut Args) -> Result<Outgoing> {
+    let multisig = find_multisig_pda(&args.next("client")?).0;
+    let member: Pubkey = args.next("member")?;
+    let approvers = approvers(args)?;
+
+    multisig_change(ctx, approvers, |keys| {
+        client::add_multisig_member(
+            &AddMultisigMemberAccounts {
+                multisig,
+                config: find_config_pda().0,
+            },
+            &AddMultisigMember {
+                member: member.to_bytes(),
+            },
+            keys,
+        )
+    })
+}
+
+pub fn multisig_remove(ctx: &Context, args: &mut Args) -> Result<Outgoing> {
+    let multisig = find_multisig_pda(&args.next("client")?).0;
+    let member: Pubkey = args.next("member")?;
+    let approvers = approvers(args)?;
+
+    multisig_change(ctx, approvers, |keys| {
+        client::remove_multisig_member(
+            &RemoveMultisigMemberAccounts {
+                multisig,
+                config: find_config_pda().0,
+            },
+            &RemoveMultisigMember {
+                member: member.to_bytes(),
+            },
+            keys,
+        )
+    })
+}
+
+pub fn multisig_rotate(ctx: &Context, args: &mut Args) -> Result<Outgoing> {
+    let multisig = find_multisig_pda(&args.next("client")?).0;
+    let old_member: Pubkey = args.next("old-member")?;
+    let new_member: Pubkey = args.next("new-member")?;
+    let approvers = approvers(args)?;
+
+    multisig_change(ctx, approvers, |keys| {
+        client::rotate_multisig_member(
+            &RotateMultisigMemberAccounts {
+                multisig,
+                config: find_config_pda().0,
+            },
+            &RotateMultisigMember {
+                old_member: old_member.to_bytes(),
+                new_member: new_member.to_bytes(),
+            },
+            keys,
+        )
+    })
+}
+
+pub fn multisig_threshold(ctx: &Context, args: &mut Args) -> Result<Outgoing> {
+    let multisig = find_multisig_pda(&args.next("client")?).0;
+    let threshold: u8 = args.next("threshold")?;
+    let approvers = approvers(args)?;
+
+    multisig_change(ctx, approvers, |keys| {
+        client::change_threshold(
+            &ChangeThresholdAccounts {
+                multisig,
+                config: find_config_pda().0,
+            },
+            &ChangeThreshold { threshold },
+            keys,
+        )
+    })
+}
@@ -0,0 +1,134 @@
+//! `bondr`: build, sign and send Bondr instructions and inspect program accounts.
+//! Built with the `cli` feature.
+
+mod args;
+mod commands;
+mod rpc;
+
+use std::{env, process::ExitCode};
+
+use args::Args;
+use commands::Context;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+const USAGE: &str = "\
+usage: bondr [--url URL] [--keypair PATH] <command> [args]
+
+Addresses and state:
+  pda <target>                         print a PDA and its bump
+  show <target|address>                fetch and print a program account
+    targets: config | treasury | escrow <client> <freelancer> <reference>
+             | badge <freelancer> | multisig <client> | stats <user>
+
+Transactions, signed by --keypair:
+  create-escrow <freelancer> <amount> <reference>
+      [--mint MINT] [--deadline UNIX] [--auto-release UNIX] [--arbiter ADDRESS]
+      [--milestones A,B,..] [--multisig ADDRESS] [--extra-accounts ADDRESS[:w],..]
+  release <freelancer> <reference> [--milestone INDEX]
+  approve <client> <freelancer> <reference>
+  claim <client> <reference> [--milestone INDEX] [--extra-accounts ADDRESS[:w],..]
+  cancel <freelancer> <reference> [--receiver-keypair PATH]
+      [--extra-accounts ADDRESS[:w],..]
+      --extra-accounts are appended for a Token-2022 transfer hook, :w if writable
+  init-badge
+  mint-nft
+  init-multisig <threshold> <member>...
+  multisig-add <client> <member> [--approvers PATH,..]
+  multisig-remove <client> <member> [--approvers PATH,..]
+  multisig-rotate <client> <old-member> <new-member> [--approvers PATH,..]
+  multisig-threshold <client> <threshold> [--approvers PATH,..]
+      changes the multisig created by <client>, approved by --keypair and
+      each --approvers keypair, all of them members
+
+Options:
+  --url URL        JSON-RPC endpoint, plain http only [default: http://127.0.0.1:8899]
+  --keypair PATH   signer and fee payer [default: ~/.config/solana/id.json]
+";
+
+const COMMANDS: &[&str] = &[
+    "show",
+    "create-escrow",
+    "release",
+    "approve",
+    "claim",
+    "cancel",
+    "init-badge",
+    "mint-nft",
+    "init-multisig",
+    "multisig-add",
+    "multisig-remove",
+    "multisig-rotate",
+    "multisig-threshold",
+];
+
+fn main() -> ExitCode {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("error: {err}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if args.command.is_none() || args.flag("help") {
+        print!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(mut args: Args) -> Result<()> {
+    let command = args.command.take().unwrap_or_default();
+
+    // resolving addresses needs neither the network nor a keypair
+    if command == "pda" {
+        args.ignore("url");
+        args.ignore("keypair");
+        let (address, bump) = commands::resolve(&mut args)?;
+        args.finish()?;
+        println!("{address} (bump {bump})");
+        return Ok(());
+    }
+
+    if !COMMANDS.contains(&command.as_str()) {
+        return Err(format!("unknown command `{command}`").into());
+    }
+
+    let rpc = commands::connect(&mut args)?;
+    if command == "show" {
+        args.ignore("keypair");
+        let address = commands::show_address(&mut args)?;
+        args.finish()?;
+        return commands::show(&rpc, &address);
+    }
+
+    let ctx = Context::new(rpc, &mut args)?;
+
+    let outgoing = match command.as_str() {
+        "create-escrow" => commands::create_escrow(&ctx, &mut args)?,
+        "release" => commands::release(&ctx, &mut args)?,
+        "approve" => commands::approve(&ctx, &mut args)?,
+        "claim" => commands::claim(&ctx, &mut args)?,
+        "cancel" => commands::cancel(&ctx, &mut args)?,
+        "init-badge" => commands::init_badge(&ctx)?,
+        "mint-nft" => commands::mint_nft(&ctx)?,
+        "init-multisig" => commands::init_multisig(&ctx, &mut args)?,
+        "multisig-add" => commands::multisig_add(&ctx, &mut args)?,
+        "multisig-remove" => commands::multisig_remove(&ctx, &mut args)?,
+        "multisig-rotate" => commands::multisig_rotate(&ctx, &mut args)?,
+        "multisig-threshold" => commands::multisig_threshold(&ctx, &mut args)?,
+        _ => unreachable!(),
+    };
+
+    // nothing is sent while an argument is left unread, e.g. a mistyped option
+    args.finish()?;
+    ctx.send(&outgoing)
+}
@@ -0,0 +1,196 @@
+//! Just enough JSON-RPC over plain HTTP to talk to a local test validator.
+
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+    thread,
+    time::Duration,
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+use solana_hash::Hash;
+use solana_pubkey::Pubkey;
+use solana_transaction::Transaction;
+
+use crate::Result;
+
+pub struct RpcClient {
+    host: String,
+    path: String,
+}
+
+pub struct FetchedAccount {
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> Result<Self> {
+        // there is no TLS here; a public cluster needs a local http proxy in front of it
+        if url.starts_with("https://") {
+            return Err(format!(
+                "{url}: https is not supported, only plain http:// such as a local test validator"
+            )
+            .into());
+        }
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or("only http:// urls are supported")?;
+        let (host, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let host = if host.contains(':') {
+            host.to_string()
+        } else {
+            format!("{host}:80")
+        };
+
+        Ok(RpcClient {
+            host,
+            path: path.to_string(),
+        })
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let body =
+            json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }).to_string();
+
+        let mut stream = TcpStream::connect(&self.host)?;
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.path,
+            self.host,
+            body.len(),
+            body
+        )?;
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response)?;
+        let response = String::from_utf8(response)?;
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .ok_or("malformed http response")?;
+
+        let body = if head
+            .to_ascii_lowercase()
+            .contains("transfer-encoding: chunked")
+        {
+            dechunk(body)?
+        } else {
+            body.to_string()
+        };
+
+        let mut reply: Value = serde_json::from_str(&body)?;
+        if let Some(error) = reply.get("error") {
+            let logs = error["data"]["logs"]
+                .as_array()
+                .map(|logs| {
+                    logs.iter()
+                        .filter_map(Value::as_str)
+                        .map(|line| format!("\n  {line}"))
+                        .collect::<String>()
+                })
+                .unwrap_or_default();
+            return Err(format!("{method}: {}{logs}", error["message"]).into());
+        }
+        Ok(reply["result"].take())
+    }
+
+    pub fn get_account(&self, address: &Pubkey) -> Result<Option<FetchedAccount>> {
+        let result = self.call(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+
+        let value = &result["value"];
+        if value.is_null() {
+            return Ok(None);
+        }
+
+        let owner = value["owner"].as_str().ok_or("account has no owner")?;
+        let data = value["data"][0].as_str().ok_or("account has no data")?;
+
+        Ok(Some(FetchedAccount {
+            owner: owner.parse()?,
+            data: STANDARD.decode(data)?,
+        }))
+    }
+
+    pub fn latest_blockhash(&self) -> Result<Hash> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+        let blockhash = result["value"]["blockhash"]
+            .as_str()
+            .ok_or("missing blockhash")?;
+        Ok(blockhash.parse()?)
+    }
+
+    /// Sends `transaction` and waits until it is confirmed, returning its signature.
+    pub fn send_and_confirm(&self, transaction: &Transaction) -> Result<String> {
+        let wire = STANDARD.encode(bincode::serialize(transaction)?);
+        let signature = self.call(
+            "sendTransaction",
+            json!([wire, { "encoding": "base64", "preflightCommitment": "confirmed" }]),
+        )?;
+        let signature = signature.as_str().ok_or("missing signature")?.to_string();
+
+        for _ in 0..60 {
+            let result = self.call("getSignatureStatuses", json!([[signature]]))?;
+            let status = &result["value"][0];
+
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    return Err(format!("{signature} failed: {}", status["err"]).into());
+                }
+                if matches!(
+                    status["confirmationStatus"].as_str(),
+                    Some("confirmed" | "finalized")
+                ) {
+                    return Ok(signature);
+                }
+            }
+            thread::sleep(Duration::from_millis(500));
+        }
+
+        Err(format!("{signature} was not confirmed in time").into())
+    }
+
+    /// Log lines of a confirmed transaction.
+    pub fn transaction_logs(&self, signature: &str) -> Result<Vec<String>> {
+        let result = self.call(
+            "getTransaction",
+            json!([signature, {
+                "encoding": "json",
+                "commitment": "confirmed",
+                "maxSupportedTransactionVersion": 0
+            }]),
+        )?;
+
+        Ok(result["meta"]["logMessages"]
+            .as_array()
+            .map(|logs| {
+                logs.iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+fn dechunk(mut body: &str) -> Result<String> {
+    let mut out = String::new();
+
+    loop {
+        let (size, rest) = body.split_once("\r\n").ok_or("malformed chunk")?;
+        let size = usize::from_str_radix(size.trim(), 16)?;
+        if size == 0 {
+            return Ok(out);
+        }
+        out.push_str(rest.get(..size).ok_or("truncated chunk")?);
+        body = rest[size..].trim_start_matches("\r\n");
+    }
+}
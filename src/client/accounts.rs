@@ -0,0 +1,260 @@
+//! Accounts for each instruction, in the order its handler destructures them.
+//!
+//! Native SOL escrows take the system program id (`Pubkey::default()`) for the mint,
+//! token program and token accounts. Instructions that move tokens accept extra
+//! accounts (e.g. a Token-2022 transfer hook's) appended after the ones listed here.
+//! Multisig membership changes are followed by the approving members, as signers.
+
+use solana_instruction::AccountMeta;
+use solana_pubkey::Pubkey;
+use std::{vec, vec::Vec};
+
+macro_rules! account_meta {
+    (readonly, $key:expr) => {
+        AccountMeta::new_readonly($key, false)
+    };
+    (writable, $key:expr) => {
+        AccountMeta::new($key, false)
+    };
+    (signer, $key:expr) => {
+        AccountMeta::new_readonly($key, true)
+    };
+    (writable_signer, $key:expr) => {
+        AccountMeta::new($key, true)
+    };
+}
+
+macro_rules! instruction_accounts {
+    ($($(#[$meta:meta])* $name:ident { $($field:ident: $kind:ident),* $(,)? })*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+            pub struct $name {
+                $(pub $field: Pubkey,)*
+            }
+
+            impl $name {
+                /// Field names, in account order.
+                pub const NAMES: &'static [&'static str] = &[$(stringify!($field)),*];
+
+                pub fn to_account_metas(&self) -> Vec<AccountMeta> {
+                    vec![$(account_meta!($kind, self.$field)),*]
+                }
+            }
+        )*
+    };
+}
+
+instruction_accounts! {
+    InitializeEscrowAccounts {
+        sender: writable_signer,
+        receiver: readonly,
+        escrow: writable,
+        sender_stats: writable,
+        client_multisig: writable, // any account when the escrow has no multisig
+        multisig_approval: writable, // likewise
+        sender_token_account: writable,
+        escrow_token_account: writable,
+        token_mint: readonly,
+        sysvar_rent: readonly, // unused; kept so the account list is unchanged
+        token_program: readonly,
+        system_program: readonly,
+        config: readonly,
+    }
+
+    InitializeFreelancerBadgeAccounts {
+        freelancer: writable_signer,
+        badge: writable,
+        system_program: readonly,
+        sysvar_rent: readonly,
+        config: readonly,
+    }
+
+    InitializeMultisigClientAccounts {
+        client: writable_signer,
+        multisig: writable,
+        system_program: readonly,
+        sysvar_rent: readonly,
+        config: readonly,
+    }
+
+    ReleasePaymentAccounts {
+        client: writable_signer, // pays to grow sender_stats from an older layout
+        escrow: writable,
+        sender_stats: writable,
+        system_program: readonly,
+        config: readonly,
+    }
+
+    ClaimPaymentAccounts {
+        client: writable, // receives the escrow's rent
+        freelancer: writable_signer,
+        escrow: writable,
+        receiver_stats: writable,
+        multisig: writable,          // any account without a multisig
+        multisig_approval: writable, // closed to the client
+        escrow_token_account: writable,
+        receiver_token_account: writable,
+        token_mint: readonly,
+        token_program: readonly,
+        system_program: readonly,
+        config: readonly,
+        treasury: writable,
+        treasury_token_account: writable,
+        receiver_badge: writable,
+    }
+
+    ApproveMultisigReleaseAccounts {
+        member: writable_signer, // pays to grow sender_stats from an older layout
+        multisig: readonly,
+        escrow: writable,
+        multisig_approval: writable,
+        sender_stats: writable, // of the escrow's creator
+        system_program: readonly,
+        config: readonly,
+    }
+
+    UpdateFreelancerBadgeAccounts {
+        admin: signer,
+        badge: writable,
+        config: readonly,
+    }
+
+    MintReputationNftAccounts {
+        freelancer: writable_signer,
+        badge: writable,
+        asset: writable_signer,
+        collection: writable,
+        config: readonly,
+        mpl_core_program: readonly,
+        system_program: readonly,
+    }
+
+    CancelEscrowAccounts {
+        sender: writable_signer,
+        receiver: readonly, // signs for a mutual cancel before the deadline
+        escrow: writable,
+        sender_stats: writable,
+        multisig: writable,          // any account without a multisig
+        multisig_approval: writable, // closed to the client; likewise
+        escrow_token_account: writable,
+        sender_token_account: writable,
+        token_mint: readonly,
+        token_program: readonly,
+        system_program: readonly,
+    }
+
+    ReleaseMilestoneAccounts {
+        client: writable_signer, // pays to grow sender_stats from an older layout
+        escrow: writable,
+        sender_stats: writable,
+        system_program: readonly,
+        config: readonly,
+    }
+
+    ClaimMilestoneAccounts {
+        client: writable,
+        freelancer: writable_signer,
+        escrow: writable,
+        receiver_stats: writable,
+        escrow_token_account: writable,
+        receiver_token_account: writable,
+        token_mint: readonly,
+        token_program: readonly,
+        system_program: readonly,
+        config: readonly,
+        treasury: writable,
+        treasury_token_account: writable,
+        receiver_badge: writable,
+    }
+
+    OpenDisputeAccounts {
+        party: signer,
+        escrow: writable,
+    }
+
+    ResolveDisputeAccounts {
+        arbiter: writable_signer, // pays to grow sender_stats from an older layout
+        sender: writable,
+        receiver: writable,
+        escrow: writable,
+        escrow_token_account: writable,
+        sender_token_account: writable,
+        receiver_token_account: writable,
+        token_mint: readonly,
+        receiver_badge: writable,
+        sender_stats: writable,
+        multisig: writable,          // any account without a multisig
+        multisig_approval: writable, // closed to the client; likewise
+        config: readonly,
+        treasury: writable,
+        treasury_token_account: writable,
+        token_program: readonly,
+        system_program: readonly,
+    }
+
+    InitializeConfigAccounts {
+        admin: writable_signer,
+        config: writable,
+        treasury: writable,
+        program: readonly,
+        program_data: readonly,
+        system_program: readonly,
+        sysvar_rent: readonly,
+    }
+
+    WithdrawTreasuryAccounts {
+        admin: signer,
+        config: readonly,
+        treasury: writable,
+        destination: writable,
+        treasury_token_account: writable,
+        destination_token_account: writable,
+        token_mint: readonly,
+        token_program: readonly,
+        system_program: readonly,
+    }
+
+    UpdateConfigAccounts {
+        admin: signer,
+        config: writable,
+    }
+
+    TransferAdminAccounts {
+        admin: signer,
+        new_admin: signer,
+        config: writable,
+    }
+
+    SetPauseAccounts {
+        authority: signer,
+        config: writable,
+    }
+
+    MigrateAccountAccounts {
+        payer: writable_signer,
+        account: writable,
+        system_program: readonly,
+        config: readonly, // the account itself when migrating the config
+    }
+
+    AddMultisigMemberAccounts {
+        multisig: writable,
+        config: readonly,
+    }
+
+    RemoveMultisigMemberAccounts {
+        multisig: writable,
+        config: readonly,
+    }
+
+    RotateMultisigMemberAccounts {
+        multisig: writable,
+        config: readonly,
+    }
+
+    ChangeThresholdAccounts {
+        multisig: writable,
+        config: readonly,
+    }
+}
@@ -0,0 +1,265 @@
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+use std::vec::Vec;
+
+use crate::{
+    client::{accounts::*, PROGRAM_ID},
+    instructions::{
+        AddMultisigMember, ApproveMultisigRelease, BondrInstruction, CancelEscrow, ChangeThreshold,
+        ClaimMilestone, ClaimPayment, InitializeConfig, InitializeEscrow,
+        InitializeFreelancerBadge, InitializeMultisigClient, MigrateAccount, MintReputationNft,
+        OpenDispute, ReleaseMilestone, ReleasePayment, RemoveMultisigMember, ResolveDispute,
+        RotateMultisigMember, SetPause, TransferAdmin, UpdateConfig, UpdateFreelancerBadge,
+        WithdrawTreasury,
+    },
+    states::{to_bytes, DataLen},
+};
+
+/// `[instruction] + payload`, the layout the entrypoint expects.
+fn build<T: DataLen>(
+    instruction: BondrInstruction,
+    accounts: Vec<AccountMeta>,
+    payload: &T,
+) -> Instruction {
+    let mut data = Vec::with_capacity(1 + T::LEN);
+    data.push(instruction as u8);
+    data.extend_from_slice(unsafe { to_bytes(payload) });
+
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts,
+        data,
+    }
+}
+
+/// `accounts` followed by the members approving a multisig change, as signers.
+fn with_approvers(mut accounts: Vec<AccountMeta>, approvers: &[Pubkey]) -> Vec<AccountMeta> {
+    accounts.extend(
+        approvers
+            .iter()
+            .map(|approver| AccountMeta::new_readonly(*approver, true)),
+    );
+    accounts
+}
+
+pub fn initialize_escrow(
+    accounts: &InitializeEscrowAccounts,
+    args: &InitializeEscrow,
+) -> Instruction {
+    build(
+        BondrInstruction::InitializeEscrow,
+        accounts.to_account_metas(),
+        args,
+    )
+}
+
+pub fn initialize_freelancer_badge(
+    accounts: &InitializeFreelancerBadgeAccounts,
+    args: &InitializeFreelancerBadge,
+) -> Instruction {
+    build(
+        BondrInstruction::InitializeFreelancerBadge,
+        accounts.to_account_metas(),
+        args,
+    )
+}
+
+pub fn initialize_multisig_client(
+    accounts: &InitializeMultisigClientAccounts,
+    args: &InitializeMultisigClient,
+) -> Instruction {
+    build(
+        BondrInstruction::InitializeMultisigClient,
+        accounts.to_account_metas(),
+        args,
+    )
+}
+
+pub fn release_payment(accounts: &ReleasePaymentAccounts) -> Instruction {
+    build(
+        BondrInstruction::ReleasePayment,
+        accounts.to_account_metas(),
+        &ReleasePayment {},
+    )
+}
+
+pub fn claim_payment(accounts: &ClaimPaymentAccounts, args: &ClaimPayment) -> Instruction {
+    build(
+        BondrInstruction::ClaimPayment,
+        accounts.to_account_metas(),
+        args,
+    )
+}
+
+pub fn approve_multisig_release(accounts: &ApproveMultisigReleaseAccounts) -> Instruction {
+    build(
+        BondrInstruction::ApproveMultisigRelease,
+        accounts.to_account_metas(),
+        &ApproveMultisigRelease {},
+    )
+}
+
+pub fn update_freelancer_badge(
+    accounts: &UpdateFreelancerBadgeAccounts,
+    args: &UpdateFreelancerBadge,
+) -> Instruction {
+    build(
+        BondrInstruction::UpdateFreelancerBadge,
+        accounts.to_account_metas(),
+        args,
+    )
+}
+
+pub fn mint_reputation_nft(accounts: &MintReputationNftAccounts) -> Instruction {
+    build(
+        BondrInstruction::MintReputationNft,
+        accounts.to_account_metas(),
+        &MintReputationNft {},
+    )
+}
+
+/// `receiver_signs` marks the freelancer as a signer, which lets the client cancel
+/// before the deadline.
+pub fn cancel_escrow(accounts: &CancelEscrowAccounts, receiver_signs: bool) -> Instruction {
+    let mut metas = accounts.to_account_metas();
+    metas[1].is_signer = receiver_signs;
+
+    build(BondrInstruction::CancelEscrow, metas, &CancelEscrow {})
+}
+
+pub fn release_milestone(
+    accounts: &ReleaseMilestoneAccounts,
+    args: &ReleaseMilestone,
+) -> Instruction {
+    build(
+        BondrInstruction::ReleaseMilestone,
+        accounts.to_account_metas(),
+        args,
+    )
+}
+
+pub fn claim_milestone(accounts: &ClaimMilestoneAccounts, args: &ClaimMilestone) -> Instruction {
+    build(
+        BondrInstruction::ClaimMilestone,
+        accounts.to_account_metas(),
+        args,
+    )
+}
+
+pub fn open_dispute(accounts: &OpenDisputeAccounts) -> Instruction {
+    build(
+        BondrInstruction::OpenDispute,
+        accounts.to_account_metas(),
+        &OpenDispute {},
+    )
+}
+
+pub fn resolve_dispute(accounts: &ResolveDisputeAccounts, args: &ResolveDispute) -> Instruction {
+    build(
+        BondrInstruction::ResolveDispute,
+        accounts.to_account_metas(),
+        args,
+    )
+}
+
+pub fn initialize_config(
+    accounts: &InitializeConfigAccounts,
+    args: &InitializeConfig,
+) -> Instruction {
+    build(
+        BondrInstruction::InitializeConfig,
+        accounts.to_account_metas(),
+        args,
+    )
+}
+
+pub fn withdraw_treasury(
+    accounts: &WithdrawTreasuryAccounts,
+    args: &WithdrawTreasury,
+) -> Instruction {
+    build(
+        BondrInstruction::WithdrawTreasury,
+        accounts.to_account_metas(),
+        args,
+    )
+}
+
+pub fn update_config(accounts: &UpdateConfigAccounts, args: &UpdateConfig) -> Instruction {
+    build(
+        BondrInstruction::UpdateConfig,
+        accounts.to_account_metas(),
+        args,
+    )
+}
+
+pub fn transfer_admin(accounts: &TransferAdminAccounts) -> Instruction {
+    build(
+        BondrInstruction::TransferAdmin,
+        accounts.to_account_metas(),
+        &TransferAdmin {},
+    )
+}
+
+pub fn set_pause(accounts: &SetPauseAccounts, args: &SetPause) -> Instruction {
+    build(
+        BondrInstruction::SetPause,
+        accounts.to_account_metas(),
+        args,
+    )
+}
+
+pub fn migrate_account(accounts: &MigrateAccountAccounts) -> Instruction {
+    build(
+        BondrInstruction::MigrateAccount,
+        accounts.to_account_metas(),
+        &MigrateAccount {},
+    )
+}
+
+pub fn add_multisig_member(
+    accounts: &AddMultisigMemberAccounts,
+    args: &AddMultisigMember,
+    approvers: &[Pubkey],
+) -> Instruction {
+    build(
+        BondrInstruction::AddMultisigMember,
+        with_approvers(accounts.to_account_metas(), approvers),
+        args,
+    )
+}
+
+pub fn remove_multisig_member(
+    accounts: &RemoveMultisigMemberAccounts,
+    args: &RemoveMultisigMember,
+    approvers: &[Pubkey],
+) -> Instruction {
+    build(
+        BondrInstruction::RemoveMultisigMember,
+        with_approvers(accounts.to_account_metas(), approvers),
+        args,
+    )
+}
+
+pub fn rotate_multisig_member(
+    accounts: &RotateMultisigMemberAccounts,
+    args: &RotateMultisigMember,
+    approvers: &[Pubkey],
+) -> Instruction {
+    build(
+        BondrInstruction::RotateMultisigMember,
+        with_approvers(accounts.to_account_metas(), approvers),
+        args,
+    )
+}
+
+pub fn change_threshold(
+    accounts: &ChangeThresholdAccounts,
+    args: &ChangeThreshold,
+    approvers: &[Pubkey],
+) -> Instruction {
+    build(
+        BondrInstruction::ChangeThreshold,
+        with_approvers(accounts.to_account_metas(), approvers),
+        args,
+    )
+}
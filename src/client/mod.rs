@@ -0,0 +1,14 @@
+//! Off-chain helpers for building Bondr transactions: PDA finders and one builder per
+//! `BondrInstruction`. Built with the `client` feature.
+
+pub mod accounts;
+pub mod instructions;
+pub mod pda;
+
+pub use accounts::*;
+pub use instructions::*;
+pub use pda::*;
+
+use solana_pubkey::Pubkey;
+
+pub const PROGRAM_ID: Pubkey = Pubkey::new_from_array(crate::ID);
@@ -0,0 +1,66 @@
+use solana_pubkey::Pubkey;
+
+use crate::{
+    client::PROGRAM_ID,
+    constants::BPF_LOADER_UPGRADEABLE_ID,
+    states::{ClientMultisig, Config, Escrow, FreelancerBadge, MultisigApproval, UserStats},
+};
+
+pub fn find_config_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[Config::SEED.as_bytes()], &PROGRAM_ID)
+}
+
+pub fn find_treasury_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[Config::TREASURY_SEED.as_bytes()], &PROGRAM_ID)
+}
+
+pub fn find_escrow_pda(sender: &Pubkey, receiver: &Pubkey, reference_seed: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            Escrow::SEED.as_bytes(),
+            sender.as_ref(),
+            receiver.as_ref(),
+            &reference_seed.to_le_bytes(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+pub fn find_badge_pda(freelancer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[FreelancerBadge::SEED.as_bytes(), freelancer.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+pub fn find_multisig_pda(client: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ClientMultisig::SEED.as_bytes(), client.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+/// The record collecting `multisig`'s approvals to release `escrow`.
+pub fn find_multisig_approval_pda(multisig: &Pubkey, escrow: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            MultisigApproval::SEED.as_bytes(),
+            multisig.as_ref(),
+            escrow.as_ref(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+pub fn find_user_stats_pda(user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[UserStats::SEED.as_bytes(), user.as_ref()], &PROGRAM_ID)
+}
+
+/// The upgradeable loader's `ProgramData` account, needed by `InitializeConfig`.
+pub fn find_program_data_address() -> Pubkey {
+    Pubkey::find_program_address(
+        &[PROGRAM_ID.as_ref()],
+        &Pubkey::new_from_array(BPF_LOADER_UPGRADEABLE_ID),
+    )
+    .0
+}
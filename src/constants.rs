@@ -1,8 +1,24 @@
+// suggested badge metadata and tiers for `InitializeConfig`; the program itself only
+// reads the tier URIs and thresholds the admin wrote into the config
 pub const VERIFIED_METADATA_URI: &str =
     "https://gateway.pinata.cloud/ipfs/bafkreiciowrcah3qtj62sejtvihwl3dwt7zqncwyuqofye2jailiki2wkm";
 pub const PROFESSIONAL_METADATA_URI: &str =
     "https://gateway.pinata.cloud/ipfs/bafkreibjqolqul673vv3if5hgavqzegtwuxp6bi5ulfkyhktuiapqx5aqm";
 pub const ELITE_METADATA_URI: &str =
     "https://gateway.pinata.cloud/ipfs/bafkreifcjchjznq2psd64rofzxf7kewxbinbgftp67mgqazyal7ojgezlm";
+pub const DEFAULT_TIER_THRESHOLDS: [u32; 3] = [3, 10, 25];
 
 pub const MAX_MULTISIG_MEMBERS: usize = 5;
+
+pub const MAX_ALLOWED_MINTS: usize = 8;
+
+pub const MAX_METADATA_URI_LEN: usize = 128;
+
+pub const MAX_MILESTONES: usize = 8;
+
+pub const BPS_DENOMINATOR: u16 = 10_000;
+
+pub const MAX_PROTOCOL_FEE_BPS: u16 = 1_000;
+
+pub const BPF_LOADER_UPGRADEABLE_ID: pinocchio::pubkey::Pubkey =
+    pinocchio_pubkey::pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
@@ -0,0 +1,405 @@
+use serde::{Deserialize, Serialize};
+use std::{string::String, vec::Vec};
+
+use pinocchio::pubkey::Pubkey;
+
+use crate::{
+    constants::{MAX_ALLOWED_MINTS, MAX_METADATA_URI_LEN, MAX_MILESTONES, MAX_MULTISIG_MEMBERS},
+    decoder::{
+        field::{active_keys, read_field},
+        DecodeError,
+    },
+    states::{
+        AccountDiscriminator, ClientMultisig, Config, ConfigSettings, DataLen, Escrow,
+        FreelancerBadge, Milestone, MultisigApproval, ProgramAccount, ReputationTier, UserStats,
+    },
+};
+
+/// Any account owned by the program, tagged by its type.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "account")]
+pub enum DecodedAccount {
+    Config(ConfigAccount),
+    Escrow(EscrowAccount),
+    ClientMultisig(ClientMultisigAccount),
+    FreelancerBadge(FreelancerBadgeAccount),
+    UserStats(UserStatsAccount),
+    MultisigApproval(MultisigApprovalAccount),
+}
+
+/// Decodes program account data, dispatching on its discriminator byte.
+pub fn decode_account(data: &[u8]) -> Result<DecodedAccount, DecodeError> {
+    let discriminator = *data.first().ok_or(DecodeError::InvalidLength {
+        expected: 1,
+        actual: 0,
+    })?;
+
+    match discriminator {
+        d if d == AccountDiscriminator::Config as u8 => {
+            ConfigAccount::decode(data).map(DecodedAccount::Config)
+        }
+        d if d == AccountDiscriminator::Escrow as u8 => {
+            EscrowAccount::decode(data).map(DecodedAccount::Escrow)
+        }
+        d if d == AccountDiscriminator::ClientMultisig as u8 => {
+            ClientMultisigAccount::decode(data).map(DecodedAccount::ClientMultisig)
+        }
+        d if d == AccountDiscriminator::FreelancerBadge as u8 => {
+            FreelancerBadgeAccount::decode(data).map(DecodedAccount::FreelancerBadge)
+        }
+        d if d == AccountDiscriminator::UserStats as u8 => {
+            UserStatsAccount::decode(data).map(DecodedAccount::UserStats)
+        }
+        d if d == AccountDiscriminator::MultisigApproval as u8 => {
+            MultisigApprovalAccount::decode(data).map(DecodedAccount::MultisigApproval)
+        }
+        d => Err(DecodeError::UnknownAccount(d)),
+    }
+}
+
+/// Checks the `[discriminator, version]` header and that `data` is exactly as long as
+/// that version's layout, returning the version. `older` lists the `(version, length)`
+/// of earlier layouts that can still be on chain; any other version has to be migrated
+/// before it can be decoded.
+fn check_header<T: ProgramAccount>(data: &[u8], older: &[(u8, usize)]) -> Result<u8, DecodeError> {
+    let (discriminator, version) = match data {
+        [d, v, ..] => (*d, *v),
+        _ => {
+            return Err(DecodeError::InvalidLength {
+                expected: T::LEN,
+                actual: data.len(),
+            })
+        }
+    };
+    if discriminator != T::DISCRIMINATOR as u8 {
+        return Err(DecodeError::UnknownAccount(discriminator));
+    }
+
+    let expected = if version == T::VERSION {
+        T::LEN
+    } else {
+        let (_, len) = older.iter().find(|(v, _)| *v == version).ok_or(
+            DecodeError::UnsupportedAccountVersion {
+                discriminator,
+                version,
+            },
+        )?;
+        *len
+    };
+    if data.len() != expected {
+        return Err(DecodeError::InvalidLength {
+            expected,
+            actual: data.len(),
+        });
+    }
+    Ok(version)
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    #[serde(with = "crate::decoder::base58")]
+    pub guardian: Pubkey,
+    pub fee_bps: u16,
+    pub tier_fee_discount_bps: [u16; 4],
+    pub tier_thresholds: [u32; 3],
+    #[serde(with = "crate::decoder::base58")]
+    pub badge_collection: Pubkey,
+    #[serde(with = "crate::decoder::base58")]
+    pub allowed_mints: Vec<Pubkey>,
+    pub max_multisig_members: u8,
+    pub tier_uris: Vec<String>,
+}
+
+impl Settings {
+    /// Reads a `ConfigSettings` that starts at `base` in `data`.
+    pub(crate) fn read(data: &[u8], base: usize) -> Result<Self, DecodeError> {
+        let uris: [[u8; MAX_METADATA_URI_LEN]; 3] =
+            read_field!(data, base, ConfigSettings, tier_uris);
+
+        Ok(Settings {
+            guardian: read_field!(data, base, ConfigSettings, guardian),
+            fee_bps: read_field!(data, base, ConfigSettings, fee_bps),
+            tier_fee_discount_bps: read_field!(data, base, ConfigSettings, tier_fee_discount_bps),
+            tier_thresholds: read_field!(data, base, ConfigSettings, tier_thresholds),
+            badge_collection: read_field!(data, base, ConfigSettings, badge_collection),
+            allowed_mints: active_keys::<MAX_ALLOWED_MINTS>(
+                read_field!(data, base, ConfigSettings, allowed_mints),
+                read_field!(data, base, ConfigSettings, allowed_mint_count),
+                "allowed_mint_count",
+            )?,
+            max_multisig_members: read_field!(data, base, ConfigSettings, max_multisig_members),
+            tier_uris: uris
+                .iter()
+                .map(|uri| {
+                    let len = uri.iter().position(|&b| b == 0).unwrap_or(uri.len());
+                    String::from_utf8_lossy(&uri[..len]).into_owned()
+                })
+                .collect(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfigAccount {
+    pub version: u8,
+    #[serde(with = "crate::decoder::base58")]
+    pub admin: Pubkey,
+    pub settings: Settings,
+    pub paused_instructions: u32,
+    pub paused: bool,
+    pub bump: u8,
+    pub treasury_bump: u8,
+    /// Indexed like `settings.allowed_mints`.
+    pub min_reputation_amounts: Vec<u64>,
+}
+
+/// Version 1 configs ended before the reputation minimums.
+const CONFIG_V1_LEN: usize = core::mem::offset_of!(Config, min_reputation_amounts);
+
+impl ConfigAccount {
+    pub fn decode(data: &[u8]) -> Result<Self, DecodeError> {
+        let version = check_header::<Config>(data, &[(1, CONFIG_V1_LEN)])?;
+        let settings = Settings::read(data, core::mem::offset_of!(Config, settings))?;
+        // version 1 had no minimums
+        let min_reputation_amounts: [u64; MAX_ALLOWED_MINTS] = match version {
+            1 => [0; MAX_ALLOWED_MINTS],
+            _ => read_field!(data, Config, min_reputation_amounts),
+        };
+        let min_reputation_amounts =
+            min_reputation_amounts[..settings.allowed_mints.len()].to_vec();
+
+        Ok(ConfigAccount {
+            version: read_field!(data, Config, version),
+            admin: read_field!(data, Config, admin),
+            settings,
+            paused_instructions: read_field!(data, Config, paused_instructions),
+            paused: read_field!(data, Config, paused),
+            bump: read_field!(data, Config, bump),
+            treasury_bump: read_field!(data, Config, treasury_bump),
+            min_reputation_amounts,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EscrowAccount {
+    pub version: u8,
+    #[serde(with = "crate::decoder::base58")]
+    pub sender: Pubkey,
+    #[serde(with = "crate::decoder::base58")]
+    pub receiver: Pubkey,
+    #[serde(with = "crate::decoder::base58")]
+    pub mint: Pubkey,
+    #[serde(with = "crate::decoder::base58")]
+    pub token_program: Pubkey,
+    pub amount: u64,
+    pub reference_seed: u64,
+    pub is_released: bool,
+    pub bump: u8,
+    #[serde(with = "crate::decoder::base58")]
+    pub client_multisig: Pubkey,
+    pub has_multisig: bool,
+    pub has_approval: bool,
+    pub approval_bump: u8,
+    pub created_at: i64,
+    pub deadline: i64,
+    pub auto_release_at: i64,
+    /// Only the active milestones; empty for a single-payment escrow.
+    pub milestones: Vec<Milestone>,
+    #[serde(with = "crate::decoder::base58")]
+    pub arbiter: Pubkey,
+    pub is_disputed: bool,
+    pub is_native: bool,
+}
+
+impl EscrowAccount {
+    pub fn decode(data: &[u8]) -> Result<Self, DecodeError> {
+        // version 1 has the same layout, with zeros where version 2 tracks the approvals
+        check_header::<Escrow>(data, &[(1, Escrow::LEN)])?;
+
+        let milestone_count: u8 = read_field!(data, Escrow, milestone_count);
+        if milestone_count as usize > MAX_MILESTONES {
+            return Err(DecodeError::InvalidField("milestone_count"));
+        }
+
+        let milestones = (0..milestone_count as usize)
+            .map(|i| {
+                let base = core::mem::offset_of!(Escrow, milestones) + i * Milestone::LEN;
+                Ok(Milestone {
+                    amount: read_field!(data, base, Milestone, amount),
+                    state: read_field!(data, base, Milestone, state),
+                    ..Milestone::EMPTY
+                })
+            })
+            .collect::<Result<Vec<_>, DecodeError>>()?;
+
+        Ok(EscrowAccount {
+            version: read_field!(data, Escrow, version),
+            sender: read_field!(data, Escrow, sender),
+            receiver: read_field!(data, Escrow, receiver),
+            mint: read_field!(data, Escrow, mint),
+            token_program: read_field!(data, Escrow, token_program),
+            amount: read_field!(data, Escrow, amount),
+            reference_seed: read_field!(data, Escrow, reference_seed),
+            is_released: read_field!(data, Escrow, is_released),
+            bump: read_field!(data, Escrow, bump),
+            client_multisig: read_field!(data, Escrow, client_multisig),
+            has_multisig: read_field!(data, Escrow, has_multisig),
+            has_approval: read_field!(data, Escrow, has_approval),
+            approval_bump: read_field!(data, Escrow, approval_bump),
+            created_at: read_field!(data, Escrow, created_at),
+            deadline: read_field!(data, Escrow, deadline),
+            auto_release_at: read_field!(data, Escrow, auto_release_at),
+            milestones,
+            arbiter: read_field!(data, Escrow, arbiter),
+            is_disputed: read_field!(data, Escrow, is_disputed),
+            is_native: read_field!(data, Escrow, is_native),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClientMultisigAccount {
+    pub version: u8,
+    #[serde(with = "crate::decoder::base58")]
+    pub client: Pubkey,
+    #[serde(with = "crate::decoder::base58")]
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+    pub open_approvals: u16,
+    pub bump: u8,
+}
+
+impl ClientMultisigAccount {
+    pub fn decode(data: &[u8]) -> Result<Self, DecodeError> {
+        // version 1 only differs in what is now `open_approvals` and padding
+        let version = check_header::<ClientMultisig>(data, &[(1, ClientMultisig::LEN)])?;
+
+        Ok(ClientMultisigAccount {
+            version: read_field!(data, ClientMultisig, version),
+            client: read_field!(data, ClientMultisig, client),
+            members: active_keys::<MAX_MULTISIG_MEMBERS>(
+                read_field!(data, ClientMultisig, members),
+                read_field!(data, ClientMultisig, member_count),
+                "member_count",
+            )?,
+            threshold: read_field!(data, ClientMultisig, threshold),
+            open_approvals: match version {
+                1 => 0,
+                _ => read_field!(data, ClientMultisig, open_approvals),
+            },
+            bump: read_field!(data, ClientMultisig, bump),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FreelancerBadgeAccount {
+    pub version: u8,
+    #[serde(with = "crate::decoder::base58")]
+    pub freelancer: Pubkey,
+    pub tier: ReputationTier,
+    pub completed_escrows: u32,
+    pub total_value_completed: u64,
+    pub bump: u8,
+    pub disputes_won: u32,
+    pub disputes_lost: u32,
+}
+
+impl FreelancerBadgeAccount {
+    pub fn decode(data: &[u8]) -> Result<Self, DecodeError> {
+        check_header::<FreelancerBadge>(data, &[])?;
+
+        Ok(FreelancerBadgeAccount {
+            version: read_field!(data, FreelancerBadge, version),
+            freelancer: read_field!(data, FreelancerBadge, freelancer),
+            tier: read_field!(data, FreelancerBadge, tier),
+            completed_escrows: read_field!(data, FreelancerBadge, completed_escrows),
+            total_value_completed: read_field!(data, FreelancerBadge, total_value_completed),
+            bump: read_field!(data, FreelancerBadge, bump),
+            disputes_won: read_field!(data, FreelancerBadge, disputes_won),
+            disputes_lost: read_field!(data, FreelancerBadge, disputes_lost),
+        })
+    }
+}
+
+/// Version 1 stats ended with the dispute counters.
+const USER_STATS_V1_LEN: usize = core::mem::offset_of!(UserStats, escrows_funded);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UserStatsAccount {
+    pub version: u8,
+    #[serde(with = "crate::decoder::base58")]
+    pub user: Pubkey,
+    pub completed_escrows: u32,
+    pub bump: u8,
+    pub disputes_won: u32,
+    pub disputes_lost: u32,
+    pub escrows_funded: u32,
+    pub escrows_released: u32,
+    pub escrows_refunded: u32,
+    pub total_funded: u64,
+    pub total_release_seconds: u64,
+    /// `None` until the user has released an escrow as a client.
+    pub average_release_seconds: Option<u64>,
+}
+
+impl UserStatsAccount {
+    pub fn decode(data: &[u8]) -> Result<Self, DecodeError> {
+        check_header::<UserStats>(data, &[(1, USER_STATS_V1_LEN)])?;
+        // the client record appended in version 2 reads as zero until the program grows
+        // the account on its next use
+        let mut data = data.to_vec();
+        data.resize(UserStats::LEN, 0);
+        let data = data.as_slice();
+
+        let escrows_released: u32 = read_field!(data, UserStats, escrows_released);
+        let total_release_seconds: u64 = read_field!(data, UserStats, total_release_seconds);
+
+        Ok(UserStatsAccount {
+            version: read_field!(data, UserStats, version),
+            user: read_field!(data, UserStats, user),
+            completed_escrows: read_field!(data, UserStats, completed_escrows),
+            bump: read_field!(data, UserStats, bump),
+            disputes_won: read_field!(data, UserStats, disputes_won),
+            disputes_lost: read_field!(data, UserStats, disputes_lost),
+            escrows_funded: read_field!(data, UserStats, escrows_funded),
+            escrows_released,
+            escrows_refunded: read_field!(data, UserStats, escrows_refunded),
+            total_funded: read_field!(data, UserStats, total_funded),
+            total_release_seconds,
+            average_release_seconds: total_release_seconds.checked_div(escrows_released as u64),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MultisigApprovalAccount {
+    pub version: u8,
+    #[serde(with = "crate::decoder::base58")]
+    pub multisig: Pubkey,
+    #[serde(with = "crate::decoder::base58")]
+    pub escrow: Pubkey,
+    /// Members who approved, including any since removed from the multisig.
+    #[serde(with = "crate::decoder::base58")]
+    pub approvers: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl MultisigApprovalAccount {
+    pub fn decode(data: &[u8]) -> Result<Self, DecodeError> {
+        check_header::<MultisigApproval>(data, &[])?;
+
+        Ok(MultisigApprovalAccount {
+            version: read_field!(data, MultisigApproval, version),
+            multisig: read_field!(data, MultisigApproval, multisig),
+            escrow: read_field!(data, MultisigApproval, escrow),
+            approvers: active_keys::<MAX_MULTISIG_MEMBERS>(
+                read_field!(data, MultisigApproval, approvers),
+                read_field!(data, MultisigApproval, approver_count),
+                "approver_count",
+            )?,
+            bump: read_field!(data, MultisigApproval, bump),
+        })
+    }
+}
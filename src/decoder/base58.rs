@@ -0,0 +1,72 @@
+//! Serde adapter that writes pubkeys as base58 strings, for use with
+//! `#[serde(with = "crate::decoder::base58")]`. It is implemented for every event field
+//! type so the `events!` macro can apply it to all fields; non-key values pass through.
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use std::{string::String, vec::Vec};
+
+use pinocchio::pubkey::Pubkey;
+
+pub trait Base58: Sized {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+macro_rules! passthrough {
+    ($($ty:ty),*) => {
+        $(
+            impl Base58 for $ty {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    Serialize::serialize(self, serializer)
+                }
+
+                fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    <$ty as Deserialize>::deserialize(deserializer)
+                }
+            }
+        )*
+    };
+}
+
+passthrough!(u8, u16, u32, u64, i64, bool);
+
+impl Base58 for Pubkey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&bs58::encode(self).into_string())
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        decode_pubkey(&encoded).map_err(D::Error::custom)
+    }
+}
+
+impl Base58 for Vec<Pubkey> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(|key| bs58::encode(key).into_string()))
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|encoded| decode_pubkey(encoded).map_err(D::Error::custom))
+            .collect()
+    }
+}
+
+pub fn serialize<T: Base58, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    value.serialize(serializer)
+}
+
+pub fn deserialize<'de, T: Base58, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    T::deserialize(deserializer)
+}
+
+pub fn decode_pubkey(encoded: &str) -> Result<Pubkey, &'static str> {
+    bs58::decode(encoded)
+        .into_vec()
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or("invalid base58 pubkey")
+}
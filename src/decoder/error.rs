@@ -0,0 +1,47 @@
+use core::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The data is not the size of the type it claims to be.
+    InvalidLength {
+        expected: usize,
+        actual: usize,
+    },
+    UnknownAccount(u8),
+    /// The account was written by another program version; migrate it first.
+    UnsupportedAccountVersion {
+        discriminator: u8,
+        version: u8,
+    },
+    UnknownInstruction(u8),
+    UnknownEvent(u8),
+    UnsupportedEventVersion(u8),
+    /// A bool or enum field holds a byte that is not one of its values.
+    InvalidField(&'static str),
+    InvalidBase64,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidLength { expected, actual } => {
+                write!(f, "expected {expected} bytes, got {actual}")
+            }
+            DecodeError::UnknownAccount(d) => write!(f, "unknown account discriminator {d}"),
+            DecodeError::UnsupportedAccountVersion {
+                discriminator,
+                version,
+            } => write!(
+                f,
+                "unsupported version {version} for account discriminator {discriminator}"
+            ),
+            DecodeError::UnknownInstruction(d) => write!(f, "unknown instruction {d}"),
+            DecodeError::UnknownEvent(k) => write!(f, "unknown event kind {k}"),
+            DecodeError::UnsupportedEventVersion(v) => write!(f, "unsupported event version {v}"),
+            DecodeError::InvalidField(name) => write!(f, "invalid value for `{name}`"),
+            DecodeError::InvalidBase64 => write!(f, "invalid base64 in program data"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
@@ -0,0 +1,90 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use std::vec::Vec;
+
+use crate::{
+    decoder::DecodeError,
+    events::{Event, EventKind, EVENT_VERSION},
+};
+
+// the events are re-exported from here so consumers only need the decoder
+pub use crate::events::{
+    AccountMigrated, BadgeCreated, BadgeUpdated, ConfigUpdated, DisputeOpened, DisputeResolved,
+    EscrowCancelled, EscrowCreated, MilestoneClaimed, MilestoneReleased, MultisigApproved,
+    MultisigCreated, MultisigUpdated, NftMinted, PauseUpdated, PaymentClaimed, PaymentReleased,
+    TreasuryWithdrawn,
+};
+
+/// An event that can be read back from the bytes it was logged with.
+pub trait DecodeEvent: Event {
+    fn decode(bytes: &[u8]) -> Result<Self, DecodeError>;
+}
+
+macro_rules! decoded_events {
+    ($($name:ident),* $(,)?) => {
+        /// Any event emitted by the program, tagged by its kind.
+        #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+        #[serde(tag = "event")]
+        pub enum DecodedEvent {
+            $($name($name),)*
+        }
+
+        fn decode_kind(kind: u8, bytes: &[u8]) -> Result<DecodedEvent, DecodeError> {
+            match kind {
+                $(k if k == EventKind::$name as u8 => $name::decode(bytes).map(DecodedEvent::$name),)*
+                k => Err(DecodeError::UnknownEvent(k)),
+            }
+        }
+    };
+}
+
+decoded_events!(
+    EscrowCreated,
+    PaymentReleased,
+    MultisigApproved,
+    PaymentClaimed,
+    EscrowCancelled,
+    MilestoneReleased,
+    MilestoneClaimed,
+    DisputeOpened,
+    DisputeResolved,
+    BadgeCreated,
+    BadgeUpdated,
+    NftMinted,
+    MultisigCreated,
+    ConfigUpdated,
+    PauseUpdated,
+    TreasuryWithdrawn,
+    AccountMigrated,
+    MultisigUpdated,
+);
+
+/// Decodes an event from its logged bytes: `[EVENT_VERSION, kind]` followed by the event.
+pub fn decode_event(data: &[u8]) -> Result<DecodedEvent, DecodeError> {
+    match data {
+        [version, ..] if *version != EVENT_VERSION => {
+            Err(DecodeError::UnsupportedEventVersion(*version))
+        }
+        [_, kind, bytes @ ..] => decode_kind(*kind, bytes),
+        _ => Err(DecodeError::InvalidLength {
+            expected: 2,
+            actual: data.len(),
+        }),
+    }
+}
+
+/// Decodes the base64 payload of a `Program data: ` log line, with or without that
+/// prefix. Only pass lines logged while this program was the one executing.
+pub fn decode_program_data(line: &str) -> Result<DecodedEvent, DecodeError> {
+    let encoded = line.strip_prefix("Program data: ").unwrap_or(line);
+
+    // `sol_log_data` encodes each slice separately, separated by spaces
+    let mut data = Vec::new();
+    for part in encoded.split_whitespace() {
+        STANDARD
+            .decode_vec(part, &mut data)
+            .map_err(|_| DecodeError::InvalidBase64)?;
+    }
+
+    decode_event(&data)
+}
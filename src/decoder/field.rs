@@ -0,0 +1,117 @@
+use pinocchio::pubkey::Pubkey;
+
+use crate::{
+    decoder::DecodeError,
+    states::{MilestoneState, ReputationTier},
+};
+
+/// A value stored little-endian in account, instruction or event data. `read` rejects
+/// bytes that are not a valid value instead of transmuting them.
+pub trait Field: Sized {
+    const SIZE: usize;
+
+    fn read(bytes: &[u8]) -> Option<Self>;
+}
+
+macro_rules! int_fields {
+    ($($ty:ty),*) => {
+        $(
+            impl Field for $ty {
+                const SIZE: usize = core::mem::size_of::<$ty>();
+
+                fn read(bytes: &[u8]) -> Option<Self> {
+                    Some(<$ty>::from_le_bytes(bytes.get(..Self::SIZE)?.try_into().ok()?))
+                }
+            }
+        )*
+    };
+}
+
+int_fields!(u8, u16, u32, u64, i64);
+
+impl Field for bool {
+    const SIZE: usize = 1;
+
+    fn read(bytes: &[u8]) -> Option<Self> {
+        match bytes.first()? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl Field for ReputationTier {
+    const SIZE: usize = 1;
+
+    fn read(bytes: &[u8]) -> Option<Self> {
+        match bytes.first()? {
+            0 => Some(ReputationTier::Unranked),
+            1 => Some(ReputationTier::Verified),
+            2 => Some(ReputationTier::Professional),
+            3 => Some(ReputationTier::Elite),
+            _ => None,
+        }
+    }
+}
+
+impl Field for MilestoneState {
+    const SIZE: usize = 1;
+
+    fn read(bytes: &[u8]) -> Option<Self> {
+        match bytes.first()? {
+            0 => Some(MilestoneState::Pending),
+            1 => Some(MilestoneState::Released),
+            2 => Some(MilestoneState::Claimed),
+            _ => None,
+        }
+    }
+}
+
+// also covers `Pubkey`
+impl<T: Field, const N: usize> Field for [T; N] {
+    const SIZE: usize = T::SIZE * N;
+
+    fn read(bytes: &[u8]) -> Option<Self> {
+        (0..N)
+            .map(|i| T::read(bytes.get(i * T::SIZE..)?))
+            .collect::<Option<std::vec::Vec<T>>>()?
+            .try_into()
+            .ok()
+    }
+}
+
+/// Reads the field of type `T` starting at `offset`; `name` is reported if it is invalid.
+pub fn read_at<T: Field>(data: &[u8], offset: usize, name: &'static str) -> Result<T, DecodeError> {
+    data.get(offset..)
+        .and_then(T::read)
+        .ok_or(DecodeError::InvalidField(name))
+}
+
+/// Reads a field of an on-chain `#[repr(C)]` struct at its compiler-assigned offset,
+/// optionally relative to where that struct starts in `data`.
+macro_rules! read_field {
+    ($data:expr, $ty:ty, $($field:ident).+) => {
+        read_field!($data, 0, $ty, $($field).+)
+    };
+    ($data:expr, $base:expr, $ty:ty, $($field:ident).+) => {
+        $crate::decoder::field::read_at(
+            $data,
+            $base + core::mem::offset_of!($ty, $($field).+),
+            stringify!($($field).+),
+        )?
+    };
+}
+
+pub(crate) use read_field;
+
+/// Keys in `keys[..count]`, the active entries of a fixed-size key array.
+pub(crate) fn active_keys<const N: usize>(
+    keys: [Pubkey; N],
+    count: u8,
+    name: &'static str,
+) -> Result<std::vec::Vec<Pubkey>, DecodeError> {
+    keys.get(..count as usize)
+        .map(|active| active.to_vec())
+        .ok_or(DecodeError::InvalidField(name))
+}
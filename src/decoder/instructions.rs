@@ -0,0 +1,289 @@
+use serde::{Deserialize, Serialize};
+use std::vec::Vec;
+
+use pinocchio::pubkey::Pubkey;
+
+use crate::{
+    constants::{MAX_ALLOWED_MINTS, MAX_MILESTONES, MAX_MULTISIG_MEMBERS},
+    decoder::{
+        field::{active_keys, read_field},
+        DecodeError, Settings,
+    },
+    instructions::*,
+    states::DataLen,
+};
+
+/// Instruction data for every `BondrInstruction`, tagged by its name.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "instruction")]
+pub enum DecodedInstruction {
+    InitializeEscrow {
+        amount: u64,
+        reference_seed: u64,
+        deadline: i64,
+        auto_release_at: i64,
+        /// Empty for a single-payment escrow.
+        milestone_amounts: Vec<u64>,
+        #[serde(with = "crate::decoder::base58")]
+        arbiter: Pubkey,
+        bump: u8,
+        stats_bump: u8,
+        approval_bump: u8,
+        is_multisig: bool,
+        is_native: bool,
+    },
+    InitializeFreelancerBadge {
+        bump: u8,
+    },
+    InitializeMultisigClient {
+        #[serde(with = "crate::decoder::base58")]
+        members: Vec<Pubkey>,
+        threshold: u8,
+        bump: u8,
+    },
+    ReleasePayment,
+    ClaimPayment {
+        receiver_stats_bump: u8,
+    },
+    ApproveMultisigRelease,
+    UpdateFreelancerBadge {
+        value: u64,
+    },
+    MintReputationNft,
+    CancelEscrow,
+    ReleaseMilestone {
+        index: u8,
+    },
+    ClaimMilestone {
+        index: u8,
+        receiver_stats_bump: u8,
+    },
+    OpenDispute,
+    ResolveDispute {
+        receiver_bps: u16,
+    },
+    InitializeConfig {
+        settings: Settings,
+        bump: u8,
+        treasury_bump: u8,
+        min_reputation_amounts: [u64; MAX_ALLOWED_MINTS],
+    },
+    WithdrawTreasury {
+        amount: u64,
+    },
+    UpdateConfig {
+        settings: Settings,
+        min_reputation_amounts: [u64; MAX_ALLOWED_MINTS],
+    },
+    TransferAdmin,
+    SetPause {
+        paused_instructions: u32,
+        paused: bool,
+    },
+    MigrateAccount,
+    AddMultisigMember {
+        #[serde(with = "crate::decoder::base58")]
+        member: Pubkey,
+    },
+    RemoveMultisigMember {
+        #[serde(with = "crate::decoder::base58")]
+        member: Pubkey,
+    },
+    RotateMultisigMember {
+        #[serde(with = "crate::decoder::base58")]
+        old_member: Pubkey,
+        #[serde(with = "crate::decoder::base58")]
+        new_member: Pubkey,
+    },
+    ChangeThreshold {
+        threshold: u8,
+    },
+}
+
+/// Decodes instruction data: the `BondrInstruction` byte followed by its payload.
+pub fn decode_instruction(data: &[u8]) -> Result<DecodedInstruction, DecodeError> {
+    let (tag, payload) = data.split_first().ok_or(DecodeError::InvalidLength {
+        expected: 1,
+        actual: 0,
+    })?;
+    let instruction =
+        BondrInstruction::try_from(tag).map_err(|_| DecodeError::UnknownInstruction(*tag))?;
+
+    let decoded = match instruction {
+        BondrInstruction::InitializeEscrow => {
+            check_len::<InitializeEscrow>(payload)?;
+
+            let milestone_count: u8 = read_field!(payload, InitializeEscrow, milestone_count);
+            let amounts: [u64; MAX_MILESTONES] =
+                read_field!(payload, InitializeEscrow, milestone_amounts);
+
+            DecodedInstruction::InitializeEscrow {
+                amount: read_field!(payload, InitializeEscrow, amount),
+                reference_seed: read_field!(payload, InitializeEscrow, reference_seed),
+                deadline: read_field!(payload, InitializeEscrow, deadline),
+                auto_release_at: read_field!(payload, InitializeEscrow, auto_release_at),
+                milestone_amounts: amounts
+                    .get(..milestone_count as usize)
+                    .ok_or(DecodeError::InvalidField("milestone_count"))?
+                    .to_vec(),
+                arbiter: read_field!(payload, InitializeEscrow, arbiter),
+                bump: read_field!(payload, InitializeEscrow, bump),
+                stats_bump: read_field!(payload, InitializeEscrow, stats_bump),
+                approval_bump: read_field!(payload, InitializeEscrow, approval_bump),
+                is_multisig: read_field!(payload, InitializeEscrow, is_multisig),
+                is_native: read_field!(payload, InitializeEscrow, is_native),
+            }
+        }
+        BondrInstruction::InitializeFreelancerBadge => {
+            check_len::<InitializeFreelancerBadge>(payload)?;
+            DecodedInstruction::InitializeFreelancerBadge {
+                bump: read_field!(payload, InitializeFreelancerBadge, bump),
+            }
+        }
+        BondrInstruction::InitializeMultisigClient => {
+            check_len::<InitializeMultisigClient>(payload)?;
+            DecodedInstruction::InitializeMultisigClient {
+                members: active_keys::<MAX_MULTISIG_MEMBERS>(
+                    read_field!(payload, InitializeMultisigClient, members),
+                    read_field!(payload, InitializeMultisigClient, member_count),
+                    "member_count",
+                )?,
+                threshold: read_field!(payload, InitializeMultisigClient, threshold),
+                bump: read_field!(payload, InitializeMultisigClient, bump),
+            }
+        }
+        BondrInstruction::ReleasePayment => {
+            check_len::<ReleasePayment>(payload)?;
+            DecodedInstruction::ReleasePayment
+        }
+        BondrInstruction::ClaimPayment => {
+            check_len::<ClaimPayment>(payload)?;
+            DecodedInstruction::ClaimPayment {
+                receiver_stats_bump: read_field!(payload, ClaimPayment, receiver_stats_bump),
+            }
+        }
+        BondrInstruction::ApproveMultisigRelease => {
+            check_len::<ApproveMultisigRelease>(payload)?;
+            DecodedInstruction::ApproveMultisigRelease
+        }
+        BondrInstruction::UpdateFreelancerBadge => {
+            check_len::<UpdateFreelancerBadge>(payload)?;
+            DecodedInstruction::UpdateFreelancerBadge {
+                value: read_field!(payload, UpdateFreelancerBadge, value),
+            }
+        }
+        BondrInstruction::MintReputationNft => {
+            check_len::<MintReputationNft>(payload)?;
+            DecodedInstruction::MintReputationNft
+        }
+        BondrInstruction::CancelEscrow => {
+            check_len::<CancelEscrow>(payload)?;
+            DecodedInstruction::CancelEscrow
+        }
+        BondrInstruction::ReleaseMilestone => {
+            check_len::<ReleaseMilestone>(payload)?;
+            DecodedInstruction::ReleaseMilestone {
+                index: read_field!(payload, ReleaseMilestone, index),
+            }
+        }
+        BondrInstruction::ClaimMilestone => {
+            check_len::<ClaimMilestone>(payload)?;
+            DecodedInstruction::ClaimMilestone {
+                index: read_field!(payload, ClaimMilestone, index),
+                receiver_stats_bump: read_field!(payload, ClaimMilestone, receiver_stats_bump),
+            }
+        }
+        BondrInstruction::OpenDispute => {
+            check_len::<OpenDispute>(payload)?;
+            DecodedInstruction::OpenDispute
+        }
+        BondrInstruction::ResolveDispute => {
+            check_len::<ResolveDispute>(payload)?;
+            DecodedInstruction::ResolveDispute {
+                receiver_bps: read_field!(payload, ResolveDispute, receiver_bps),
+            }
+        }
+        BondrInstruction::InitializeConfig => {
+            check_len::<InitializeConfig>(payload)?;
+            DecodedInstruction::InitializeConfig {
+                settings: Settings::read(
+                    payload,
+                    core::mem::offset_of!(InitializeConfig, settings),
+                )?,
+                bump: read_field!(payload, InitializeConfig, bump),
+                treasury_bump: read_field!(payload, InitializeConfig, treasury_bump),
+                min_reputation_amounts: read_field!(
+                    payload,
+                    InitializeConfig,
+                    min_reputation_amounts
+                ),
+            }
+        }
+        BondrInstruction::WithdrawTreasury => {
+            check_len::<WithdrawTreasury>(payload)?;
+            DecodedInstruction::WithdrawTreasury {
+                amount: read_field!(payload, WithdrawTreasury, amount),
+            }
+        }
+        BondrInstruction::UpdateConfig => {
+            check_len::<UpdateConfig>(payload)?;
+            DecodedInstruction::UpdateConfig {
+                settings: Settings::read(payload, core::mem::offset_of!(UpdateConfig, settings))?,
+                min_reputation_amounts: read_field!(payload, UpdateConfig, min_reputation_amounts),
+            }
+        }
+        BondrInstruction::TransferAdmin => {
+            check_len::<TransferAdmin>(payload)?;
+            DecodedInstruction::TransferAdmin
+        }
+        BondrInstruction::SetPause => {
+            check_len::<SetPause>(payload)?;
+            DecodedInstruction::SetPause {
+                paused_instructions: read_field!(payload, SetPause, paused_instructions),
+                paused: read_field!(payload, SetPause, paused),
+            }
+        }
+        BondrInstruction::MigrateAccount => {
+            check_len::<MigrateAccount>(payload)?;
+            DecodedInstruction::MigrateAccount
+        }
+        BondrInstruction::AddMultisigMember => {
+            check_len::<AddMultisigMember>(payload)?;
+            DecodedInstruction::AddMultisigMember {
+                member: read_field!(payload, AddMultisigMember, member),
+            }
+        }
+        BondrInstruction::RemoveMultisigMember => {
+            check_len::<RemoveMultisigMember>(payload)?;
+            DecodedInstruction::RemoveMultisigMember {
+                member: read_field!(payload, RemoveMultisigMember, member),
+            }
+        }
+        BondrInstruction::RotateMultisigMember => {
+            check_len::<RotateMultisigMember>(payload)?;
+            DecodedInstruction::RotateMultisigMember {
+                old_member: read_field!(payload, RotateMultisigMember, old_member),
+                new_member: read_field!(payload, RotateMultisigMember, new_member),
+            }
+        }
+        BondrInstruction::ChangeThreshold => {
+            check_len::<ChangeThreshold>(payload)?;
+            DecodedInstruction::ChangeThreshold {
+                threshold: read_field!(payload, ChangeThreshold, threshold),
+            }
+        }
+    };
+
+    Ok(decoded)
+}
+
+/// The program rejects payloads that are not exactly the size of their struct.
+fn check_len<T: DataLen>(payload: &[u8]) -> Result<(), DecodeError> {
+    if payload.len() != T::LEN {
+        return Err(DecodeError::InvalidLength {
+            expected: T::LEN,
+            actual: payload.len(),
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,18 @@
+//! Off-chain decoding of Bondr accounts, instruction data and events into owned,
+//! serde-serializable types. Built with the `std` feature.
+//!
+//! Fields are read at the offsets the compiler picked for the on-chain `#[repr(C)]`
+//! structs (`core::mem::offset_of!`), so padding changes are picked up on rebuild
+//! instead of silently shifting every field after them.
+
+pub mod accounts;
+pub mod base58;
+pub mod error;
+pub mod events;
+pub mod field;
+pub mod instructions;
+
+pub use accounts::*;
+pub use error::*;
+pub use events::*;
+pub use instructions::*;
@@ -1,6 +1,10 @@
 #![allow(unexpected_cfgs)]
 
-use crate::instructions::{self, BondrInstruction};
+use crate::{
+    errors::BondrError,
+    instructions::{self, BondrInstruction},
+    states::{account_version, load_account, Config, ProgramAccount},
+};
 use pinocchio::{
     account_info::AccountInfo, default_panic_handler, no_allocator, program_entrypoint,
     program_error::ProgramError, pubkey::Pubkey, ProgramResult,
@@ -23,7 +27,13 @@ fn process_instruction(
         .split_first()
         .ok_or(ProgramError::InvalidInstructionData)?;
 
-    match BondrInstruction::try_from(ix_disc)? {
+    let instruction = BondrInstruction::try_from(ix_disc)?;
+
+    if let Some(config_index) = instruction.config_index() {
+        ensure_not_paused(instruction, accounts, config_index)?;
+    }
+
+    match instruction {
         BondrInstruction::InitializeEscrow => instructions::init_escrow(accounts, instruction_data),
         BondrInstruction::InitializeFreelancerBadge => {
             instructions::init_freelancer_badge(accounts, instruction_data)
@@ -44,5 +54,66 @@ fn process_instruction(
         BondrInstruction::MintReputationNft => {
             instructions::mint_rep_nft(accounts, instruction_data)
         }
+        BondrInstruction::CancelEscrow => instructions::cancel_escrow(accounts, instruction_data),
+        BondrInstruction::ReleaseMilestone => {
+            instructions::release_milestone(accounts, instruction_data)
+        }
+        BondrInstruction::ClaimMilestone => {
+            instructions::claim_milestone(accounts, instruction_data)
+        }
+        BondrInstruction::OpenDispute => instructions::open_dispute(accounts, instruction_data),
+        BondrInstruction::ResolveDispute => {
+            instructions::resolve_dispute(accounts, instruction_data)
+        }
+        BondrInstruction::InitializeConfig => instructions::init_config(accounts, instruction_data),
+        BondrInstruction::WithdrawTreasury => {
+            instructions::withdraw_treasury(accounts, instruction_data)
+        }
+        BondrInstruction::UpdateConfig => instructions::update_config(accounts, instruction_data),
+        BondrInstruction::TransferAdmin => instructions::transfer_admin(accounts, instruction_data),
+        BondrInstruction::SetPause => instructions::set_pause(accounts, instruction_data),
+        BondrInstruction::MigrateAccount => {
+            instructions::migrate_account(accounts, instruction_data)
+        }
+        BondrInstruction::AddMultisigMember => {
+            instructions::add_multisig_member(accounts, instruction_data)
+        }
+        BondrInstruction::RemoveMultisigMember => {
+            instructions::remove_multisig_member(accounts, instruction_data)
+        }
+        BondrInstruction::RotateMultisigMember => {
+            instructions::rotate_multisig_member(accounts, instruction_data)
+        }
+        BondrInstruction::ChangeThreshold => {
+            instructions::change_threshold(accounts, instruction_data)
+        }
+    }
+}
+
+/// Every gated instruction takes the config at `config_index`; check it is the config
+/// and that the instruction isn't paused.
+fn ensure_not_paused(
+    instruction: BondrInstruction,
+    accounts: &[AccountInfo],
+    config_index: usize,
+) -> ProgramResult {
+    let config_acc = accounts
+        .get(config_index)
+        .filter(|acc| acc.is_owned_by(&crate::ID))
+        .ok_or(BondrError::MissingConfig)?;
+
+    // an outdated config can't be read until it is migrated, pause or not
+    if instruction == BondrInstruction::MigrateAccount
+        && accounts[1].key() == config_acc.key()
+        && account_version::<Config>(config_acc)? < Config::VERSION
+    {
+        return Ok(());
+    }
+
+    // checks the address against the config's PDA, which `init_config` only
+    // creates at the canonical bump, so there is just the one
+    if load_account::<Config>(config_acc)?.is_paused(instruction as u8) {
+        return Err(BondrError::ProgramPaused.into());
     }
+    Ok(())
 }
@@ -26,7 +26,27 @@ pub enum BondrError {
     AlreadyApproved,
     MultisigPendingEscrowMismatch,
     MultisigThresholdNotMet,
-    UnauthorizedReceiver
+    UnauthorizedReceiver,
+    InvalidDeadline,
+    EscrowNotExpired,
+    InvalidMilestone,
+    NotMilestoneEscrow,
+    MilestoneEscrowNotSupported,
+    InvalidArbiter,
+    NoArbiter,
+    EscrowDisputed,
+    NotDisputed,
+    UnauthorizedArbiter,
+    InvalidBasisPoints,
+    TransferFeeMismatch,
+    UnauthorizedAdmin,
+    InvalidFee,
+    InvalidTreasury,
+    InvalidConfig,
+    MintNotAllowed,
+    ProgramPaused,
+    MissingConfig,
+    AccountNeedsMigration,
 }
 
 impl From<BondrError> for ProgramError {
@@ -0,0 +1,225 @@
+use pinocchio::{log::sol_log_data, pubkey::Pubkey};
+
+/// Bumped whenever an event layout changes.
+pub const EVENT_VERSION: u8 = 1;
+
+/// Second byte of every event, after [`EVENT_VERSION`].
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EventKind {
+    EscrowCreated = 0,
+    PaymentReleased = 1,
+    MultisigApproved = 2,
+    PaymentClaimed = 3,
+    EscrowCancelled = 4,
+    MilestoneReleased = 5,
+    MilestoneClaimed = 6,
+    DisputeOpened = 7,
+    DisputeResolved = 8,
+    BadgeCreated = 9,
+    BadgeUpdated = 10,
+    NftMinted = 11,
+    MultisigCreated = 12,
+    ConfigUpdated = 13,
+    PauseUpdated = 14,
+    TreasuryWithdrawn = 15,
+    AccountMigrated = 16,
+    MultisigUpdated = 17,
+}
+
+/// A packed, fixed-size event logged as `[EVENT_VERSION, kind]` followed by its bytes
+/// through `sol_log_data`, so indexers can replay state transitions from logs alone.
+pub trait Event: Sized {
+    const KIND: EventKind;
+
+    fn emit(&self) {
+        // events are `repr(C, packed)`: no padding, every byte is initialized
+        let bytes = unsafe {
+            core::slice::from_raw_parts(
+                self as *const Self as *const u8,
+                core::mem::size_of::<Self>(),
+            )
+        };
+        sol_log_data(&[&[EVENT_VERSION, Self::KIND as u8], bytes]);
+    }
+}
+
+macro_rules! events {
+    ($($(#[$meta:meta])* $name:ident { $($field:ident: $ty:ty),* $(,)? })*) => {
+        $(
+            $(#[$meta])*
+            #[repr(C, packed)]
+            #[derive(Clone, Copy, Debug, PartialEq)]
+            #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+            pub struct $name {
+                $(
+                    #[cfg_attr(feature = "std", serde(with = "crate::decoder::base58"))]
+                    pub $field: $ty,
+                )*
+            }
+
+            impl Event for $name {
+                const KIND: EventKind = EventKind::$name;
+            }
+
+            #[cfg(feature = "std")]
+            impl crate::decoder::DecodeEvent for $name {
+                fn decode(bytes: &[u8]) -> Result<Self, crate::decoder::DecodeError> {
+                    if bytes.len() != core::mem::size_of::<Self>() {
+                        return Err(crate::decoder::DecodeError::InvalidLength {
+                            expected: core::mem::size_of::<Self>(),
+                            actual: bytes.len(),
+                        });
+                    }
+
+                    // packed, so fields follow each other in declaration order
+                    let mut offset = 0;
+                    $(
+                        let $field = crate::decoder::field::read_at::<$ty>(
+                            bytes,
+                            offset,
+                            stringify!($field),
+                        )?;
+                        offset += <$ty as crate::decoder::field::Field>::SIZE;
+                    )*
+                    let _ = offset;
+
+                    Ok(Self { $($field,)* })
+                }
+            }
+        )*
+    };
+}
+
+events! {
+    EscrowCreated {
+        escrow: Pubkey,
+        sender: Pubkey,
+        receiver: Pubkey,
+        mint: Pubkey,
+        amount: u64,
+        reference_seed: u64,
+        deadline: i64,
+        auto_release_at: i64,
+        milestone_count: u8,
+        has_multisig: bool,
+        is_native: bool,
+    }
+
+    PaymentReleased {
+        escrow: Pubkey,
+        released_by: Pubkey,
+    }
+
+    /// `released` is set by the approval that reaches the threshold.
+    MultisigApproved {
+        escrow: Pubkey,
+        multisig: Pubkey,
+        member: Pubkey,
+        approvals: u8,
+        threshold: u8,
+        released: bool,
+    }
+
+    PaymentClaimed {
+        escrow: Pubkey,
+        receiver: Pubkey,
+        amount: u64, // paid to the freelancer, after the protocol fee
+        fee: u64,
+    }
+
+    EscrowCancelled {
+        escrow: Pubkey,
+        sender: Pubkey,
+        refunded: u64,
+    }
+
+    MilestoneReleased {
+        escrow: Pubkey,
+        index: u8,
+    }
+
+    MilestoneClaimed {
+        escrow: Pubkey,
+        receiver: Pubkey,
+        amount: u64,
+        fee: u64,
+        index: u8,
+        settled: bool,
+    }
+
+    DisputeOpened {
+        escrow: Pubkey,
+        opened_by: Pubkey,
+    }
+
+    DisputeResolved {
+        escrow: Pubkey,
+        arbiter: Pubkey,
+        receiver_amount: u64, // after the protocol fee on released milestones
+        sender_amount: u64,
+        receiver_bps: u16,
+    }
+
+    BadgeCreated {
+        badge: Pubkey,
+        freelancer: Pubkey,
+    }
+
+    BadgeUpdated {
+        badge: Pubkey,
+        freelancer: Pubkey,
+        completed_escrows: u32,
+        total_value_completed: u64,
+        disputes_won: u32,
+        disputes_lost: u32,
+    }
+
+    NftMinted {
+        freelancer: Pubkey,
+        asset: Pubkey,
+        tier: u8,
+    }
+
+    MultisigCreated {
+        multisig: Pubkey,
+        client: Pubkey,
+        member_count: u8,
+        threshold: u8,
+    }
+
+    /// Emitted by `InitializeConfig`, `UpdateConfig` and `TransferAdmin`.
+    ConfigUpdated {
+        admin: Pubkey,
+        fee_bps: u16,
+    }
+
+    PauseUpdated {
+        authority: Pubkey,
+        paused_instructions: u32,
+        paused: bool,
+    }
+
+    TreasuryWithdrawn {
+        mint: Pubkey, // Pubkey::default() for native SOL
+        destination: Pubkey,
+        amount: u64,
+    }
+
+    AccountMigrated {
+        account: Pubkey,
+        discriminator: u8,
+        from_version: u8,
+        to_version: u8,
+    }
+
+    /// `added` and `removed` are `Pubkey::default()` when the change didn't add or
+    /// remove a member; a rotation sets both.
+    MultisigUpdated {
+        multisig: Pubkey,
+        added: Pubkey,
+        removed: Pubkey,
+        member_count: u8,
+        threshold: u8,
+    }
+}
@@ -0,0 +1,48 @@
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+
+use crate::{
+    errors::BondrError,
+    states::{load_account, load_account_mut, load_ix_data, ClientMultisig, Config, DataLen},
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct AddMultisigMember {
+    pub member: Pubkey,
+}
+
+impl DataLen for AddMultisigMember {
+    const LEN: usize = core::mem::size_of::<AddMultisigMember>();
+}
+
+/// Appends a member. `approvers` are `threshold` current members signing the transaction.
+pub fn add_multisig_member(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [multisig_acc, config_acc, approvers @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let ix_data = unsafe { load_ix_data::<AddMultisigMember>(data)? };
+
+    let multisig = unsafe { load_account_mut::<ClientMultisig>(multisig_acc)? };
+    multisig.authorize_change(approvers)?;
+
+    let max_members = load_account::<Config>(config_acc)?
+        .settings
+        .max_multisig_members;
+
+    if multisig.member_count >= max_members {
+        return Err(BondrError::InvalidMultisigConfig.into());
+    }
+
+    if multisig.member_index(&ix_data.member).is_some() {
+        return Err(BondrError::DuplicateMember.into());
+    }
+
+    multisig.members[multisig.member_count as usize] = ix_data.member;
+    multisig.member_count += 1;
+    multisig.emit_updated(multisig_acc.key(), ix_data.member, Pubkey::default());
+
+    Ok(())
+}
@@ -1,6 +1,18 @@
-use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
-
-use crate::{errors::BondrError, states::{load_acc_mut_unchecked, ClientMultisig, DataLen, Escrow}};
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::{
+    errors::BondrError,
+    events::{Event, MultisigApproved},
+    states::{
+        load_account, load_account_mut, ClientMultisig, DataLen, Escrow, MultisigApproval,
+        UserStats,
+    },
+};
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -11,7 +23,9 @@ impl DataLen for ApproveMultisigRelease {
 }
 
 pub fn approve_multisig_release(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [member, multisig_acc, escrow_acc, _system_program] = accounts else {
+    let [member, multisig_acc, escrow_acc, multisig_approval_acc, sender_stats, _system_program, _config_acc] =
+        accounts
+    else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
@@ -23,50 +37,50 @@ pub fn approve_multisig_release(accounts: &[AccountInfo], data: &[u8]) -> Progra
     let _ix = unsafe { crate::states::load_ix_data::<ApproveMultisigRelease>(data)? };
 
     // Load account state
-    let multisig = unsafe {
-        load_acc_mut_unchecked::<ClientMultisig>(multisig_acc.borrow_mut_data_unchecked())?
-    };
+    let multisig = load_account::<ClientMultisig>(multisig_acc)?;
 
-    let escrow =
-        unsafe { load_acc_mut_unchecked::<Escrow>(escrow_acc.borrow_mut_data_unchecked())? };
+    let escrow = unsafe { load_account_mut::<Escrow>(escrow_acc)? };
 
-    if multisig.pending_escrow != *escrow_acc.key() {
+    if !escrow.has_multisig || escrow.client_multisig != *multisig_acc.key() {
         return Err(BondrError::MultisigPendingEscrowMismatch.into());
     }
 
-    let mut found = false;
-    let mut member_index: usize = 0;
-
-    for i in 0..(multisig.member_count as usize) {
-        if multisig.members[i] == *member.key() {
-            found = true;
-            member_index = i;
-            break;
-        }
+    if escrow.is_disputed {
+        return Err(BondrError::EscrowDisputed.into());
     }
 
-    if !found {
-        return Err(BondrError::NotMultisigMember.into());
+    if escrow.is_milestone_escrow() {
+        return Err(BondrError::MilestoneEscrowNotSupported.into());
     }
 
-    if multisig.approvals[member_index] == 1 {
-        return Err(BondrError::AlreadyApproved.into());
+    if escrow.is_released {
+        return Err(BondrError::AlreadyReleased.into());
     }
 
-    // approve
-    multisig.approvals[member_index] = 1;
+    let approval =
+        unsafe { MultisigApproval::load_for(multisig_approval_acc, escrow, escrow_acc.key())? };
+    approval.approve(multisig, member.key())?;
 
     // check threshold
-    let approvals_met = multisig.approvals
-        .iter()
-        .take(multisig.member_count as usize)
-        .filter(|&&a| a == 1)
-        .count() as u8;
-
+    let approvals_met = approval.approval_count(multisig);
 
     if approvals_met >= multisig.threshold {
         escrow.is_released = true;
+
+        // credited to the member who created the escrow
+        let sender_stats = unsafe { UserStats::load_mut(sender_stats, &escrow.sender, member)? };
+        sender_stats.record_released(escrow.created_at, Clock::get()?.unix_timestamp);
+    }
+
+    MultisigApproved {
+        escrow: *escrow_acc.key(),
+        multisig: *multisig_acc.key(),
+        member: *member.key(),
+        approvals: approvals_met,
+        threshold: multisig.threshold,
+        released: escrow.is_released,
     }
-    
+    .emit();
+
     Ok(())
 }
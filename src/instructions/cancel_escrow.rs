@@ -0,0 +1,114 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::{
+    errors::BondrError,
+    events::{EscrowCancelled, Event},
+    states::{
+        close_escrow, escrow_balance, load_account_mut, load_ix_data, pay_from_escrow, DataLen,
+        Escrow, EscrowPayout, EscrowVault, MilestoneState, MultisigApproval, UserStats,
+    },
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CancelEscrow {}
+
+impl DataLen for CancelEscrow {
+    const LEN: usize = core::mem::size_of::<CancelEscrow>();
+}
+
+pub fn cancel_escrow(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [sender, receiver, escrow_acc, sender_stats, multisig_acc, multisig_approval_acc, escrow_token_acc, sender_token_acc, token_mint_acc, token_program, _system_program, extra_accounts @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !sender.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let _ix_data = unsafe { load_ix_data::<CancelEscrow>(data)? };
+
+    let escrow_state = unsafe { load_account_mut::<Escrow>(escrow_acc)? };
+
+    if escrow_state.sender != *sender.key() {
+        return Err(BondrError::UnauthorizedSender.into());
+    }
+
+    if escrow_state.receiver != *receiver.key() {
+        return Err(BondrError::UnauthorizedReceiver.into());
+    }
+
+    if escrow_state.is_disputed {
+        return Err(BondrError::EscrowDisputed.into());
+    }
+
+    if escrow_state.is_released {
+        return Err(BondrError::AlreadyReleased.into());
+    }
+
+    // released milestones belong to the freelancer; they have to be claimed first
+    if escrow_state
+        .active_milestones()
+        .iter()
+        .any(|m| m.state == MilestoneState::Released)
+    {
+        return Err(BondrError::AlreadyReleased.into());
+    }
+
+    // either the freelancer co-signs the refund or the deadline has run out
+    if !receiver.is_signer() && !escrow_state.is_expired(Clock::get()?.unix_timestamp) {
+        return Err(BondrError::EscrowNotExpired.into());
+    }
+
+    let vault = EscrowVault {
+        escrow_acc,
+        escrow_token_acc,
+        mint: token_mint_acc,
+        token_program,
+        extra_accounts,
+    };
+    vault.validate(escrow_state)?;
+
+    // sweep whatever the vault holds so it can be closed
+    let balance = escrow_balance(&vault, escrow_state)?;
+
+    pay_from_escrow(
+        &vault,
+        escrow_state,
+        &[EscrowPayout {
+            wallet: sender,
+            token_account: sender_token_acc,
+            amount: balance,
+        }],
+    )?;
+
+    let sender_stats = unsafe { UserStats::load_mut(sender_stats, sender.key(), sender)? };
+    sender_stats.record_refunded();
+
+    EscrowCancelled {
+        escrow: *escrow_acc.key(),
+        sender: *sender.key(),
+        refunded: balance,
+    }
+    .emit();
+
+    unsafe {
+        MultisigApproval::close(
+            multisig_approval_acc,
+            multisig_acc,
+            escrow_state,
+            escrow_acc.key(),
+            sender,
+        )?
+    };
+    close_escrow(&vault, escrow_state, sender)?;
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+
+use crate::{
+    errors::BondrError,
+    states::{load_account_mut, load_ix_data, ClientMultisig, DataLen},
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct ChangeThreshold {
+    pub threshold: u8,
+}
+
+impl DataLen for ChangeThreshold {
+    const LEN: usize = core::mem::size_of::<ChangeThreshold>();
+}
+
+/// Sets how many approvals release an escrow, approved under the current threshold.
+pub fn change_threshold(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [multisig_acc, _config_acc, approvers @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let ix_data = unsafe { load_ix_data::<ChangeThreshold>(data)? };
+
+    let multisig = unsafe { load_account_mut::<ClientMultisig>(multisig_acc)? };
+    multisig.authorize_change(approvers)?;
+
+    if ix_data.threshold == 0 || ix_data.threshold > multisig.member_count {
+        return Err(BondrError::InvalidMultisigConfig.into());
+    }
+
+    multisig.threshold = ix_data.threshold;
+    multisig.emit_updated(multisig_acc.key(), Pubkey::default(), Pubkey::default());
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::{
+    errors::BondrError,
+    events::{Event, MilestoneClaimed},
+    states::{
+        close_escrow, escrow_balance, load_account, load_account_mut, load_ix_data,
+        pay_freelancer_from_escrow, Config, DataLen, Escrow, EscrowPayout, EscrowVault,
+        FreelancerBadge, MilestoneState, Treasury, UserStats,
+    },
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct ClaimMilestone {
+    pub index: u8,
+    pub receiver_stats_bump: u8,
+}
+
+impl DataLen for ClaimMilestone {
+    const LEN: usize = core::mem::size_of::<ClaimMilestone>();
+}
+
+pub fn claim_milestone(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [client, freelancer, escrow_acc, receiver_stats_acc, escrow_token_acc, receiver_token_acc, token_mint_acc, token_program, _system_program, config_acc, treasury, treasury_token_acc, receiver_badge_acc, extra_accounts @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !freelancer.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let ix_data = unsafe { load_ix_data::<ClaimMilestone>(data) }?;
+
+    let escrow_state = unsafe { load_account_mut::<Escrow>(escrow_acc)? };
+
+    if escrow_state.sender != *client.key() {
+        return Err(BondrError::UnauthorizedSender.into());
+    }
+
+    if escrow_state.receiver != *freelancer.key() {
+        return Err(BondrError::UnauthorizedReceiver.into());
+    }
+
+    if escrow_state.is_disputed {
+        return Err(BondrError::EscrowDisputed.into());
+    }
+
+    if !escrow_state.is_milestone_escrow() {
+        return Err(BondrError::NotMilestoneEscrow.into());
+    }
+
+    let vault = EscrowVault {
+        escrow_acc,
+        escrow_token_acc,
+        mint: token_mint_acc,
+        token_program,
+        extra_accounts,
+    };
+    vault.validate(escrow_state)?;
+
+    if ix_data.index >= escrow_state.milestone_count {
+        return Err(BondrError::InvalidMilestone.into());
+    }
+
+    let auto_released = escrow_state.is_auto_released(Clock::get()?.unix_timestamp);
+
+    let milestone = escrow_state.milestones[ix_data.index as usize];
+
+    match milestone.state {
+        MilestoneState::Released => {}
+        MilestoneState::Pending if auto_released => {}
+        MilestoneState::Pending => return Err(BondrError::NotReleased.into()),
+        MilestoneState::Claimed => return Err(BondrError::AlreadyReleased.into()),
+    }
+
+    escrow_state.milestones[ix_data.index as usize].state = MilestoneState::Claimed;
+
+    // the escrow is settled once the last milestone has been paid out;
+    // that payout also sweeps anything left in the vault so it can be closed
+    let settled = escrow_state.all_milestones_claimed();
+    let amount = if settled {
+        escrow_balance(&vault, escrow_state)?.max(milestone.amount)
+    } else {
+        milestone.amount
+    };
+
+    let treasury = Treasury {
+        config: load_account::<Config>(config_acc)?,
+        treasury,
+        treasury_token_acc,
+    };
+    let tier = FreelancerBadge::tier_of(receiver_badge_acc, freelancer.key())?;
+
+    let fee = pay_freelancer_from_escrow(
+        &vault,
+        escrow_state,
+        &treasury,
+        tier,
+        EscrowPayout {
+            wallet: freelancer,
+            token_account: receiver_token_acc,
+            amount,
+        },
+    )?;
+
+    if settled {
+        // counted like a single-payment claim; the freelancer pays for their stats the first time
+        let receiver_stats = unsafe {
+            UserStats::load_or_create(
+                receiver_stats_acc,
+                freelancer.key(),
+                ix_data.receiver_stats_bump,
+                freelancer,
+            )?
+        };
+        receiver_stats.record_completed();
+
+        if treasury
+            .config
+            .counts_toward_reputation(&escrow_state.mint, escrow_state.amount)
+        {
+            FreelancerBadge::record_completed_escrow(
+                receiver_badge_acc,
+                freelancer.key(),
+                escrow_state.amount,
+            )?;
+        }
+
+        close_escrow(&vault, escrow_state, client)?;
+    }
+
+    MilestoneClaimed {
+        escrow: *escrow_acc.key(),
+        receiver: *freelancer.key(),
+        amount: amount - fee,
+        fee,
+        index: ix_data.index,
+        settled,
+    }
+    .emit();
+
+    Ok(())
+}
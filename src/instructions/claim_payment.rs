@@ -1,19 +1,24 @@
 use pinocchio::{
-    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
 };
 
 use crate::{
-    constants::MAX_MULTISIG_MEMBERS, errors::BondrError, states::{
-        load_acc_mut_unchecked, load_ix_data, transfer_spl_tokens_from_escrow, ClientMultisig,
-        DataLen, Escrow, UserStats, 
-    }
+    errors::BondrError,
+    events::{Event, PaymentClaimed},
+    states::{
+        close_escrow, escrow_balance, load_account, load_account_mut, load_ix_data,
+        pay_freelancer_from_escrow, ClientMultisig, Config, DataLen, Escrow, EscrowPayout,
+        EscrowVault, FreelancerBadge, MultisigApproval, Treasury, UserStats,
+    },
 };
 
 #[repr(C)]
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
 pub struct ClaimPayment {
-    reference_seed: u8,
-    receiver_stats_bump: u8,
+    pub receiver_stats_bump: u8,
 }
 
 impl DataLen for ClaimPayment {
@@ -21,7 +26,7 @@ impl DataLen for ClaimPayment {
 }
 
 pub fn claim_payment(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [_client, freelancer, escrow_acc, receiver_stats_acc, multisig_acc, escrow_token_acc, receiver_token_acc, _token_mint_acc, _token_program, _system_program] =
+    let [client, freelancer, escrow_acc, receiver_stats_acc, multisig_acc, multisig_approval_acc, escrow_token_acc, receiver_token_acc, token_mint_acc, token_program, _system_program, config_acc, treasury, treasury_token_acc, receiver_badge_acc, extra_accounts @ ..] =
         accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
@@ -33,70 +38,113 @@ pub fn claim_payment(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
 
     let ix_data = unsafe { load_ix_data::<ClaimPayment>(data) }?;
 
-    let escrow_state =
-        unsafe { load_acc_mut_unchecked::<Escrow>(escrow_acc.borrow_mut_data_unchecked())? };
+    let escrow_state = unsafe { load_account_mut::<Escrow>(escrow_acc)? };
+
+    if escrow_state.sender != *client.key() {
+        return Err(BondrError::UnauthorizedSender.into());
+    }
 
     if escrow_state.receiver != *freelancer.key() {
         return Err(BondrError::UnauthorizedReceiver.into());
     }
 
-    if !escrow_state.is_released {
-        return Err(BondrError::NotReleased.into());
+    if escrow_state.is_disputed {
+        return Err(BondrError::EscrowDisputed.into());
     }
 
-    if !multisig_acc.data_is_empty() {
-        let multisig_state = unsafe {
-            load_acc_mut_unchecked::<ClientMultisig>(multisig_acc.borrow_mut_data_unchecked())?
-        };
+    if escrow_state.is_milestone_escrow() {
+        return Err(BondrError::MilestoneEscrowNotSupported.into());
+    }
 
-        // pending escrow must match
-        if multisig_state.pending_escrow != *escrow_acc.key() {
-            return Err(BondrError::MultisigPendingEscrowMismatch.into());
-        }
+    // past `auto_release_at` the client (or its multisig) no longer has to release
+    let auto_released = escrow_state.is_auto_released(Clock::get()?.unix_timestamp);
 
-        // approvals >= threshold
-        let approvals_met = multisig_state
-            .approvals
-            .iter()
-            .take(multisig_state.member_count as usize)
-            .filter(|&&a| a == 1)
-            .count() as u8;
+    if !escrow_state.is_released && !auto_released {
+        return Err(BondrError::NotReleased.into());
+    }
 
-        if approvals_met < multisig_state.threshold {
-            return Err(BondrError::MultisigThresholdNotMet.into());
+    let vault = EscrowVault {
+        escrow_acc,
+        escrow_token_acc,
+        mint: token_mint_acc,
+        token_program,
+        extra_accounts,
+    };
+    vault.validate(escrow_state)?;
+
+    if escrow_state.has_multisig {
+        // the multisig the escrow was created under, not whichever one is passed in
+        if escrow_state.client_multisig != *multisig_acc.key() {
+            return Err(BondrError::MultisigPendingEscrowMismatch.into());
         }
 
-        // reset pending escrow + approvals
-        multisig_state.pending_escrow = Pubkey::default();
-        multisig_state.approvals = [0u8; MAX_MULTISIG_MEMBERS];
+        // the approvals were checked when they released the escrow, and an auto-released
+        // escrow doesn't need them
+        load_account::<ClientMultisig>(multisig_acc)?;
     }
 
-    transfer_spl_tokens_from_escrow(
-        escrow_token_acc,
-        receiver_token_acc,
-        escrow_acc,
-        &escrow_state.sender,
-        &escrow_state.receiver,
-        ix_data.reference_seed,
-        escrow_state.bump,
-        escrow_state.amount,
+    let treasury = Treasury {
+        config: load_account::<Config>(config_acc)?,
+        treasury,
+        treasury_token_acc,
+    };
+    let tier = FreelancerBadge::tier_of(receiver_badge_acc, freelancer.key())?;
+
+    // pay out the whole vault so it can be closed, less the protocol fee
+    let balance = escrow_balance(&vault, escrow_state)?;
+    let fee = pay_freelancer_from_escrow(
+        &vault,
+        escrow_state,
+        &treasury,
+        tier,
+        EscrowPayout {
+            wallet: freelancer,
+            token_account: receiver_token_acc,
+            amount: balance,
+        },
     )?;
 
+    // the freelancer pays for their stats account the first time they claim
     let receiver_stats = unsafe {
-        load_acc_mut_unchecked::<UserStats>(receiver_stats_acc.borrow_mut_data_unchecked())?
+        UserStats::load_or_create(
+            receiver_stats_acc,
+            freelancer.key(),
+            ix_data.receiver_stats_bump,
+            freelancer,
+        )?
     };
-
-    if receiver_stats.user == Pubkey::default() {
-        *receiver_stats = UserStats {
-            user: *freelancer.key(),
-            completed_escrows: 1,
-            bump: ix_data.receiver_stats_bump,
-        };
-    } else {
-        receiver_stats.completed_escrows = receiver_stats.completed_escrows.saturating_add(1);
+    receiver_stats.record_completed();
+
+    if treasury
+        .config
+        .counts_toward_reputation(&escrow_state.mint, escrow_state.amount)
+    {
+        FreelancerBadge::record_completed_escrow(
+            receiver_badge_acc,
+            freelancer.key(),
+            escrow_state.amount,
+        )?;
     }
 
-    escrow_acc.close();
+    // rent for the vault, the escrow and its approvals goes back to the client who funded them
+    unsafe {
+        MultisigApproval::close(
+            multisig_approval_acc,
+            multisig_acc,
+            escrow_state,
+            escrow_acc.key(),
+            client,
+        )?
+    };
+    close_escrow(&vault, escrow_state, client)?;
+
+    PaymentClaimed {
+        escrow: *escrow_acc.key(),
+        receiver: *freelancer.key(),
+        amount: balance - fee,
+        fee,
+    }
+    .emit();
 
     Ok(())
 }
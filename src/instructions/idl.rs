@@ -0,0 +1,199 @@
+//! Instruction layout for the shank IDL. The program dispatches on the bare
+//! `BondrInstruction` byte, so this enum is never constructed: it only gives shank
+//! each instruction's accounts and argument struct. Keep it in step with
+//! `BondrInstruction` and the handlers.
+
+use super::*;
+
+#[derive(shank::ShankInstruction)]
+#[rustfmt::skip]
+pub enum BondrInstructionIdl {
+    #[account(0, writable, signer, name = "sender")]
+    #[account(1, name = "receiver")]
+    #[account(2, writable, name = "escrow")]
+    #[account(3, writable, name = "sender_stats", desc = "Created on the client's first escrow")]
+    #[account(4, writable, name = "client_multisig", desc = "Any account when the escrow has no multisig")]
+    #[account(5, writable, name = "multisig_approval", desc = "Any account when the escrow has no multisig")]
+    #[account(6, writable, name = "sender_token_account")]
+    #[account(7, writable, name = "escrow_token_account")]
+    #[account(8, name = "token_mint")]
+    #[account(9, name = "sysvar_rent", desc = "Unused; kept so the account list is unchanged")]
+    #[account(10, name = "token_program")]
+    #[account(11, name = "system_program")]
+    #[account(12, name = "config")]
+    InitializeEscrow(InitializeEscrow),
+
+    #[account(0, writable, signer, name = "freelancer")]
+    #[account(1, writable, name = "badge")]
+    #[account(2, name = "system_program")]
+    #[account(3, name = "sysvar_rent")]
+    #[account(4, name = "config")]
+    InitializeFreelancerBadge(InitializeFreelancerBadge),
+
+    #[account(0, writable, signer, name = "client")]
+    #[account(1, writable, name = "multisig")]
+    #[account(2, name = "system_program")]
+    #[account(3, name = "sysvar_rent")]
+    #[account(4, name = "config")]
+    InitializeMultisigClient(InitializeMultisigClient),
+
+    #[account(0, writable, signer, name = "client", desc = "Pays to grow sender_stats from an older layout")]
+    #[account(1, writable, name = "escrow")]
+    #[account(2, writable, name = "sender_stats")]
+    #[account(3, name = "system_program")]
+    #[account(4, name = "config")]
+    ReleasePayment,
+
+    #[account(0, writable, name = "client", desc = "Receives the escrow's rent")]
+    #[account(1, writable, signer, name = "freelancer")]
+    #[account(2, writable, name = "escrow")]
+    #[account(3, writable, name = "receiver_stats", desc = "Created on the freelancer's first claim")]
+    #[account(4, writable, name = "multisig", desc = "Any account when the escrow has no multisig")]
+    #[account(5, writable, name = "multisig_approval", desc = "Closed to the client; any account when the escrow has no multisig")]
+    #[account(6, writable, name = "escrow_token_account")]
+    #[account(7, writable, name = "receiver_token_account")]
+    #[account(8, name = "token_mint")]
+    #[account(9, name = "token_program")]
+    #[account(10, name = "system_program")]
+    #[account(11, name = "config")]
+    #[account(12, writable, name = "treasury")]
+    #[account(13, writable, name = "treasury_token_account")]
+    #[account(14, writable, name = "receiver_badge")]
+    ClaimPayment(ClaimPayment),
+
+    #[account(0, writable, signer, name = "member", desc = "Pays to grow sender_stats from an older layout")]
+    #[account(1, name = "multisig")]
+    #[account(2, writable, name = "escrow")]
+    #[account(3, writable, name = "multisig_approval")]
+    #[account(4, writable, name = "sender_stats", desc = "Stats of the member who created the escrow")]
+    #[account(5, name = "system_program")]
+    #[account(6, name = "config")]
+    ApproveMultisigRelease,
+
+    #[account(0, signer, name = "admin")]
+    #[account(1, writable, name = "badge")]
+    #[account(2, name = "config")]
+    UpdateFreelancerBadge(UpdateFreelancerBadge),
+
+    #[account(0, writable, signer, name = "freelancer")]
+    #[account(1, writable, name = "badge")]
+    #[account(2, writable, signer, name = "asset")]
+    #[account(3, writable, name = "collection")]
+    #[account(4, name = "config")]
+    #[account(5, name = "mpl_core_program")]
+    #[account(6, name = "system_program")]
+    MintReputationNft,
+
+    #[account(0, writable, signer, name = "sender")]
+    #[account(1, optional_signer, name = "receiver", desc = "Signs for a mutual cancel before the deadline")]
+    #[account(2, writable, name = "escrow")]
+    #[account(3, writable, name = "sender_stats")]
+    #[account(4, writable, name = "multisig", desc = "Any account when the escrow has no multisig")]
+    #[account(5, writable, name = "multisig_approval", desc = "Closed to the client; any account when the escrow has no multisig")]
+    #[account(6, writable, name = "escrow_token_account")]
+    #[account(7, writable, name = "sender_token_account")]
+    #[account(8, name = "token_mint")]
+    #[account(9, name = "token_program")]
+    #[account(10, name = "system_program")]
+    CancelEscrow,
+
+    #[account(0, writable, signer, name = "client", desc = "Pays to grow sender_stats from an older layout")]
+    #[account(1, writable, name = "escrow")]
+    #[account(2, writable, name = "sender_stats")]
+    #[account(3, name = "system_program")]
+    #[account(4, name = "config")]
+    ReleaseMilestone(ReleaseMilestone),
+
+    #[account(0, writable, name = "client")]
+    #[account(1, writable, signer, name = "freelancer")]
+    #[account(2, writable, name = "escrow")]
+    #[account(3, writable, name = "receiver_stats", desc = "Created on the freelancer's first claim")]
+    #[account(4, writable, name = "escrow_token_account")]
+    #[account(5, writable, name = "receiver_token_account")]
+    #[account(6, name = "token_mint")]
+    #[account(7, name = "token_program")]
+    #[account(8, name = "system_program")]
+    #[account(9, name = "config")]
+    #[account(10, writable, name = "treasury")]
+    #[account(11, writable, name = "treasury_token_account")]
+    #[account(12, writable, name = "receiver_badge")]
+    ClaimMilestone(ClaimMilestone),
+
+    #[account(0, signer, name = "party")]
+    #[account(1, writable, name = "escrow")]
+    OpenDispute,
+
+    #[account(0, writable, signer, name = "arbiter", desc = "Pays to grow sender_stats from an older layout")]
+    #[account(1, writable, name = "sender")]
+    #[account(2, writable, name = "receiver")]
+    #[account(3, writable, name = "escrow")]
+    #[account(4, writable, name = "escrow_token_account")]
+    #[account(5, writable, name = "sender_token_account")]
+    #[account(6, writable, name = "receiver_token_account")]
+    #[account(7, name = "token_mint")]
+    #[account(8, writable, name = "receiver_badge")]
+    #[account(9, writable, name = "sender_stats")]
+    #[account(10, writable, name = "multisig", desc = "Any account when the escrow has no multisig")]
+    #[account(11, writable, name = "multisig_approval", desc = "Closed to the client; any account when the escrow has no multisig")]
+    #[account(12, name = "config")]
+    #[account(13, writable, name = "treasury")]
+    #[account(14, writable, name = "treasury_token_account")]
+    #[account(15, name = "token_program")]
+    #[account(16, name = "system_program")]
+    ResolveDispute(ResolveDispute),
+
+    #[account(0, writable, signer, name = "admin")]
+    #[account(1, writable, name = "config")]
+    #[account(2, writable, name = "treasury")]
+    #[account(3, name = "program")]
+    #[account(4, name = "program_data")]
+    #[account(5, name = "system_program")]
+    #[account(6, name = "sysvar_rent")]
+    InitializeConfig(InitializeConfig),
+
+    #[account(0, signer, name = "admin")]
+    #[account(1, name = "config")]
+    #[account(2, writable, name = "treasury")]
+    #[account(3, writable, name = "destination")]
+    #[account(4, writable, name = "treasury_token_account")]
+    #[account(5, writable, name = "destination_token_account")]
+    #[account(6, name = "token_mint")]
+    #[account(7, name = "token_program")]
+    #[account(8, name = "system_program")]
+    WithdrawTreasury(WithdrawTreasury),
+
+    #[account(0, signer, name = "admin")]
+    #[account(1, writable, name = "config")]
+    UpdateConfig(UpdateConfig),
+
+    #[account(0, signer, name = "admin")]
+    #[account(1, signer, name = "new_admin")]
+    #[account(2, writable, name = "config")]
+    TransferAdmin,
+
+    #[account(0, signer, name = "authority")]
+    #[account(1, writable, name = "config")]
+    SetPause(SetPause),
+
+    #[account(0, writable, signer, name = "payer")]
+    #[account(1, writable, name = "account")]
+    #[account(2, name = "system_program")]
+    #[account(3, name = "config", desc = "The account itself when migrating the config")]
+    MigrateAccount,
+
+    #[account(0, writable, name = "multisig", desc = "Followed by `threshold` members signing as approvers")]
+    #[account(1, name = "config")]
+    AddMultisigMember(AddMultisigMember),
+
+    #[account(0, writable, name = "multisig", desc = "Followed by `threshold` members signing as approvers")]
+    #[account(1, name = "config")]
+    RemoveMultisigMember(RemoveMultisigMember),
+
+    #[account(0, writable, name = "multisig", desc = "Followed by `threshold` members signing as approvers")]
+    #[account(1, name = "config")]
+    RotateMultisigMember(RotateMultisigMember),
+
+    #[account(0, writable, name = "multisig", desc = "Followed by `threshold` members signing as approvers")]
+    #[account(1, name = "config")]
+    ChangeThreshold(ChangeThreshold),
+}
@@ -0,0 +1,146 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::rent::Rent,
+    ProgramResult,
+};
+
+use pinocchio_system::instructions::{CreateAccount, Transfer};
+
+use crate::{
+    constants::BPF_LOADER_UPGRADEABLE_ID,
+    errors::BondrError,
+    events::{ConfigUpdated, Event},
+    states::{
+        init_account,
+        utils::{load_ix_data, DataLen},
+        Config, ConfigSettings, ProgramAccount,
+    },
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct InitializeConfig {
+    pub settings: ConfigSettings,
+    pub bump: u8,
+    pub treasury_bump: u8,
+    pub _padding: [u8; 2],
+    pub min_reputation_amounts: [u64; 8], // MAX_ALLOWED_MINTS
+}
+
+impl DataLen for InitializeConfig {
+    const LEN: usize = core::mem::size_of::<InitializeConfig>();
+}
+
+pub fn init_config(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [admin, config_acc, treasury, program_acc, program_data_acc, _system_program, sysvar_rent_acc] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !admin.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if !config_acc.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    // only the upgrade authority may claim the admin role
+    validate_upgrade_authority(program_acc, program_data_acc, admin)?;
+
+    let ix_data = unsafe { load_ix_data::<InitializeConfig>(data)? };
+
+    ix_data.settings.validate()?;
+
+    Config::validate_canonical_pda(ix_data.bump, config_acc.key())?;
+    Config::validate_treasury_pda(ix_data.treasury_bump, treasury.key())?;
+
+    let rent = Rent::from_account_info(sysvar_rent_acc)?;
+
+    let bump_bytes = [ix_data.bump];
+    let signer_seeds = [
+        Seed::from(Config::SEED.as_bytes()),
+        Seed::from(&bump_bytes[..]),
+    ];
+
+    CreateAccount {
+        from: admin,
+        to: config_acc,
+        lamports: rent.minimum_balance(Config::LEN),
+        space: Config::LEN as u64,
+        owner: &crate::ID,
+    }
+    .invoke_signed(&[Signer::from(&signer_seeds[..])])?;
+
+    // native SOL fees are credited straight to the treasury, so it has to be rent exempt first
+    let treasury_rent = rent.minimum_balance(0);
+    if treasury.lamports() < treasury_rent {
+        Transfer {
+            from: admin,
+            to: treasury,
+            lamports: treasury_rent - treasury.lamports(),
+        }
+        .invoke()?;
+    }
+
+    let config_state = unsafe { init_account::<Config>(config_acc)? };
+
+    *config_state = Config {
+        discriminator: Config::DISCRIMINATOR as u8,
+        version: Config::VERSION,
+        admin: *admin.key(),
+        _padding0: [0; 2],
+        settings: ix_data.settings,
+        paused_instructions: 0,
+        paused: false,
+        bump: ix_data.bump,
+        treasury_bump: ix_data.treasury_bump,
+        _padding1: [0; 1],
+        min_reputation_amounts: ix_data.min_reputation_amounts,
+    };
+
+    ConfigUpdated {
+        admin: config_state.admin,
+        fee_bps: config_state.settings.fee_bps,
+    }
+    .emit();
+
+    Ok(())
+}
+
+/// Checks `authority` against the upgradeable loader's `ProgramData` for this program.
+fn validate_upgrade_authority(
+    program_acc: &AccountInfo,
+    program_data_acc: &AccountInfo,
+    authority: &AccountInfo,
+) -> ProgramResult {
+    if *program_acc.key() != crate::ID
+        || !program_acc.is_owned_by(&BPF_LOADER_UPGRADEABLE_ID)
+        || !program_data_acc.is_owned_by(&BPF_LOADER_UPGRADEABLE_ID)
+    {
+        return Err(BondrError::UnauthorizedAdmin.into());
+    }
+
+    // Program: tag (u32 = 2) + programdata address
+    let program_data = program_acc.try_borrow_data()?;
+    if program_data.len() < 36
+        || program_data[..4] != 2u32.to_le_bytes()
+        || program_data[4..36] != *program_data_acc.key()
+    {
+        return Err(BondrError::UnauthorizedAdmin.into());
+    }
+
+    // ProgramData: tag (u32 = 3) + slot (u64) + Option<upgrade authority>
+    let data = program_data_acc.try_borrow_data()?;
+    if data.len() < 45 || data[..4] != 3u32.to_le_bytes() || data[12] != 1 {
+        return Err(BondrError::UnauthorizedAdmin.into());
+    }
+    if data[13..45] != *authority.key() {
+        return Err(BondrError::UnauthorizedAdmin.into());
+    }
+
+    Ok(())
+}
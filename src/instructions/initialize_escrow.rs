@@ -2,27 +2,42 @@ use pinocchio::{
     account_info::AccountInfo,
     instruction::{Seed, Signer},
     program_error::ProgramError,
-    sysvars::rent::Rent,
+    pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar},
     ProgramResult,
 };
 
-use pinocchio_system::instructions::CreateAccount;
+use pinocchio_system::instructions::Transfer;
 
 use crate::{
+    constants::MAX_MILESTONES,
     errors::BondrError,
+    events::{EscrowCreated, Event},
     states::{
-        utils::{load_ix_data, DataLen},
-        Escrow,
+        create_pda_account, init_account, load_account, load_token_account, mint_decimals,
+        mint_transfer_fee,
+        utils::{load_ix_data_with_bools, DataLen},
+        validate_token_program, ClientMultisig, Config, Escrow, Milestone, MultisigApproval,
+        ProgramAccount, TokenTransfer, UserStats,
     },
 };
 
 #[repr(C)]
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
 pub struct InitializeEscrow {
     pub amount: u64,
+    pub reference_seed: u64,
+    pub deadline: i64,
+    pub auto_release_at: i64,
+    pub milestone_amounts: [u64; 8], // MAX_MILESTONES
+    pub arbiter: Pubkey,             // Pubkey::default() = no arbiter
+    pub milestone_count: u8,         // 0 = single payment
     pub bump: u8,
     pub stats_bump: u8,
     pub is_multisig: bool,
+    pub is_native: bool,   // escrow lamports instead of SPL tokens
+    pub approval_bump: u8, // of the `MultisigApproval`, for multisig escrows
+    pub _padding: [u8; 2],
 }
 
 impl DataLen for InitializeEscrow {
@@ -30,7 +45,7 @@ impl DataLen for InitializeEscrow {
 }
 
 pub fn init_escrow(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [sender, receiver, escrow_acc, _sender_stats, client_multisig, _sender_token_account, _escrow_token_account, sysvar_rent_acc, ..] =
+    let [sender, receiver, escrow_acc, sender_stats, client_multisig, multisig_approval, sender_token_account, escrow_token_account, token_mint_acc, _sysvar_rent_acc, token_program, _system_program, config_acc, extra_accounts @ ..] =
         accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
@@ -44,9 +59,15 @@ pub fn init_escrow(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
         return Err(ProgramError::AccountAlreadyInitialized);
     }
 
-    let rent = Rent::from_account_info(sysvar_rent_acc)?;
-
-    let ix_data = unsafe { load_ix_data::<InitializeEscrow>(data)? };
+    let ix_data = unsafe {
+        load_ix_data_with_bools::<InitializeEscrow>(
+            data,
+            &[
+                core::mem::offset_of!(InitializeEscrow, is_multisig),
+                core::mem::offset_of!(InitializeEscrow, is_native),
+            ],
+        )?
+    };
 
     if ix_data.amount == 0 {
         return Err(BondrError::InvalidAmountZero.into());
@@ -55,13 +76,106 @@ pub fn init_escrow(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
         return Err(BondrError::SelfTransfer.into());
     }
 
+    // the arbiter has to be a neutral third party
+    if ix_data.arbiter == *sender.key() || ix_data.arbiter == *receiver.key() {
+        return Err(BondrError::InvalidArbiter.into());
+    }
+
+    if ix_data.milestone_count as usize > MAX_MILESTONES {
+        return Err(BondrError::InvalidMilestone.into());
+    }
+
+    // milestones must be non-zero and add up to exactly the escrowed amount
+    let mut milestones = [Milestone::EMPTY; MAX_MILESTONES];
+    if ix_data.milestone_count > 0 {
+        let mut total: u64 = 0;
+        let count = ix_data.milestone_count as usize;
+        for (milestone, &amount) in milestones
+            .iter_mut()
+            .zip(ix_data.milestone_amounts.iter())
+            .take(count)
+        {
+            if amount == 0 {
+                return Err(BondrError::InvalidAmountZero.into());
+            }
+            total = total
+                .checked_add(amount)
+                .ok_or(BondrError::AmountTooLarge)?;
+            *milestone = Milestone {
+                amount,
+                ..Milestone::EMPTY
+            };
+        }
+        if total != ix_data.amount {
+            return Err(BondrError::InvalidAmount.into());
+        }
+    }
+
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+
+    if ix_data.deadline != 0 && ix_data.deadline <= now {
+        return Err(BondrError::InvalidDeadline.into());
+    }
+    if ix_data.auto_release_at != 0 && ix_data.auto_release_at <= now {
+        return Err(BondrError::InvalidDeadline.into());
+    }
+    // the refund window after the deadline must close before funds auto-release
+    if ix_data.deadline != 0
+        && ix_data.auto_release_at != 0
+        && ix_data.auto_release_at <= ix_data.deadline
+    {
+        return Err(BondrError::InvalidDeadline.into());
+    }
+
     Escrow::validate_pda(
         ix_data.bump,
-        &escrow_acc.key(),
-        &sender.key(),
-        &receiver.key(),
+        escrow_acc.key(),
+        sender.key(),
+        receiver.key(),
+        ix_data.reference_seed,
     )?;
 
+    if !load_account::<Config>(config_acc)?.is_mint_allowed(token_mint_acc.key()) {
+        return Err(BondrError::MintNotAllowed.into());
+    }
+
+    // a multisig escrow is released through member approvals
+    if ix_data.is_multisig {
+        if ix_data.milestone_count > 0 {
+            return Err(BondrError::MilestoneEscrowNotSupported.into());
+        }
+
+        let multisig_state = load_account::<ClientMultisig>(client_multisig)?;
+
+        if multisig_state.member_index(sender.key()).is_none() {
+            return Err(BondrError::NotMultisigMember.into());
+        }
+    }
+
+    if ix_data.is_native {
+        // native escrows record the system program id as their mint and token program
+        if *token_mint_acc.key() != Pubkey::default() || *token_program.key() != Pubkey::default() {
+            return Err(BondrError::MissingTokenAccounts.into());
+        }
+        if sender.lamports() < ix_data.amount {
+            return Err(BondrError::InsufficientBalance.into());
+        }
+    } else {
+        validate_token_program(token_program, token_mint_acc)?;
+
+        // the vault must be a token account of the escrowed mint controlled by the escrow PDA
+        let vault = load_token_account(escrow_token_account, token_program.key())?;
+
+        if vault.owner() != escrow_acc.key() {
+            return Err(BondrError::InvalidOwner.into());
+        }
+        if vault.mint() != token_mint_acc.key() {
+            return Err(BondrError::MissingTokenAccounts.into());
+        }
+    }
+
+    let reference_bytes = ix_data.reference_seed.to_le_bytes();
     let pda_bump_bytes = [ix_data.bump];
 
     // signer seeds
@@ -69,33 +183,132 @@ pub fn init_escrow(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
         Seed::from(Escrow::SEED.as_bytes()),
         Seed::from(sender.key().as_ref()),
         Seed::from(receiver.key().as_ref()),
+        Seed::from(&reference_bytes[..]),
         Seed::from(&pda_bump_bytes[..]),
     ];
-    let signers = [Signer::from(&signer_seeds[..])];
-
-    CreateAccount {
-        from: sender,
-        to: escrow_acc,
-        lamports: rent.minimum_balance(Escrow::LEN),
-        space: Escrow::LEN as u64,
-        owner: &crate::ID,
+    create_pda_account(
+        escrow_acc,
+        sender,
+        Escrow::LEN,
+        &[Signer::from(&signer_seeds[..])],
+    )?;
+
+    // fund the escrow: lamports sit on the PDA itself, tokens in the vault
+    if ix_data.is_native {
+        Transfer {
+            from: sender,
+            to: escrow_acc,
+            lamports: ix_data.amount,
+        }
+        .invoke()?;
+    } else {
+        // gross up fee-on-transfer mints so the vault receives exactly `amount`
+        let deposit = match mint_transfer_fee(token_mint_acc, clock.epoch)? {
+            Some(fee) => fee
+                .calculate_pre_fee_amount(ix_data.amount)
+                .ok_or(BondrError::AmountTooLarge)?,
+            None => ix_data.amount,
+        };
+
+        let balance_before =
+            load_token_account(escrow_token_account, token_program.key())?.amount();
+
+        if load_token_account(sender_token_account, token_program.key())?.amount() < deposit {
+            return Err(BondrError::InsufficientBalance.into());
+        }
+
+        TokenTransfer {
+            from: sender_token_account,
+            mint: token_mint_acc,
+            to: escrow_token_account,
+            authority: sender,
+            token_program,
+            extra_accounts,
+            amount: deposit,
+            decimals: mint_decimals(token_mint_acc)?,
+        }
+        .invoke()?;
+
+        let received = load_token_account(escrow_token_account, token_program.key())?
+            .amount()
+            .saturating_sub(balance_before);
+
+        if received < ix_data.amount {
+            return Err(BondrError::TransferFeeMismatch.into());
+        }
     }
-    .invoke_signed(&signers)?;
+
+    // the client's stats exist from their first escrow on
+    let sender_stats = unsafe {
+        UserStats::load_or_create(sender_stats, sender.key(), ix_data.stats_bump, sender)?
+    };
+    sender_stats.record_funded(ix_data.amount);
 
     let multisig_pubkey = if ix_data.is_multisig {
-        Some(*client_multisig.key())
+        // each escrow collects its own approvals, so several can be pending at once
+        unsafe {
+            MultisigApproval::open(
+                multisig_approval,
+                client_multisig,
+                escrow_acc.key(),
+                ix_data.approval_bump,
+                sender,
+            )?
+        };
+        *client_multisig.key()
     } else {
-        None
+        Pubkey::default()
     };
 
-    Escrow::initialize(
-        escrow_acc,
-        *sender.key(),
-        *receiver.key(),
-        ix_data.amount,
-        ix_data.bump,
-        multisig_pubkey,
-    )?;
+    let escrow_state = unsafe { init_account::<Escrow>(escrow_acc)? };
+
+    *escrow_state = Escrow {
+        discriminator: Escrow::DISCRIMINATOR as u8,
+        version: Escrow::VERSION,
+        sender: *sender.key(),
+        receiver: *receiver.key(),
+        mint: *token_mint_acc.key(),
+        token_program: *token_program.key(),
+        _padding0: [0; 6],
+        amount: ix_data.amount,
+        reference_seed: ix_data.reference_seed,
+        is_released: false,
+        bump: ix_data.bump,
+        client_multisig: multisig_pubkey,
+        has_multisig: ix_data.is_multisig,
+        has_approval: ix_data.is_multisig,
+        approval_bump: if ix_data.is_multisig {
+            ix_data.approval_bump
+        } else {
+            0
+        },
+        _padding1: [0; 3],
+        created_at: now,
+        deadline: ix_data.deadline,
+        auto_release_at: ix_data.auto_release_at,
+        milestone_count: ix_data.milestone_count,
+        _padding2: [0; 7],
+        milestones,
+        arbiter: ix_data.arbiter,
+        is_disputed: false,
+        is_native: ix_data.is_native,
+        _padding3: [0; 6],
+    };
+
+    EscrowCreated {
+        escrow: *escrow_acc.key(),
+        sender: *sender.key(),
+        receiver: *receiver.key(),
+        mint: *token_mint_acc.key(),
+        amount: ix_data.amount,
+        reference_seed: ix_data.reference_seed,
+        deadline: ix_data.deadline,
+        auto_release_at: ix_data.auto_release_at,
+        milestone_count: ix_data.milestone_count,
+        has_multisig: ix_data.is_multisig,
+        is_native: ix_data.is_native,
+    }
+    .emit();
 
     Ok(())
 }
@@ -1,21 +1,19 @@
 use pinocchio::{
-    account_info::AccountInfo, program_error::ProgramError, pubkey::create_program_address,
+    account_info::AccountInfo, instruction::Signer, program_error::ProgramError, seeds,
     sysvars::rent::Rent, ProgramResult,
 };
 
 use pinocchio_system::instructions::CreateAccount;
 
-use crate::{
-    errors::BondrError,
-    states::{
-        load_acc_mut_unchecked,
-        utils::{load_ix_data, DataLen},
-        FreelancerBadge, ReputationTier,
-    },
+use crate::events::{BadgeCreated, Event};
+use crate::states::{
+    init_account,
+    utils::{load_ix_data, DataLen},
+    FreelancerBadge, ProgramAccount, ReputationTier,
 };
 
 #[repr(C)]
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
 pub struct InitializeFreelancerBadge {
     pub bump: u8,
 }
@@ -25,7 +23,7 @@ impl DataLen for InitializeFreelancerBadge {
 }
 
 pub fn init_freelancer_badge(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [freelancer, badge, _system_program, sysvar_rent_acc] = accounts else {
+    let [freelancer, badge, _system_program, sysvar_rent_acc, _config_acc] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
@@ -39,19 +37,11 @@ pub fn init_freelancer_badge(accounts: &[AccountInfo], data: &[u8]) -> ProgramRe
 
     let ix_data = unsafe { load_ix_data::<InitializeFreelancerBadge>(data)? };
 
-    let seeds = &[
-        b"badge".as_ref(),
-        freelancer.key().as_ref(),
-        &[ix_data.bump],
-    ];
-    let derived_pda = create_program_address(seeds, &crate::ID)?;
-    if derived_pda != *badge.key() {
-        return Err(BondrError::PdaMismatch.into());
-    }
+    FreelancerBadge::validate_canonical_pda(ix_data.bump, badge.key(), freelancer.key())?;
 
     let rent = Rent::from_account_info(sysvar_rent_acc)?;
     let min_lamports = rent.minimum_balance(FreelancerBadge::LEN);
-    
+
     CreateAccount {
         from: freelancer,
         to: badge,
@@ -59,18 +49,34 @@ pub fn init_freelancer_badge(accounts: &[AccountInfo], data: &[u8]) -> ProgramRe
         space: FreelancerBadge::LEN as u64,
         owner: &crate::ID,
     }
-    .invoke()?;
+    .invoke_signed(&[Signer::from(&seeds!(
+        FreelancerBadge::SEED.as_bytes(),
+        freelancer.key(),
+        &[ix_data.bump]
+    ))])?;
 
-    let badge_state =
-        unsafe { load_acc_mut_unchecked::<FreelancerBadge>(badge.borrow_mut_data_unchecked())? };
+    let badge_state = unsafe { init_account::<FreelancerBadge>(badge)? };
 
     *badge_state = FreelancerBadge {
+        discriminator: FreelancerBadge::DISCRIMINATOR as u8,
+        version: FreelancerBadge::VERSION,
         tier: ReputationTier::Unranked,
+        _padding0: [0; 1],
         completed_escrows: 0,
         total_value_completed: 0,
         freelancer: *freelancer.key(),
         bump: ix_data.bump,
+        _padding1: [0; 3],
+        disputes_won: 0,
+        disputes_lost: 0,
+        _padding2: [0; 4],
     };
 
+    BadgeCreated {
+        badge: *badge.key(),
+        freelancer: *freelancer.key(),
+    }
+    .emit();
+
     Ok(())
 }
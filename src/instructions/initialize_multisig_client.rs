@@ -1,28 +1,27 @@
 use pinocchio::{
-    account_info::AccountInfo,
-    program_error::ProgramError,
-    pubkey::{create_program_address, Pubkey},
-    sysvars::rent::Rent,
-    ProgramResult,
+    account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey::Pubkey,
+    seeds, sysvars::rent::Rent, ProgramResult,
 };
 
 use pinocchio_system::instructions::CreateAccount;
 
 use crate::{
-    constants::MAX_MULTISIG_MEMBERS, errors::BondrError, states::{
-        load_acc_mut_unchecked,
+    errors::BondrError,
+    events::{Event, MultisigCreated},
+    states::{
+        init_account, load_account,
         utils::{load_ix_data, DataLen},
-        ClientMultisig, 
-    }
+        ClientMultisig, Config, ProgramAccount,
+    },
 };
 
 #[repr(C)]
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
 pub struct InitializeMultisigClient {
-    members: [Pubkey; MAX_MULTISIG_MEMBERS],
-    member_count: u8,
-    threshold: u8,
-    bump: u8,
+    pub members: [Pubkey; 5], // MAX_MULTISIG_MEMBERS
+    pub member_count: u8,
+    pub threshold: u8,
+    pub bump: u8,
 }
 
 impl DataLen for InitializeMultisigClient {
@@ -30,7 +29,7 @@ impl DataLen for InitializeMultisigClient {
 }
 
 pub fn init_multisig_client(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [client, multisig, _system_program, sysvar_rent_acc] = accounts else {
+    let [client, multisig, _system_program, sysvar_rent_acc, config_acc] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
@@ -44,18 +43,14 @@ pub fn init_multisig_client(accounts: &[AccountInfo], data: &[u8]) -> ProgramRes
 
     let ix_data = unsafe { load_ix_data::<InitializeMultisigClient>(data)? };
 
-    let seeds = &[
-        b"client_multisig".as_ref(),
-        client.key().as_ref(),
-        &[ix_data.bump],
-    ];
+    ClientMultisig::validate_pda(ix_data.bump, multisig.key(), client.key())?;
 
-    let derived_pda = create_program_address(seeds, &crate::ID)?;
-    if derived_pda != *multisig.key() {
-        return Err(BondrError::PdaMismatch.into());
-    }
+    // storage fits MAX_MULTISIG_MEMBERS, the config can lower the cap
+    let max_members = load_account::<Config>(config_acc)?
+        .settings
+        .max_multisig_members;
 
-    if ix_data.member_count == 0 || (ix_data.member_count as usize) > MAX_MULTISIG_MEMBERS {
+    if ix_data.member_count == 0 || ix_data.member_count > max_members {
         return Err(BondrError::InvalidMultisigConfig.into());
     }
 
@@ -86,19 +81,33 @@ pub fn init_multisig_client(accounts: &[AccountInfo], data: &[u8]) -> ProgramRes
         space: ClientMultisig::LEN as u64,
         owner: &crate::ID,
     }
-    .invoke()?;
+    .invoke_signed(&[Signer::from(&seeds!(
+        ClientMultisig::SEED.as_bytes(),
+        client.key(),
+        &[ix_data.bump]
+    ))])?;
 
-    let multisig_state =
-        unsafe { load_acc_mut_unchecked::<ClientMultisig>(multisig.borrow_mut_data_unchecked())? };
+    let multisig_state = unsafe { init_account::<ClientMultisig>(multisig)? };
 
     *multisig_state = ClientMultisig {
+        discriminator: ClientMultisig::DISCRIMINATOR as u8,
+        version: ClientMultisig::VERSION,
         members: ix_data.members,
         member_count: ix_data.member_count,
         threshold: ix_data.threshold,
-        approvals: [0u8; MAX_MULTISIG_MEMBERS],
-        pending_escrow: Pubkey::default(),
+        open_approvals: 0,
+        _padding0: [0; 35],
         bump: ix_data.bump,
+        client: *client.key(),
     };
 
+    MultisigCreated {
+        multisig: *multisig.key(),
+        client: *client.key(),
+        member_count: ix_data.member_count,
+        threshold: ix_data.threshold,
+    }
+    .emit();
+
     Ok(())
 }
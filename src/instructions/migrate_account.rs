@@ -0,0 +1,83 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::{
+    errors::BondrError,
+    events::{AccountMigrated, Event},
+    states::{
+        account_version, grow_account, load_account_mut, load_ix_data, AccountDiscriminator,
+        ClientMultisig, Config, DataLen, Escrow, FreelancerBadge, MultisigApproval, ProgramAccount,
+        UserStats,
+    },
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MigrateAccount {}
+
+impl DataLen for MigrateAccount {
+    const LEN: usize = core::mem::size_of::<MigrateAccount>();
+}
+
+/// Upgrades a program account to the current layout of its type. Anyone may call it;
+/// `payer` covers the rent for any added space.
+pub fn migrate_account(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [payer, account, _system_program, _config_acc] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !payer.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let _ix_data = unsafe { load_ix_data::<MigrateAccount>(data)? };
+
+    if !account.is_owned_by(&crate::ID) {
+        return Err(BondrError::InvalidOwner.into());
+    }
+
+    let discriminator = *account
+        .try_borrow_data()?
+        .first()
+        .ok_or(ProgramError::InvalidAccountData)?;
+
+    match discriminator {
+        d if d == AccountDiscriminator::Config as u8 => migrate::<Config>(payer, account),
+        d if d == AccountDiscriminator::Escrow as u8 => migrate::<Escrow>(payer, account),
+        d if d == AccountDiscriminator::ClientMultisig as u8 => {
+            migrate::<ClientMultisig>(payer, account)
+        }
+        d if d == AccountDiscriminator::FreelancerBadge as u8 => {
+            migrate::<FreelancerBadge>(payer, account)
+        }
+        d if d == AccountDiscriminator::UserStats as u8 => migrate::<UserStats>(payer, account),
+        d if d == AccountDiscriminator::MultisigApproval as u8 => {
+            migrate::<MultisigApproval>(payer, account)
+        }
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+fn migrate<T: ProgramAccount>(payer: &AccountInfo, account: &AccountInfo) -> ProgramResult {
+    let from_version = account_version::<T>(account)?;
+    if from_version == T::VERSION && account.data_len() == T::LEN {
+        return Ok(());
+    }
+
+    if account.data_len() < T::LEN {
+        // zero-extends; the new fields are filled in by `ProgramAccount::migrate`
+        grow_account(account, payer, T::LEN)?;
+    }
+
+    // loading for write upgrades the data in place
+    unsafe { load_account_mut::<T>(account)? };
+
+    AccountMigrated {
+        account: *account.key(),
+        discriminator: T::DISCRIMINATOR as u8,
+        from_version,
+        to_version: T::VERSION,
+    }
+    .emit();
+
+    Ok(())
+}
@@ -1,12 +1,14 @@
-use mpl_core::instructions::CreateV2;
-use mpl_core::types::DataState;
 use pinocchio::{
-    account_info::AccountInfo, program::invoke, program_error::ProgramError, ProgramResult,
+    account_info::AccountInfo, instruction::Signer, program_error::ProgramError, seeds,
+    ProgramResult,
 };
 
-use crate::constants::*;
 use crate::errors::BondrError;
-use crate::states::{load_acc_mut_unchecked, utils::DataLen, FreelancerBadge};
+use crate::events::{Event, NftMinted};
+use crate::states::{
+    load_account, load_account_mut, utils::DataLen, Config, CreateCoreAssetV2, FreelancerBadge,
+    ReputationTier, MPL_CORE_ID,
+};
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -16,8 +18,9 @@ impl DataLen for MintReputationNft {
     const LEN: usize = core::mem::size_of::<MintReputationNft>();
 }
 
-pub fn mint_rep_nft(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [freelancer, badge_acc, asset, collection, mpl_core_program, system_program] = accounts
+pub fn mint_rep_nft(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [freelancer, badge_acc, asset, collection, config_acc, mpl_core_program, system_program] =
+        accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
@@ -26,71 +29,70 @@ pub fn mint_rep_nft(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    let badge = unsafe {
-        load_acc_mut_unchecked::<FreelancerBadge>(badge_acc.borrow_mut_data_unchecked())?
-    };
+    if *mpl_core_program.key() != MPL_CORE_ID {
+        return Err(BondrError::InvalidMplKey.into());
+    }
+
+    let badge = unsafe { load_account_mut::<FreelancerBadge>(badge_acc)? };
 
     // check freelancer matches badge
     if badge.freelancer != *freelancer.key() {
         return Err(BondrError::UnauthorizedSender.into());
     }
 
+    let config_state = load_account::<Config>(config_acc)?;
+
+    if config_state.settings.badge_collection != *collection.key() {
+        return Err(BondrError::InvalidMplKey.into());
+    }
+
     // tier calculation
-    let tier = match badge.completed_escrows {
-        0..=2 => return Err(BondrError::InsufficientEscrows.into()),
-        3..=9 => crate::states::ReputationTier::Verified,
-        10..=24 => crate::states::ReputationTier::Professional,
-        _ => crate::states::ReputationTier::Elite,
-    };
+    let tier = config_state.tier_for(badge.completed_escrows);
+
+    if tier == ReputationTier::Unranked {
+        return Err(BondrError::InsufficientEscrows.into());
+    }
 
     if badge.tier >= tier {
         return Err(BondrError::NFTAlreadyMinted.into());
     }
 
-    let (name, uri) = match tier {
-        crate::states::ReputationTier::Verified => ("Bondr Verified Badge", VERIFIED_METADATA_URI),
-        crate::states::ReputationTier::Professional => {
-            ("Bondr Professional Badge", PROFESSIONAL_METADATA_URI)
-        }
-        crate::states::ReputationTier::Elite => ("Bondr Elite Badge", ELITE_METADATA_URI),
-        _ => return Err(BondrError::InsufficientEscrows.into()),
+    let name: &[u8] = match tier {
+        ReputationTier::Verified => b"Bondr Verified Badge",
+        ReputationTier::Professional => b"Bondr Professional Badge",
+        ReputationTier::Elite => b"Bondr Elite Badge",
+        ReputationTier::Unranked => return Err(BondrError::InsufficientEscrows.into()),
     };
+    let uri = config_state
+        .tier_uri(tier)
+        .ok_or(BondrError::InsufficientEscrows)?;
 
-    // Build the CPI instruction manually
-    let ix = CreateV2 {
-        asset: *asset.key(),
-        collection: Some(*collection.key()),
-        authority: Some(*freelancer.key()),
-        payer: *freelancer.key(),
-        owner: Some(*freelancer.key()),
-        system_program: *system_program.key(),
-        update_authority: Some(*freelancer.key()),
-        log_wrapper: *mpl_core_program.key(),
-        name: name.to_string(),
-        uri: uri.to_string(),
-        data_state: DataState::AccountState,
+    // the config PDA is the collection's update authority
+    let bump_ref = &[config_state.bump];
+    let seeds_arr = seeds!(Config::SEED.as_bytes(), bump_ref);
+
+    CreateCoreAssetV2 {
+        asset,
+        collection,
+        authority: config_acc,
+        payer: freelancer,
+        owner: freelancer,
+        system_program,
+        mpl_core_program,
+        name,
+        uri,
     }
-    .instruction(&[
-        asset.clone(),
-        collection.clone(),
-        freelancer.clone(),
-        system_program.clone(),
-        mpl_core_program.clone(),
-    ]);
-
-    invoke(
-        &ix,
-        &[
-            asset.clone(),
-            collection.clone(),
-            freelancer.clone(),
-            system_program.clone(),
-            mpl_core_program.clone(),
-        ],
-    )?;
+    .invoke_signed(&[Signer::from(&seeds_arr)])?;
 
     // update badge tier
     badge.tier = tier;
 
+    NftMinted {
+        freelancer: *freelancer.key(),
+        asset: *asset.key(),
+        tier: tier as u8,
+    }
+    .emit();
+
     Ok(())
 }
@@ -1,24 +1,56 @@
 use pinocchio::program_error::ProgramError;
 
+pub mod add_multisig_member;
 pub mod approve_multisig_release;
+pub mod cancel_escrow;
+pub mod change_threshold;
+pub mod claim_milestone;
 pub mod claim_payment;
+pub mod idl;
+pub mod initialize_config;
 pub mod initialize_escrow;
 pub mod initialize_freelancer_badge;
 pub mod initialize_multisig_client;
+pub mod migrate_account;
 pub mod mint_reputation_nft;
+pub mod open_dispute;
+pub mod release_milestone;
 pub mod release_payment;
+pub mod remove_multisig_member;
+pub mod resolve_dispute;
+pub mod rotate_multisig_member;
+pub mod set_pause;
+pub mod transfer_admin;
+pub mod update_config;
 pub mod update_freelancer_badge;
+pub mod withdraw_treasury;
 
+pub use add_multisig_member::*;
 pub use approve_multisig_release::*;
+pub use cancel_escrow::*;
+pub use change_threshold::*;
+pub use claim_milestone::*;
 pub use claim_payment::*;
+pub use initialize_config::*;
 pub use initialize_escrow::*;
 pub use initialize_freelancer_badge::*;
 pub use initialize_multisig_client::*;
+pub use migrate_account::*;
 pub use mint_reputation_nft::*;
+pub use open_dispute::*;
+pub use release_milestone::*;
 pub use release_payment::*;
+pub use remove_multisig_member::*;
+pub use resolve_dispute::*;
+pub use rotate_multisig_member::*;
+pub use set_pause::*;
+pub use transfer_admin::*;
+pub use update_config::*;
 pub use update_freelancer_badge::*;
+pub use withdraw_treasury::*;
 
 #[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum BondrInstruction {
     InitializeEscrow,
     InitializeFreelancerBadge,
@@ -28,6 +60,58 @@ pub enum BondrInstruction {
     ApproveMultisigRelease,
     UpdateFreelancerBadge,
     MintReputationNft,
+    CancelEscrow,
+    ReleaseMilestone,
+    ClaimMilestone,
+    OpenDispute,
+    ResolveDispute,
+    InitializeConfig,
+    WithdrawTreasury,
+    UpdateConfig,
+    TransferAdmin,
+    SetPause,
+    MigrateAccount,
+    AddMultisigMember,
+    RemoveMultisigMember,
+    RotateMultisigMember,
+    ChangeThreshold,
+}
+
+impl BondrInstruction {
+    /// Instructions that stay available while the program is paused: refunds,
+    /// freezing funds in a dispute and settling it, and the admin's own controls.
+    pub fn is_pause_exempt(self) -> bool {
+        self.config_index().is_none()
+    }
+
+    /// Position of the config among the accounts of an instruction the pause applies to.
+    pub fn config_index(self) -> Option<usize> {
+        match self {
+            BondrInstruction::InitializeEscrow => Some(12),
+            BondrInstruction::InitializeFreelancerBadge => Some(4),
+            BondrInstruction::InitializeMultisigClient => Some(4),
+            BondrInstruction::ReleasePayment => Some(4),
+            BondrInstruction::ClaimPayment => Some(11),
+            BondrInstruction::ApproveMultisigRelease => Some(6),
+            BondrInstruction::UpdateFreelancerBadge => Some(2),
+            BondrInstruction::MintReputationNft => Some(4),
+            BondrInstruction::ReleaseMilestone => Some(4),
+            BondrInstruction::ClaimMilestone => Some(9),
+            BondrInstruction::MigrateAccount => Some(3),
+            BondrInstruction::AddMultisigMember
+            | BondrInstruction::RemoveMultisigMember
+            | BondrInstruction::RotateMultisigMember
+            | BondrInstruction::ChangeThreshold => Some(1),
+            BondrInstruction::CancelEscrow
+            | BondrInstruction::OpenDispute
+            | BondrInstruction::ResolveDispute
+            | BondrInstruction::InitializeConfig
+            | BondrInstruction::WithdrawTreasury
+            | BondrInstruction::UpdateConfig
+            | BondrInstruction::TransferAdmin
+            | BondrInstruction::SetPause => None,
+        }
+    }
 }
 
 impl TryFrom<&u8> for BondrInstruction {
@@ -43,6 +127,21 @@ impl TryFrom<&u8> for BondrInstruction {
             5 => Ok(BondrInstruction::ApproveMultisigRelease),
             6 => Ok(BondrInstruction::UpdateFreelancerBadge),
             7 => Ok(BondrInstruction::MintReputationNft),
+            8 => Ok(BondrInstruction::CancelEscrow),
+            9 => Ok(BondrInstruction::ReleaseMilestone),
+            10 => Ok(BondrInstruction::ClaimMilestone),
+            11 => Ok(BondrInstruction::OpenDispute),
+            12 => Ok(BondrInstruction::ResolveDispute),
+            13 => Ok(BondrInstruction::InitializeConfig),
+            14 => Ok(BondrInstruction::WithdrawTreasury),
+            15 => Ok(BondrInstruction::UpdateConfig),
+            16 => Ok(BondrInstruction::TransferAdmin),
+            17 => Ok(BondrInstruction::SetPause),
+            18 => Ok(BondrInstruction::MigrateAccount),
+            19 => Ok(BondrInstruction::AddMultisigMember),
+            20 => Ok(BondrInstruction::RemoveMultisigMember),
+            21 => Ok(BondrInstruction::RotateMultisigMember),
+            22 => Ok(BondrInstruction::ChangeThreshold),
             _ => Err(ProgramError::InvalidInstructionData),
         }
     }
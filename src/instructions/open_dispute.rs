@@ -0,0 +1,59 @@
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+
+use crate::{
+    errors::BondrError,
+    events::{DisputeOpened, Event},
+    states::{load_account_mut, load_ix_data, DataLen, Escrow},
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OpenDispute {}
+
+impl DataLen for OpenDispute {
+    const LEN: usize = core::mem::size_of::<OpenDispute>();
+}
+
+pub fn open_dispute(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [party, escrow_acc] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !party.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let _ix_data = unsafe { load_ix_data::<OpenDispute>(data)? };
+
+    let escrow_state = unsafe { load_account_mut::<Escrow>(escrow_acc)? };
+
+    // only the client or the freelancer can raise a dispute
+    if escrow_state.sender != *party.key() && escrow_state.receiver != *party.key() {
+        return Err(BondrError::UnauthorizedSender.into());
+    }
+
+    if escrow_state.arbiter == Pubkey::default() {
+        return Err(BondrError::NoArbiter.into());
+    }
+
+    if escrow_state.is_disputed {
+        return Err(BondrError::EscrowDisputed.into());
+    }
+
+    if !escrow_state.is_disputable() {
+        return Err(BondrError::AlreadyReleased.into());
+    }
+
+    // freezes release, claim and cancel until the arbiter resolves it
+    escrow_state.is_disputed = true;
+
+    DisputeOpened {
+        escrow: *escrow_acc.key(),
+        opened_by: *party.key(),
+    }
+    .emit();
+
+    Ok(())
+}
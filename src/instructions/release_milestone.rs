@@ -0,0 +1,78 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::{
+    errors::BondrError,
+    events::{Event, MilestoneReleased},
+    states::{load_account_mut, load_ix_data, DataLen, Escrow, MilestoneState, UserStats},
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct ReleaseMilestone {
+    pub index: u8,
+}
+
+impl DataLen for ReleaseMilestone {
+    const LEN: usize = core::mem::size_of::<ReleaseMilestone>();
+}
+
+pub fn release_milestone(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [client, escrow, sender_stats, _system_program, _config_acc] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let ix_data = unsafe { load_ix_data::<ReleaseMilestone>(data) }?;
+
+    if !client.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let escrow_state = unsafe { load_account_mut::<Escrow>(escrow)? };
+
+    if escrow_state.sender != *client.key() {
+        return Err(BondrError::UnauthorizedSender.into());
+    }
+
+    if escrow_state.is_disputed {
+        return Err(BondrError::EscrowDisputed.into());
+    }
+
+    if !escrow_state.is_milestone_escrow() {
+        return Err(BondrError::NotMilestoneEscrow.into());
+    }
+
+    if ix_data.index >= escrow_state.milestone_count {
+        return Err(BondrError::InvalidMilestone.into());
+    }
+
+    let milestone = &mut escrow_state.milestones[ix_data.index as usize];
+
+    if milestone.state != MilestoneState::Pending {
+        return Err(BondrError::AlreadyReleased.into());
+    }
+
+    milestone.state = MilestoneState::Released;
+
+    // the escrow counts as released once its last milestone is
+    if escrow_state
+        .active_milestones()
+        .iter()
+        .all(|m| m.state != MilestoneState::Pending)
+    {
+        let sender_stats = unsafe { UserStats::load_mut(sender_stats, client.key(), client)? };
+        sender_stats.record_released(escrow_state.created_at, Clock::get()?.unix_timestamp);
+    }
+
+    MilestoneReleased {
+        escrow: *escrow.key(),
+        index: ix_data.index,
+    }
+    .emit();
+
+    Ok(())
+}
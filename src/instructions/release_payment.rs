@@ -1,60 +1,69 @@
 use pinocchio::{
-    account_info::AccountInfo, program_error::ProgramError, pubkey::create_program_address,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
     ProgramResult,
 };
 
 use crate::{
     errors::BondrError,
-    states::{load_acc_mut_unchecked, load_ix_data, DataLen, Escrow},
+    events::{Event, PaymentReleased},
+    states::{load_account_mut, load_ix_data, DataLen, Escrow, UserStats},
 };
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq)]
-pub struct ReleasePayment {
-    reference_seed: u8,
-}
+pub struct ReleasePayment {}
 
 impl DataLen for ReleasePayment {
     const LEN: usize = core::mem::size_of::<ReleasePayment>();
 }
 
 pub fn release_payment(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [client, escrow] = accounts else {
+    let [client, escrow, sender_stats, _system_program, _config_acc] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
-    let ix_data = unsafe { load_ix_data::<ReleasePayment>(data) }?;
+    let _ix_data = unsafe { load_ix_data::<ReleasePayment>(data) }?;
 
     if !client.is_signer() {
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    let escrow_state =
-        unsafe { load_acc_mut_unchecked::<Escrow>(escrow.borrow_mut_data_unchecked())? };
-
-    let expected_pda = create_program_address(
-        &[
-            b"escrow".as_ref(),
-            escrow_state.sender.as_ref(),
-            escrow_state.receiver.as_ref(),
-            &[ix_data.reference_seed],
-        ],
-        &crate::ID,
-    )?;
-
-    if expected_pda != *escrow.key() {
-        return Err(BondrError::PdaMismatch.into());
-    }
+    let escrow_state = unsafe { load_account_mut::<Escrow>(escrow)? };
 
     if escrow_state.sender != *client.key() {
         return Err(BondrError::UnauthorizedSender.into());
     }
 
+    if escrow_state.is_disputed {
+        return Err(BondrError::EscrowDisputed.into());
+    }
+
+    // milestone escrows are released piece by piece through ReleaseMilestone
+    if escrow_state.is_milestone_escrow() {
+        return Err(BondrError::MilestoneEscrowNotSupported.into());
+    }
+
+    // multisig escrows are released by member approvals, not the creator alone
+    if escrow_state.has_multisig {
+        return Err(BondrError::MultisigThresholdNotMet.into());
+    }
+
     if escrow_state.is_released {
         return Err(BondrError::AlreadyReleased.into());
     }
 
     escrow_state.is_released = true;
 
+    let sender_stats = unsafe { UserStats::load_mut(sender_stats, client.key(), client)? };
+    sender_stats.record_released(escrow_state.created_at, Clock::get()?.unix_timestamp);
+
+    PaymentReleased {
+        escrow: *escrow.key(),
+        released_by: *client.key(),
+    }
+    .emit();
+
     Ok(())
 }
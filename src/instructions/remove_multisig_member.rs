@@ -0,0 +1,47 @@
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+
+use crate::{
+    errors::BondrError,
+    states::{load_account_mut, load_ix_data, ClientMultisig, DataLen},
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct RemoveMultisigMember {
+    pub member: Pubkey,
+}
+
+impl DataLen for RemoveMultisigMember {
+    const LEN: usize = core::mem::size_of::<RemoveMultisigMember>();
+}
+
+/// Removes a member, keeping the others in order. The threshold must still be reachable
+/// without them; lower it first with `ChangeThreshold` if it isn't.
+pub fn remove_multisig_member(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [multisig_acc, _config_acc, approvers @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let ix_data = unsafe { load_ix_data::<RemoveMultisigMember>(data)? };
+
+    let multisig = unsafe { load_account_mut::<ClientMultisig>(multisig_acc)? };
+    multisig.authorize_change(approvers)?;
+
+    let index = multisig
+        .member_index(&ix_data.member)
+        .ok_or(BondrError::NotMultisigMember)?;
+
+    if multisig.member_count - 1 < multisig.threshold {
+        return Err(BondrError::InvalidMultisigConfig.into());
+    }
+
+    let count = multisig.member_count as usize;
+    multisig.members.copy_within(index + 1..count, index);
+    multisig.members[count - 1] = Pubkey::default();
+    multisig.member_count -= 1;
+    multisig.emit_updated(multisig_acc.key(), Pubkey::default(), ix_data.member);
+
+    Ok(())
+}
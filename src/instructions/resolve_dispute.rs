@@ -0,0 +1,148 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::{
+    constants::BPS_DENOMINATOR,
+    errors::BondrError,
+    events::{DisputeResolved, Event},
+    states::{
+        close_escrow, escrow_balance, load_account, load_account_mut, load_ix_data,
+        pay_freelancer_from_escrow, pay_from_escrow, Config, DataLen, Escrow, EscrowPayout,
+        EscrowVault, FreelancerBadge, MultisigApproval, Treasury, UserStats,
+    },
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct ResolveDispute {
+    pub receiver_bps: u16, // share of the disputed funds awarded to the freelancer
+}
+
+impl DataLen for ResolveDispute {
+    const LEN: usize = core::mem::size_of::<ResolveDispute>();
+}
+
+pub fn resolve_dispute(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [arbiter, sender, receiver, escrow_acc, escrow_token_acc, sender_token_acc, receiver_token_acc, token_mint_acc, receiver_badge_acc, sender_stats_acc, multisig_acc, multisig_approval_acc, config_acc, treasury, treasury_token_acc, token_program, _system_program, extra_accounts @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !arbiter.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let ix_data = unsafe { load_ix_data::<ResolveDispute>(data)? };
+
+    if ix_data.receiver_bps > BPS_DENOMINATOR {
+        return Err(BondrError::InvalidBasisPoints.into());
+    }
+
+    let escrow_state = unsafe { load_account_mut::<Escrow>(escrow_acc)? };
+
+    if escrow_state.arbiter != *arbiter.key() {
+        return Err(BondrError::UnauthorizedArbiter.into());
+    }
+
+    if !escrow_state.is_disputed {
+        return Err(BondrError::NotDisputed.into());
+    }
+
+    if escrow_state.sender != *sender.key() {
+        return Err(BondrError::UnauthorizedSender.into());
+    }
+
+    if escrow_state.receiver != *receiver.key() {
+        return Err(BondrError::UnauthorizedReceiver.into());
+    }
+
+    let vault = EscrowVault {
+        escrow_acc,
+        escrow_token_acc,
+        mint: token_mint_acc,
+        token_program,
+        extra_accounts,
+    };
+    vault.validate(escrow_state)?;
+
+    // released milestones already belong to the freelancer; only the rest is disputed
+    let vault_balance = escrow_balance(&vault, escrow_state)?;
+    let released = escrow_state.released_unclaimed_amount().min(vault_balance);
+    let disputed = vault_balance - released;
+
+    let awarded =
+        ((disputed as u128 * ix_data.receiver_bps as u128) / BPS_DENOMINATOR as u128) as u64;
+    let sender_amount = disputed - awarded;
+
+    // released milestones are paid as if claimed, with the protocol fee
+    let treasury = Treasury {
+        config: load_account::<Config>(config_acc)?,
+        treasury,
+        treasury_token_acc,
+    };
+    let tier = FreelancerBadge::tier_of(receiver_badge_acc, receiver.key())?;
+    let fee = pay_freelancer_from_escrow(
+        &vault,
+        escrow_state,
+        &treasury,
+        tier,
+        EscrowPayout {
+            wallet: receiver,
+            token_account: receiver_token_acc,
+            amount: released,
+        },
+    )?;
+    let receiver_amount = released - fee + awarded;
+
+    pay_from_escrow(
+        &vault,
+        escrow_state,
+        &[
+            EscrowPayout {
+                wallet: receiver,
+                token_account: receiver_token_acc,
+                amount: awarded,
+            },
+            EscrowPayout {
+                wallet: sender,
+                token_account: sender_token_acc,
+                amount: sender_amount,
+            },
+        ],
+    )?;
+
+    // the freelancer wins if awarded at least half of the disputed funds
+    let receiver_won = ix_data.receiver_bps as u32 * 2 >= BPS_DENOMINATOR as u32;
+
+    FreelancerBadge::record_dispute(receiver_badge_acc, receiver.key(), receiver_won)?;
+
+    // the client's record always counts, so a lost dispute can't be left off it
+    let sender_stats = unsafe { UserStats::load_mut(sender_stats_acc, sender.key(), arbiter)? };
+    if receiver_won {
+        sender_stats.disputes_lost = sender_stats.disputes_lost.saturating_add(1);
+    } else {
+        sender_stats.disputes_won = sender_stats.disputes_won.saturating_add(1);
+    }
+
+    unsafe {
+        MultisigApproval::close(
+            multisig_approval_acc,
+            multisig_acc,
+            escrow_state,
+            escrow_acc.key(),
+            sender,
+        )?
+    };
+    close_escrow(&vault, escrow_state, sender)?;
+
+    DisputeResolved {
+        escrow: *escrow_acc.key(),
+        arbiter: *arbiter.key(),
+        receiver_amount,
+        sender_amount,
+        receiver_bps: ix_data.receiver_bps,
+    }
+    .emit();
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+
+use crate::{
+    errors::BondrError,
+    states::{load_account_mut, load_ix_data, ClientMultisig, DataLen},
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct RotateMultisigMember {
+    pub old_member: Pubkey,
+    pub new_member: Pubkey,
+}
+
+impl DataLen for RotateMultisigMember {
+    const LEN: usize = core::mem::size_of::<RotateMultisigMember>();
+}
+
+/// Replaces a member's key in place, e.g. after a lost or leaked key.
+pub fn rotate_multisig_member(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [multisig_acc, _config_acc, approvers @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let ix_data = unsafe { load_ix_data::<RotateMultisigMember>(data)? };
+
+    let multisig = unsafe { load_account_mut::<ClientMultisig>(multisig_acc)? };
+    multisig.authorize_change(approvers)?;
+
+    let index = multisig
+        .member_index(&ix_data.old_member)
+        .ok_or(BondrError::NotMultisigMember)?;
+
+    if multisig.member_index(&ix_data.new_member).is_some() {
+        return Err(BondrError::DuplicateMember.into());
+    }
+
+    multisig.members[index] = ix_data.new_member;
+    multisig.emit_updated(multisig_acc.key(), ix_data.new_member, ix_data.old_member);
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+
+use crate::{
+    errors::BondrError,
+    events::{Event, PauseUpdated},
+    states::{load_account_mut, load_ix_data_with_bools, Config, DataLen},
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct SetPause {
+    pub paused_instructions: u32,
+    pub paused: bool,
+    pub _padding: [u8; 3],
+}
+
+impl DataLen for SetPause {
+    const LEN: usize = core::mem::size_of::<SetPause>();
+}
+
+/// Replaces the pause state. The guardian can only pause further; lifting a pause takes the admin.
+pub fn set_pause(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [authority, config_acc] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let ix_data = unsafe {
+        load_ix_data_with_bools::<SetPause>(data, &[core::mem::offset_of!(SetPause, paused)])?
+    };
+
+    let config_state = unsafe { load_account_mut::<Config>(config_acc)? };
+
+    if config_state.admin != *authority.key() {
+        let is_guardian = config_state.settings.guardian == *authority.key()
+            && config_state.settings.guardian != Pubkey::default();
+
+        if !is_guardian {
+            return Err(BondrError::UnauthorizedAdmin.into());
+        }
+
+        let unpauses = config_state.paused && !ix_data.paused;
+        let unpauses_instructions =
+            config_state.paused_instructions & !ix_data.paused_instructions != 0;
+
+        if unpauses || unpauses_instructions {
+            return Err(BondrError::UnauthorizedAdmin.into());
+        }
+    }
+
+    config_state.paused = ix_data.paused;
+    config_state.paused_instructions = ix_data.paused_instructions;
+
+    PauseUpdated {
+        authority: *authority.key(),
+        paused_instructions: ix_data.paused_instructions,
+        paused: ix_data.paused,
+    }
+    .emit();
+
+    Ok(())
+}
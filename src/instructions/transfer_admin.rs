@@ -0,0 +1,44 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::{
+    errors::BondrError,
+    events::{ConfigUpdated, Event},
+    states::{load_account_mut, load_ix_data, Config, DataLen},
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransferAdmin {}
+
+impl DataLen for TransferAdmin {
+    const LEN: usize = core::mem::size_of::<TransferAdmin>();
+}
+
+pub fn transfer_admin(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [admin, new_admin, config_acc] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // the new admin signs too, so the role can't be handed to a key nobody controls
+    if !admin.is_signer() || !new_admin.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let _ix_data = unsafe { load_ix_data::<TransferAdmin>(data)? };
+
+    let config_state = unsafe { load_account_mut::<Config>(config_acc)? };
+
+    if config_state.admin != *admin.key() {
+        return Err(BondrError::UnauthorizedAdmin.into());
+    }
+
+    config_state.admin = *new_admin.key();
+
+    ConfigUpdated {
+        admin: config_state.admin,
+        fee_bps: config_state.settings.fee_bps,
+    }
+    .emit();
+
+    Ok(())
+}
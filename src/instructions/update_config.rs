@@ -0,0 +1,50 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::{
+    errors::BondrError,
+    events::{ConfigUpdated, Event},
+    states::{load_account_mut, load_ix_data, Config, ConfigSettings, DataLen},
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct UpdateConfig {
+    pub settings: ConfigSettings,
+    pub _padding: [u8; 4],
+    pub min_reputation_amounts: [u64; 8], // MAX_ALLOWED_MINTS
+}
+
+impl DataLen for UpdateConfig {
+    const LEN: usize = core::mem::size_of::<UpdateConfig>();
+}
+
+pub fn update_config(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [admin, config_acc] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !admin.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let ix_data = unsafe { load_ix_data::<UpdateConfig>(data)? };
+
+    let config_state = unsafe { load_account_mut::<Config>(config_acc)? };
+
+    if config_state.admin != *admin.key() {
+        return Err(BondrError::UnauthorizedAdmin.into());
+    }
+
+    ix_data.settings.validate()?;
+
+    config_state.settings = ix_data.settings;
+    config_state.min_reputation_amounts = ix_data.min_reputation_amounts;
+
+    ConfigUpdated {
+        admin: config_state.admin,
+        fee_bps: config_state.settings.fee_bps,
+    }
+    .emit();
+
+    Ok(())
+}
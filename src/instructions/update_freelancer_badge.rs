@@ -2,43 +2,45 @@ use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramR
 
 use crate::{
     errors::BondrError,
-    states::{load_acc_mut_unchecked, load_ix_data, DataLen, FreelancerBadge},
+    states::{load_account, load_account_mut, load_ix_data, Config, DataLen, FreelancerBadge},
 };
 
 #[repr(C)]
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
 pub struct UpdateFreelancerBadge {
-    value: u64,
+    pub value: u64,
 }
 
 impl DataLen for UpdateFreelancerBadge {
     const LEN: usize = core::mem::size_of::<UpdateFreelancerBadge>();
 }
 
+/// Admin correction for a badge; settled escrows are credited when the freelancer claims them.
 pub fn update_freelancer_badge(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [freelancer, badge] = accounts else {
+    let [admin, badge, config_acc] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
-    if !freelancer.is_signer() {
+    if !admin.is_signer() {
         return Err(ProgramError::MissingRequiredSignature);
     }
 
+    if load_account::<Config>(config_acc)?.admin != *admin.key() {
+        return Err(BondrError::UnauthorizedAdmin.into());
+    }
+
     // deserialize instruction data
     let ix = unsafe { load_ix_data::<UpdateFreelancerBadge>(data)? };
 
-    let badge =
-        unsafe { load_acc_mut_unchecked::<FreelancerBadge>(badge.borrow_mut_data_unchecked())? };
+    let badge_state = unsafe { load_account_mut::<FreelancerBadge>(badge)? };
 
-    if badge.freelancer != *freelancer.key() {
-        return Err(BondrError::UnauthorizedSender.into());
-    }
     if ix.value == 0 {
         return Err(BondrError::InvalidAmountZero.into());
     }
 
-    badge.completed_escrows = badge.completed_escrows.saturating_add(1);
-    badge.total_value_completed = badge.total_value_completed.saturating_add(ix.value);
+    badge_state.completed_escrows = badge_state.completed_escrows.saturating_add(1);
+    badge_state.total_value_completed = badge_state.total_value_completed.saturating_add(ix.value);
+    badge_state.emit_updated(badge.key());
 
     Ok(())
 }
@@ -0,0 +1,115 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::Signer,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    seeds,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use pinocchio_system::instructions::Transfer;
+
+use crate::{
+    errors::BondrError,
+    events::{Event, TreasuryWithdrawn},
+    states::{
+        load_account, load_ix_data, load_token_account, mint_decimals, validate_token_program,
+        Config, DataLen, TokenTransfer,
+    },
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct WithdrawTreasury {
+    pub amount: u64,
+}
+
+impl DataLen for WithdrawTreasury {
+    const LEN: usize = core::mem::size_of::<WithdrawTreasury>();
+}
+
+/// Moves collected fees out of the treasury. Passing the system program as the mint
+/// withdraws native SOL to `destination`; otherwise tokens go to `destination_token_acc`.
+pub fn withdraw_treasury(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [admin, config_acc, treasury, destination, treasury_token_acc, destination_token_acc, token_mint_acc, token_program, _system_program, extra_accounts @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !admin.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let ix_data = unsafe { load_ix_data::<WithdrawTreasury>(data)? };
+
+    let config_state = load_account::<Config>(config_acc)?;
+
+    if config_state.admin != *admin.key() {
+        return Err(BondrError::UnauthorizedAdmin.into());
+    }
+
+    Config::validate_treasury_pda(config_state.treasury_bump, treasury.key())?;
+
+    let bump_ref = &[config_state.treasury_bump];
+    let seeds_arr = seeds!(Config::TREASURY_SEED.as_bytes(), bump_ref);
+    let signers = [Signer::from(&seeds_arr)];
+
+    let is_native = *token_mint_acc.key() == Pubkey::default();
+
+    if is_native {
+        // the treasury has to stay rent exempt
+        let available = treasury
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(0));
+
+        if ix_data.amount > available {
+            return Err(BondrError::InsufficientBalance.into());
+        }
+
+        Transfer {
+            from: treasury,
+            to: destination,
+            lamports: ix_data.amount,
+        }
+        .invoke_signed(&signers)?;
+    } else {
+        validate_token_program(token_program, token_mint_acc)?;
+
+        let treasury_token = load_token_account(treasury_token_acc, token_program.key())?;
+
+        if treasury_token.owner() != treasury.key() {
+            return Err(BondrError::InvalidTreasury.into());
+        }
+        if treasury_token.amount() < ix_data.amount {
+            return Err(BondrError::InsufficientBalance.into());
+        }
+        drop(treasury_token);
+
+        TokenTransfer {
+            from: treasury_token_acc,
+            mint: token_mint_acc,
+            to: destination_token_acc,
+            authority: treasury,
+            token_program,
+            extra_accounts,
+            amount: ix_data.amount,
+            decimals: mint_decimals(token_mint_acc)?,
+        }
+        .invoke_signed(&signers)?;
+    }
+
+    TreasuryWithdrawn {
+        mint: *token_mint_acc.key(),
+        destination: if is_native {
+            *destination.key()
+        } else {
+            *destination_token_acc.key()
+        },
+        amount: ix_data.amount,
+    }
+    .emit();
+
+    Ok(())
+}
@@ -6,8 +6,13 @@ mod entrypoint;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(feature = "client")]
+pub mod client;
 pub mod constants;
+#[cfg(feature = "std")]
+pub mod decoder;
 pub mod errors;
+pub mod events;
 pub mod instructions;
 pub mod states;
 
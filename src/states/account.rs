@@ -0,0 +1,136 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::{errors::BondrError, states::DataLen};
+
+/// Leading byte of every program account, identifying its type.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AccountDiscriminator {
+    Uninitialized = 0,
+    Config = 1,
+    Escrow = 2,
+    ClientMultisig = 3,
+    FreelancerBadge = 4,
+    UserStats = 5,
+    MultisigApproval = 6,
+}
+
+/// Every account starts with `[discriminator, version]`.
+pub const ACCOUNT_HEADER_LEN: usize = 2;
+
+/// A `#[repr(C)]` account owned by this program, starting with the account header.
+///
+/// Layouts are append-only: a new version may only add fields at the end, so an
+/// older account is a prefix of the current layout. Older accounts of the current
+/// size load as-is and are upgraded on their next write; accounts that need to grow
+/// go through `MigrateAccount` first, except `UserStats`, which grow as they load.
+pub trait ProgramAccount: DataLen + Sized {
+    const DISCRIMINATOR: AccountDiscriminator;
+    const VERSION: u8;
+
+    /// Re-derives the account's address from the seeds and bump stored in it.
+    fn validate_address(&self, key: &Pubkey) -> Result<(), ProgramError>;
+
+    /// Fills in fields added after `from_version`; appended bytes start out zeroed.
+    fn migrate(&mut self, _from_version: u8) {}
+}
+
+/// Checks owner, header and size, returning the stored layout version.
+fn check_account<T: ProgramAccount>(account: &AccountInfo) -> Result<u8, ProgramError> {
+    if !account.is_owned_by(&crate::ID) {
+        return Err(BondrError::InvalidOwner.into());
+    }
+
+    let version = account_version::<T>(account)?;
+
+    if account.data_len() < T::LEN {
+        return Err(BondrError::AccountNeedsMigration.into());
+    }
+    if account.data_len() != T::LEN {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(version)
+}
+
+/// Reads the header of a `T` account of any supported version.
+pub fn account_version<T: ProgramAccount>(account: &AccountInfo) -> Result<u8, ProgramError> {
+    let data = unsafe { account.borrow_data_unchecked() };
+
+    if data.len() < ACCOUNT_HEADER_LEN || data[0] != T::DISCRIMINATOR as u8 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if data[1] == 0 || data[1] > T::VERSION {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(data[1])
+}
+
+/// Loads an initialized `T`: owner, size, header and address are all checked.
+pub fn load_account<T: ProgramAccount>(account: &AccountInfo) -> Result<&T, ProgramError> {
+    check_account::<T>(account)?;
+
+    let state = unsafe { &*(account.borrow_data_unchecked().as_ptr() as *const T) };
+    state.validate_address(account.key())?;
+
+    Ok(state)
+}
+
+/// Like [`load_account`], for an account the instruction writes to.
+///
+/// # Safety
+/// The data is borrowed without the runtime borrow flag; the caller must not hold
+/// any other reference to the same account's data.
+#[allow(clippy::mut_from_ref)]
+pub unsafe fn load_account_mut<T: ProgramAccount>(
+    account: &AccountInfo,
+) -> Result<&mut T, ProgramError> {
+    if !account.is_writable() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let version = check_account::<T>(account)?;
+
+    let state = &mut *(account.borrow_mut_data_unchecked().as_mut_ptr() as *mut T);
+    state.validate_address(account.key())?;
+
+    if version < T::VERSION {
+        state.migrate(version);
+        // header byte 1 is the version
+        *(state as *mut T as *mut u8).add(1) = T::VERSION;
+    }
+
+    Ok(state)
+}
+
+/// Loads an optional account: `None` if it was never created.
+///
+/// # Safety
+/// Same as [`load_account_mut`].
+#[allow(clippy::mut_from_ref)]
+pub unsafe fn load_optional_account_mut<T: ProgramAccount>(
+    account: &AccountInfo,
+) -> Result<Option<&mut T>, ProgramError> {
+    if account.data_is_empty() {
+        return Ok(None);
+    }
+    load_account_mut::<T>(account).map(Some)
+}
+
+/// Hands out a freshly created `T` for its first write; the caller validates the address.
+///
+/// # Safety
+/// Same as [`load_account_mut`].
+#[allow(clippy::mut_from_ref)]
+pub unsafe fn init_account<T: ProgramAccount>(
+    account: &AccountInfo,
+) -> Result<&mut T, ProgramError> {
+    if !account.is_owned_by(&crate::ID) || account.data_len() != T::LEN {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let data = account.borrow_mut_data_unchecked();
+    if data[0] != AccountDiscriminator::Uninitialized as u8 {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    Ok(&mut *(data.as_mut_ptr() as *mut T))
+}
@@ -1,18 +1,113 @@
-use pinocchio::pubkey::Pubkey;
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::{create_program_address, Pubkey},
+};
 
-use crate::{constants::MAX_MULTISIG_MEMBERS, states::DataLen};
+use crate::{
+    constants::MAX_MULTISIG_MEMBERS,
+    errors::BondrError,
+    events::{Event, MultisigUpdated},
+    states::{AccountDiscriminator, DataLen, ProgramAccount},
+};
 
 #[repr(C)]
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankAccount)]
 pub struct ClientMultisig {
-    pub members: [Pubkey; MAX_MULTISIG_MEMBERS], // max 5 members allowed as per now
-    pub member_count: u8,                        // how many active entries in `members`
-    pub threshold: u8,                           // approvals required
-    pub approvals: [u8; MAX_MULTISIG_MEMBERS], // 0 = not approved, 1 = approved; parallel to members
-    pub pending_escrow: Pubkey,                // escrow PDA tied to this multisig
+    pub discriminator: u8,
+    pub version: u8,
+    pub members: [Pubkey; 5], // MAX_MULTISIG_MEMBERS
+    pub member_count: u8,     // how many active entries in `members`
+    pub threshold: u8,        // approvals required
+    pub open_approvals: u16,  // escrows with a `MultisigApproval` open; members are fixed until 0
+    // held the approvals of a single pending escrow, now kept per escrow in `MultisigApproval`
+    #[padding]
+    pub _padding0: [u8; 35],
     pub bump: u8,
+    pub client: Pubkey, // creator, part of the PDA seeds
 }
 
+// shank only reads literal array lengths
+const _: () = assert!(MAX_MULTISIG_MEMBERS == 5);
+
 impl DataLen for ClientMultisig {
     const LEN: usize = core::mem::size_of::<ClientMultisig>();
 }
+
+impl ClientMultisig {
+    pub const SEED: &'static str = "client_multisig";
+
+    pub fn validate_pda(bump: u8, pda: &Pubkey, client: &Pubkey) -> Result<(), ProgramError> {
+        let seeds = &[Self::SEED.as_bytes(), client.as_ref(), &[bump]];
+        let derived = create_program_address(seeds, &crate::ID)?;
+
+        if derived != *pda {
+            return Err(BondrError::PdaMismatch.into());
+        }
+        Ok(())
+    }
+
+    pub fn member_index(&self, member: &Pubkey) -> Option<usize> {
+        self.members[..self.member_count as usize]
+            .iter()
+            .position(|m| m == member)
+    }
+
+    /// Checks that a membership change is signed by `threshold` distinct current
+    /// members, passed as `approvers`, and that none of the multisig's escrows is still
+    /// open: their approvals were given under the current members and threshold.
+    pub fn authorize_change(&self, approvers: &[AccountInfo]) -> Result<(), ProgramError> {
+        if self.open_approvals > 0 {
+            return Err(BondrError::MultisigBusy.into());
+        }
+
+        let mut approved = [false; MAX_MULTISIG_MEMBERS];
+        for approver in approvers {
+            if !approver.is_signer() {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let index = self
+                .member_index(approver.key())
+                .ok_or(BondrError::NotMultisigMember)?;
+            if approved[index] {
+                return Err(BondrError::AlreadyApproved.into());
+            }
+            approved[index] = true;
+        }
+
+        let approvals = approved.iter().filter(|&&a| a).count() as u8;
+        if approvals < self.threshold {
+            return Err(BondrError::MultisigThresholdNotMet.into());
+        }
+        Ok(())
+    }
+
+    pub fn emit_updated(&self, multisig: &Pubkey, added: Pubkey, removed: Pubkey) {
+        MultisigUpdated {
+            multisig: *multisig,
+            added,
+            removed,
+            member_count: self.member_count,
+            threshold: self.threshold,
+        }
+        .emit();
+    }
+}
+
+impl ProgramAccount for ClientMultisig {
+    const DISCRIMINATOR: AccountDiscriminator = AccountDiscriminator::ClientMultisig;
+    // version 2 moved the approvals out to `MultisigApproval`
+    const VERSION: u8 = 2;
+
+    fn validate_address(&self, key: &Pubkey) -> Result<(), ProgramError> {
+        Self::validate_pda(self.bump, key, &self.client)
+    }
+
+    fn migrate(&mut self, from_version: u8) {
+        if from_version < 2 {
+            // drop the approvals and pending escrow version 1 kept here
+            self.open_approvals = 0;
+            self._padding0 = [0; 35];
+        }
+    }
+}
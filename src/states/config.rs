@@ -0,0 +1,190 @@
+use pinocchio::{
+    program_error::ProgramError,
+    pubkey::{create_program_address, try_find_program_address, Pubkey},
+};
+
+use crate::{
+    constants::{
+        BPS_DENOMINATOR, MAX_ALLOWED_MINTS, MAX_METADATA_URI_LEN, MAX_MULTISIG_MEMBERS,
+        MAX_PROTOCOL_FEE_BPS,
+    },
+    errors::BondrError,
+    states::{AccountDiscriminator, DataLen, ProgramAccount, ReputationTier},
+};
+
+/// The admin-tunable part of the config, shared by `InitializeConfig` and `UpdateConfig`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+pub struct ConfigSettings {
+    pub guardian: Pubkey, // may pause the program; Pubkey::default() = admin only
+    pub fee_bps: u16,     // protocol fee taken from freelancer payouts
+    pub tier_fee_discount_bps: [u16; 4], // share of the fee waived, indexed by ReputationTier
+    pub _padding0: [u8; 2],
+    pub tier_thresholds: [u32; 3], // completed escrows for Verified, Professional, Elite
+    pub badge_collection: Pubkey,  // mpl-core collection badge NFTs are minted into
+    pub allowed_mints: [Pubkey; 8], // MAX_ALLOWED_MINTS; Pubkey::default() = native SOL
+    pub allowed_mint_count: u8,    // 0 = any mint may be escrowed
+    pub max_multisig_members: u8,
+    pub tier_uris: [[u8; 128]; 3], // MAX_METADATA_URI_LEN each, zero-padded, indexed like tier_thresholds
+    pub _padding1: [u8; 2],
+}
+
+// shank only reads literal array lengths
+const _: () = assert!(MAX_ALLOWED_MINTS == 8 && MAX_METADATA_URI_LEN == 128);
+
+impl ConfigSettings {
+    pub fn validate(&self) -> Result<(), ProgramError> {
+        if self.fee_bps > MAX_PROTOCOL_FEE_BPS
+            || self
+                .tier_fee_discount_bps
+                .iter()
+                .any(|&bps| bps > BPS_DENOMINATOR)
+        {
+            return Err(BondrError::InvalidFee.into());
+        }
+
+        let [verified, professional, elite] = self.tier_thresholds;
+        if verified == 0 || verified >= professional || professional >= elite {
+            return Err(BondrError::InvalidConfig.into());
+        }
+
+        if self.allowed_mint_count as usize > MAX_ALLOWED_MINTS
+            || self.max_multisig_members == 0
+            || self.max_multisig_members as usize > MAX_MULTISIG_MEMBERS
+        {
+            return Err(BondrError::InvalidConfig.into());
+        }
+
+        if self.tier_uris.iter().any(|uri| uri[0] == 0) {
+            return Err(BondrError::InvalidConfig.into());
+        }
+        Ok(())
+    }
+}
+
+/// Program-wide settings, stored in a single PDA at `["config"]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankAccount)]
+pub struct Config {
+    pub discriminator: u8,
+    pub version: u8,
+    pub admin: Pubkey,
+    #[padding]
+    pub _padding0: [u8; 2],
+    pub settings: ConfigSettings,
+    pub paused_instructions: u32, // bit n pauses BondrInstruction n
+    pub paused: bool,             // pauses every instruction that isn't pause-exempt
+    pub bump: u8,
+    pub treasury_bump: u8,
+    #[padding]
+    pub _padding1: [u8; 1],
+    pub min_reputation_amounts: [u64; 8], // MAX_ALLOWED_MINTS, indexed like allowed_mints
+}
+
+impl DataLen for Config {
+    const LEN: usize = core::mem::size_of::<Config>();
+}
+
+impl Config {
+    pub const SEED: &'static str = "config";
+    pub const TREASURY_SEED: &'static str = "treasury";
+
+    pub fn validate_pda(bump: u8, pda: &Pubkey) -> Result<(), ProgramError> {
+        let seeds = &[Self::SEED.as_bytes(), &[bump]];
+        let derived = create_program_address(seeds, &crate::ID)?;
+
+        if derived != *pda {
+            return Err(BondrError::PdaMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// Like [`Config::validate_pda`], also requiring the canonical bump. Checked when the
+    /// config is created so that it can only exist at one address.
+    pub fn validate_canonical_pda(bump: u8, pda: &Pubkey) -> Result<(), ProgramError> {
+        let (derived, canonical) = try_find_program_address(&[Self::SEED.as_bytes()], &crate::ID)
+            .ok_or(BondrError::PdaMismatch)?;
+
+        if derived != *pda || bump != canonical {
+            return Err(BondrError::PdaMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// The treasury PDA owns the fee token accounts and holds native SOL fees itself.
+    pub fn validate_treasury_pda(bump: u8, pda: &Pubkey) -> Result<(), ProgramError> {
+        let seeds = &[Self::TREASURY_SEED.as_bytes(), &[bump]];
+        let derived = create_program_address(seeds, &crate::ID)?;
+
+        if derived != *pda {
+            return Err(BondrError::InvalidTreasury.into());
+        }
+        Ok(())
+    }
+
+    /// Protocol fee on a payout of `amount` to a freelancer of the given tier (rounded down).
+    pub fn protocol_fee(&self, amount: u64, tier: ReputationTier) -> u64 {
+        let denominator = BPS_DENOMINATOR as u128;
+        let fee = amount as u128 * self.settings.fee_bps as u128 / denominator;
+        let discount =
+            fee * self.settings.tier_fee_discount_bps[tier as usize] as u128 / denominator;
+
+        (fee - discount) as u64
+    }
+
+    pub fn is_paused(&self, instruction: u8) -> bool {
+        self.paused || self.paused_instructions & (1u32 << instruction) != 0
+    }
+
+    pub fn is_mint_allowed(&self, mint: &Pubkey) -> bool {
+        let count = self.settings.allowed_mint_count as usize;
+
+        count == 0 || self.settings.allowed_mints[..count].contains(mint)
+    }
+
+    /// Only escrows of at least the mint's minimum in explicitly allowed mints build
+    /// reputation, so neither throwaway tokens nor dust escrows farm badge tiers.
+    pub fn counts_toward_reputation(&self, mint: &Pubkey, amount: u64) -> bool {
+        let count = self.settings.allowed_mint_count as usize;
+
+        self.settings.allowed_mints[..count]
+            .iter()
+            .position(|allowed| allowed == mint)
+            .is_some_and(|index| amount >= self.min_reputation_amounts[index])
+    }
+
+    /// Highest tier reached with `completed_escrows` completed escrows.
+    pub fn tier_for(&self, completed_escrows: u32) -> ReputationTier {
+        let [verified, professional, elite] = self.settings.tier_thresholds;
+
+        match completed_escrows {
+            n if n >= elite => ReputationTier::Elite,
+            n if n >= professional => ReputationTier::Professional,
+            n if n >= verified => ReputationTier::Verified,
+            _ => ReputationTier::Unranked,
+        }
+    }
+
+    /// Badge metadata URI for a ranked tier.
+    pub fn tier_uri(&self, tier: ReputationTier) -> Option<&[u8]> {
+        let uri = match tier {
+            ReputationTier::Unranked => return None,
+            ReputationTier::Verified => &self.settings.tier_uris[0],
+            ReputationTier::Professional => &self.settings.tier_uris[1],
+            ReputationTier::Elite => &self.settings.tier_uris[2],
+        };
+        let len = uri.iter().position(|&b| b == 0).unwrap_or(uri.len());
+
+        Some(&uri[..len])
+    }
+}
+
+impl ProgramAccount for Config {
+    const DISCRIMINATOR: AccountDiscriminator = AccountDiscriminator::Config;
+    // version 2 added `min_reputation_amounts`, zero (no minimum) until the admin sets them
+    const VERSION: u8 = 2;
+
+    fn validate_address(&self, key: &Pubkey) -> Result<(), ProgramError> {
+        Self::validate_pda(self.bump, key)
+    }
+}
@@ -0,0 +1,83 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::slice_invoke_signed,
+    instruction::{AccountMeta, Instruction, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
+
+use crate::constants::MAX_METADATA_URI_LEN;
+
+pub const MPL_CORE_ID: Pubkey =
+    pinocchio_pubkey::pubkey!("CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d");
+
+const CREATE_V2_DISCRIMINATOR: u8 = 20;
+const MAX_NAME_LEN: usize = 32;
+
+/// mpl-core `CreateV2` minting an asset into a collection, signed by the collection's
+/// update authority. Unused optional accounts are filled with the mpl-core program.
+pub struct CreateCoreAssetV2<'a> {
+    pub asset: &'a AccountInfo,
+    pub collection: &'a AccountInfo,
+    pub authority: &'a AccountInfo,
+    pub payer: &'a AccountInfo,
+    pub owner: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub mpl_core_program: &'a AccountInfo,
+    pub name: &'a [u8],
+    pub uri: &'a [u8],
+}
+
+impl CreateCoreAssetV2<'_> {
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        if self.name.len() > MAX_NAME_LEN || self.uri.len() > MAX_METADATA_URI_LEN {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let account_metas = [
+            AccountMeta::writable_signer(self.asset.key()),
+            AccountMeta::writable(self.collection.key()),
+            AccountMeta::readonly_signer(self.authority.key()),
+            AccountMeta::writable_signer(self.payer.key()),
+            AccountMeta::readonly(self.owner.key()),
+            AccountMeta::readonly(self.mpl_core_program.key()), // update authority: the collection's
+            AccountMeta::readonly(self.system_program.key()),
+            AccountMeta::readonly(self.mpl_core_program.key()), // no log wrapper
+        ];
+
+        // borsh: discriminator, DataState::AccountState, name, uri, no plugins, no external plugins
+        let mut instruction_data = [0u8; 2 + 4 + MAX_NAME_LEN + 4 + MAX_METADATA_URI_LEN + 2];
+        instruction_data[0] = CREATE_V2_DISCRIMINATOR;
+        let mut len = 2;
+
+        for field in [self.name, self.uri] {
+            instruction_data[len..len + 4].copy_from_slice(&(field.len() as u32).to_le_bytes());
+            len += 4;
+            instruction_data[len..len + field.len()].copy_from_slice(field);
+            len += field.len();
+        }
+        len += 2;
+
+        let instruction = Instruction {
+            program_id: &MPL_CORE_ID,
+            accounts: &account_metas,
+            data: &instruction_data[..len],
+        };
+
+        slice_invoke_signed(
+            &instruction,
+            &[
+                self.asset,
+                self.collection,
+                self.authority,
+                self.payer,
+                self.owner,
+                self.mpl_core_program,
+                self.system_program,
+                self.mpl_core_program,
+            ],
+            signers,
+        )
+    }
+}
@@ -1,27 +1,52 @@
 use pinocchio::{
-    account_info::AccountInfo,
     program_error::ProgramError,
     pubkey::{create_program_address, Pubkey},
-    ProgramResult,
 };
 
 use crate::{
+    constants::MAX_MILESTONES,
     errors::BondrError,
-    states::{load_acc_mut_unchecked, DataLen},
+    states::{AccountDiscriminator, DataLen, Milestone, MilestoneState, ProgramAccount},
 };
 
 #[repr(C)]
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankAccount)]
 pub struct Escrow {
+    pub discriminator: u8,
+    pub version: u8,
     pub sender: Pubkey,
     pub receiver: Pubkey,
+    pub mint: Pubkey,          // mint held by the escrow vault
+    pub token_program: Pubkey, // SPL Token or Token-2022, whichever owns `mint`
+    #[padding]
+    pub _padding0: [u8; 6],
     pub amount: u64,
+    pub reference_seed: u64, // job/reference id, lets a pair run several escrows at once
     pub is_released: bool,
     pub bump: u8,
     pub client_multisig: Pubkey, // store Pubkey::default() if not set
     pub has_multisig: bool,
+    pub has_approval: bool, // a `MultisigApproval` is open at `approval_bump`
+    pub approval_bump: u8,
+    #[padding]
+    pub _padding1: [u8; 3],
+    pub created_at: i64,
+    pub deadline: i64,        // 0 = no deadline
+    pub auto_release_at: i64, // 0 = never auto-releases
+    pub milestone_count: u8,  // 0 = single payment of `amount`
+    #[padding]
+    pub _padding2: [u8; 7],
+    pub milestones: [Milestone; 8], // MAX_MILESTONES
+    pub arbiter: Pubkey,            // Pubkey::default() = no arbiter, disputes are disabled
+    pub is_disputed: bool,
+    pub is_native: bool, // lamports held by the escrow PDA itself instead of an SPL vault
+    #[padding]
+    pub _padding3: [u8; 6],
 }
 
+// shank only reads literal array lengths
+const _: () = assert!(MAX_MILESTONES == 8);
+
 impl DataLen for Escrow {
     const LEN: usize = core::mem::size_of::<Escrow>();
 }
@@ -34,11 +59,13 @@ impl Escrow {
         pda: &Pubkey,
         sender: &Pubkey,
         receiver: &Pubkey,
+        reference_seed: u64,
     ) -> Result<(), ProgramError> {
         let seeds = &[
             Self::SEED.as_bytes(),
             sender.as_ref(),
             receiver.as_ref(),
+            &reference_seed.to_le_bytes(),
             &[bump],
         ];
         let derived = create_program_address(seeds, &crate::ID)?;
@@ -49,31 +76,86 @@ impl Escrow {
         Ok(())
     }
 
-    pub fn initialize(
-        escrow_acc: &AccountInfo,
-        sender: Pubkey,
-        receiver: Pubkey,
-        amount: u64,
-        bump: u8,
-        client_multisig: Option<Pubkey>,
-    ) -> ProgramResult {
-        let my_state =
-            unsafe { load_acc_mut_unchecked::<Escrow>(escrow_acc.borrow_mut_data_unchecked()) }?;
-
-        my_state.sender = sender;
-        my_state.receiver = receiver;
-        my_state.amount = amount;
-        my_state.is_released = false;
-        my_state.bump = bump;
-
-        if let Some(ms) = client_multisig {
-            my_state.client_multisig = ms;
-            my_state.has_multisig = true;
+    /// The client may reclaim the funds once the deadline has passed, unless the
+    /// escrow has already auto-released to the freelancer.
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.deadline != 0 && now >= self.deadline && !self.is_auto_released(now)
+    }
+
+    /// The freelancer may claim without an explicit release once `auto_release_at` has passed.
+    pub fn is_auto_released(&self, now: i64) -> bool {
+        self.auto_release_at != 0 && now >= self.auto_release_at
+    }
+
+    pub fn is_milestone_escrow(&self) -> bool {
+        self.milestone_count > 0
+    }
+
+    pub fn active_milestones(&self) -> &[Milestone] {
+        &self.milestones[..self.milestone_count as usize]
+    }
+
+    /// A milestone escrow can only be closed once every milestone has been paid out.
+    pub fn all_milestones_claimed(&self) -> bool {
+        self.active_milestones()
+            .iter()
+            .all(|m| m.state == MilestoneState::Claimed)
+    }
+
+    /// Either party may dispute while some of the funds are still waiting to be released.
+    pub fn is_disputable(&self) -> bool {
+        if self.is_milestone_escrow() {
+            self.active_milestones()
+                .iter()
+                .any(|m| m.state == MilestoneState::Pending)
         } else {
-            my_state.client_multisig = Pubkey::default();
-            my_state.has_multisig = false;
+            !self.is_released
         }
+    }
 
-        Ok(())
+    /// Amount that has not been paid out to the freelancer yet.
+    pub fn outstanding_amount(&self) -> u64 {
+        if self.is_milestone_escrow() {
+            self.active_milestones()
+                .iter()
+                .filter(|m| m.state != MilestoneState::Claimed)
+                .map(|m| m.amount)
+                .sum()
+        } else {
+            self.amount
+        }
+    }
+
+    /// Amount of the milestones released to the freelancer but not claimed yet.
+    pub fn released_unclaimed_amount(&self) -> u64 {
+        self.active_milestones()
+            .iter()
+            .filter(|m| m.state == MilestoneState::Released)
+            .map(|m| m.amount)
+            .sum()
+    }
+}
+
+impl ProgramAccount for Escrow {
+    const DISCRIMINATOR: AccountDiscriminator = AccountDiscriminator::Escrow;
+    // version 2 keeps track of the `MultisigApproval` in what was padding
+    const VERSION: u8 = 2;
+
+    fn validate_address(&self, key: &Pubkey) -> Result<(), ProgramError> {
+        Self::validate_pda(
+            self.bump,
+            key,
+            &self.sender,
+            &self.receiver,
+            self.reference_seed,
+        )
+    }
+
+    fn migrate(&mut self, from_version: u8) {
+        if from_version < 2 {
+            // not recorded by version 1, which wrote zeros here
+            self.has_approval = false;
+            self.approval_bump = 0;
+        }
     }
 }
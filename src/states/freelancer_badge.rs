@@ -1,17 +1,161 @@
-use pinocchio::pubkey::Pubkey;
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::{create_program_address, try_find_program_address, Pubkey},
+};
 
-use crate::states::{DataLen, ReputationTier};
+use crate::{
+    errors::BondrError,
+    events::{BadgeUpdated, Event},
+    states::{
+        load_account, load_account_mut, load_optional_account_mut, AccountDiscriminator, DataLen,
+        ProgramAccount, ReputationTier,
+    },
+};
 
 #[repr(C)]
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankAccount)]
 pub struct FreelancerBadge {
+    pub discriminator: u8,
+    pub version: u8,
     pub tier: ReputationTier, //enum - Unranked -> Verified -> Professional -> Elite
+    #[padding]
+    pub _padding0: [u8; 1],
     pub completed_escrows: u32,
     pub total_value_completed: u64,
     pub freelancer: Pubkey,
     pub bump: u8,
+    #[padding]
+    pub _padding1: [u8; 3],
+    pub disputes_won: u32,
+    pub disputes_lost: u32,
+    #[padding]
+    pub _padding2: [u8; 4],
 }
 
 impl DataLen for FreelancerBadge {
     const LEN: usize = core::mem::size_of::<FreelancerBadge>();
 }
+
+impl FreelancerBadge {
+    pub const SEED: &'static str = "badge";
+
+    pub fn validate_pda(bump: u8, pda: &Pubkey, freelancer: &Pubkey) -> Result<(), ProgramError> {
+        let seeds = &[Self::SEED.as_bytes(), freelancer.as_ref(), &[bump]];
+        let derived = create_program_address(seeds, &crate::ID)?;
+
+        if derived != *pda {
+            return Err(BondrError::PdaMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// Like [`FreelancerBadge::validate_pda`], also requiring the canonical bump. Checked
+    /// when the badge is created so that each freelancer has at most one.
+    pub fn validate_canonical_pda(
+        bump: u8,
+        pda: &Pubkey,
+        freelancer: &Pubkey,
+    ) -> Result<(), ProgramError> {
+        let (derived, canonical) = Self::find_pda(freelancer)?;
+
+        if derived != *pda || bump != canonical {
+            return Err(BondrError::PdaMismatch.into());
+        }
+        Ok(())
+    }
+
+    fn find_pda(freelancer: &Pubkey) -> Result<(Pubkey, u8), ProgramError> {
+        try_find_program_address(&[Self::SEED.as_bytes(), freelancer.as_ref()], &crate::ID)
+            .ok_or(BondrError::PdaMismatch.into())
+    }
+
+    /// Tier of `freelancer`'s badge; a badge that was never created counts as `Unranked`.
+    pub fn tier_of(
+        badge_acc: &AccountInfo,
+        freelancer: &Pubkey,
+    ) -> Result<ReputationTier, ProgramError> {
+        if badge_acc.data_is_empty() {
+            return Ok(ReputationTier::Unranked);
+        }
+
+        let badge = load_account::<FreelancerBadge>(badge_acc)?;
+        if badge.freelancer != *freelancer {
+            return Err(BondrError::InvalidOwner.into());
+        }
+
+        Ok(badge.tier)
+    }
+
+    /// Credits a settled escrow to `freelancer`'s badge, if they have one.
+    pub fn record_completed_escrow(
+        badge_acc: &AccountInfo,
+        freelancer: &Pubkey,
+        amount: u64,
+    ) -> Result<(), ProgramError> {
+        let badge = unsafe { load_optional_account_mut::<FreelancerBadge>(badge_acc)? };
+        let Some(badge) = badge else {
+            return Ok(());
+        };
+        if badge.freelancer != *freelancer {
+            return Err(BondrError::InvalidOwner.into());
+        }
+
+        badge.completed_escrows = badge.completed_escrows.saturating_add(1);
+        badge.total_value_completed = badge.total_value_completed.saturating_add(amount);
+        badge.emit_updated(badge_acc.key());
+
+        Ok(())
+    }
+
+    /// Records a dispute outcome on `freelancer`'s badge. Unlike on a claim, `badge_acc`
+    /// must be the badge's address even if it was never created, so that a lost dispute
+    /// can't be kept off an existing badge by passing some other empty account.
+    pub fn record_dispute(
+        badge_acc: &AccountInfo,
+        freelancer: &Pubkey,
+        won: bool,
+    ) -> Result<(), ProgramError> {
+        if badge_acc.data_is_empty() {
+            if Self::find_pda(freelancer)?.0 != *badge_acc.key() {
+                return Err(BondrError::PdaMismatch.into());
+            }
+            return Ok(());
+        }
+
+        let badge = unsafe { load_account_mut::<FreelancerBadge>(badge_acc)? };
+        if badge.freelancer != *freelancer {
+            return Err(BondrError::InvalidOwner.into());
+        }
+
+        if won {
+            badge.disputes_won = badge.disputes_won.saturating_add(1);
+        } else {
+            badge.disputes_lost = badge.disputes_lost.saturating_add(1);
+        }
+        badge.emit_updated(badge_acc.key());
+
+        Ok(())
+    }
+
+    pub fn emit_updated(&self, badge: &Pubkey) {
+        BadgeUpdated {
+            badge: *badge,
+            freelancer: self.freelancer,
+            completed_escrows: self.completed_escrows,
+            total_value_completed: self.total_value_completed,
+            disputes_won: self.disputes_won,
+            disputes_lost: self.disputes_lost,
+        }
+        .emit();
+    }
+}
+
+impl ProgramAccount for FreelancerBadge {
+    const DISCRIMINATOR: AccountDiscriminator = AccountDiscriminator::FreelancerBadge;
+    const VERSION: u8 = 1;
+
+    fn validate_address(&self, key: &Pubkey) -> Result<(), ProgramError> {
+        Self::validate_pda(self.bump, key, &self.freelancer)
+    }
+}
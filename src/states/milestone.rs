@@ -0,0 +1,31 @@
+use crate::states::DataLen;
+
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, shank::ShankType)]
+pub enum MilestoneState {
+    Pending = 0,
+    Released = 1,
+    Claimed = 2,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankType)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct Milestone {
+    pub amount: u64,
+    pub state: MilestoneState,
+    #[cfg_attr(feature = "std", serde(skip))]
+    pub _padding: [u8; 7],
+}
+
+impl DataLen for Milestone {
+    const LEN: usize = core::mem::size_of::<Milestone>();
+}
+
+impl Milestone {
+    pub const EMPTY: Milestone = Milestone {
+        amount: 0,
+        state: MilestoneState::Pending,
+        _padding: [0; 7],
+    };
+}
@@ -1,13 +1,25 @@
+pub mod account;
 pub mod client_multisig;
+pub mod config;
+pub mod core_asset;
 pub mod escrow;
 pub mod freelancer_badge;
+pub mod milestone;
+pub mod multisig_approval;
 pub mod reputation_tier;
+pub mod token;
 pub mod user_stats;
 pub mod utils;
 
+pub use account::*;
 pub use client_multisig::*;
+pub use config::*;
+pub use core_asset::*;
 pub use escrow::*;
 pub use freelancer_badge::*;
+pub use milestone::*;
+pub use multisig_approval::*;
 pub use reputation_tier::*;
+pub use token::*;
 pub use user_stats::*;
 pub use utils::*;
@@ -0,0 +1,205 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::Signer,
+    program_error::ProgramError,
+    pubkey::{create_program_address, Pubkey},
+    seeds, ProgramResult,
+};
+
+use crate::{
+    constants::MAX_MULTISIG_MEMBERS,
+    errors::BondrError,
+    states::{
+        close_program_account, create_pda_account, init_account, load_account_mut,
+        AccountDiscriminator, ClientMultisig, DataLen, Escrow, ProgramAccount,
+    },
+};
+
+/// Approvals for one multisig escrow, stored at `["multisig_approval", multisig, escrow]`.
+/// Opened with the escrow and closed with it, so a multisig can have any number of
+/// escrows under approval at once. The multisig counts its open records and can't
+/// change members or threshold while any is open.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankAccount)]
+pub struct MultisigApproval {
+    pub discriminator: u8,
+    pub version: u8,
+    pub multisig: Pubkey,
+    pub escrow: Pubkey,
+    pub approvers: [Pubkey; 5], // MAX_MULTISIG_MEMBERS
+    pub approver_count: u8,     // how many active entries in `approvers`
+    pub bump: u8,
+}
+
+impl DataLen for MultisigApproval {
+    const LEN: usize = core::mem::size_of::<MultisigApproval>();
+}
+
+impl MultisigApproval {
+    pub const SEED: &'static str = "multisig_approval";
+
+    pub fn validate_pda(
+        bump: u8,
+        pda: &Pubkey,
+        multisig: &Pubkey,
+        escrow: &Pubkey,
+    ) -> Result<(), ProgramError> {
+        let seeds = &[
+            Self::SEED.as_bytes(),
+            multisig.as_ref(),
+            escrow.as_ref(),
+            &[bump],
+        ];
+        let derived = create_program_address(seeds, &crate::ID)?;
+
+        if derived != *pda {
+            return Err(BondrError::PdaMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// Creates `escrow`'s record at `bump` with no approvals yet and rent from `payer`.
+    ///
+    /// # Safety
+    /// Same as [`load_account_mut`].
+    pub unsafe fn open(
+        approval_acc: &AccountInfo,
+        multisig_acc: &AccountInfo,
+        escrow: &Pubkey,
+        bump: u8,
+        payer: &AccountInfo,
+    ) -> Result<(), ProgramError> {
+        let multisig = multisig_acc.key();
+        Self::validate_pda(bump, approval_acc.key(), multisig, escrow)?;
+
+        let multisig_state = load_account_mut::<ClientMultisig>(multisig_acc)?;
+        multisig_state.open_approvals = multisig_state
+            .open_approvals
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        let bump_ref = &[bump];
+        let seeds_arr = seeds!(
+            Self::SEED.as_bytes(),
+            multisig.as_ref(),
+            escrow.as_ref(),
+            bump_ref
+        );
+        create_pda_account(approval_acc, payer, Self::LEN, &[Signer::from(&seeds_arr)])?;
+
+        *init_account::<MultisigApproval>(approval_acc)? = MultisigApproval {
+            discriminator: Self::DISCRIMINATOR as u8,
+            version: Self::VERSION,
+            multisig: *multisig,
+            escrow: *escrow,
+            approvers: [Pubkey::default(); MAX_MULTISIG_MEMBERS],
+            approver_count: 0,
+            bump,
+        };
+        Ok(())
+    }
+
+    /// The record `escrow_state` was opened with, which must be at the address it
+    /// recorded.
+    ///
+    /// # Safety
+    /// Same as [`load_account_mut`].
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn load_for<'a>(
+        approval_acc: &'a AccountInfo,
+        escrow_state: &Escrow,
+        escrow: &Pubkey,
+    ) -> Result<&'a mut MultisigApproval, ProgramError> {
+        let multisig = &escrow_state.client_multisig;
+        Self::validate_pda(
+            escrow_state.approval_bump,
+            approval_acc.key(),
+            multisig,
+            escrow,
+        )?;
+
+        let approval = load_account_mut::<MultisigApproval>(approval_acc)?;
+        approval.check(multisig, escrow)?;
+        Ok(approval)
+    }
+
+    /// Closes `escrow_state`'s record to `recipient` along with the escrow, releasing the
+    /// multisig for membership changes once it was the last; a no-op for escrows without one.
+    ///
+    /// # Safety
+    /// Same as [`load_account_mut`].
+    pub unsafe fn close(
+        approval_acc: &AccountInfo,
+        multisig_acc: &AccountInfo,
+        escrow_state: &Escrow,
+        escrow: &Pubkey,
+        recipient: &AccountInfo,
+    ) -> ProgramResult {
+        if !escrow_state.has_approval {
+            return Ok(());
+        }
+
+        if *multisig_acc.key() != escrow_state.client_multisig {
+            return Err(BondrError::MultisigPendingEscrowMismatch.into());
+        }
+        let multisig = load_account_mut::<ClientMultisig>(multisig_acc)?;
+        multisig.open_approvals = multisig.open_approvals.saturating_sub(1);
+
+        Self::load_for(approval_acc, escrow_state, escrow)?;
+        close_program_account(approval_acc, recipient)
+    }
+
+    /// Checks the record belongs to `escrow` under `multisig`.
+    pub fn check(&self, multisig: &Pubkey, escrow: &Pubkey) -> Result<(), ProgramError> {
+        if self.multisig != *multisig || self.escrow != *escrow {
+            return Err(BondrError::MultisigPendingEscrowMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// Records `member`'s approval, dropping any approvers who have since left the multisig.
+    pub fn approve(
+        &mut self,
+        multisig: &ClientMultisig,
+        member: &Pubkey,
+    ) -> Result<(), ProgramError> {
+        if multisig.member_index(member).is_none() {
+            return Err(BondrError::NotMultisigMember.into());
+        }
+
+        let mut current = [Pubkey::default(); MAX_MULTISIG_MEMBERS];
+        let mut count = 0;
+        for approver in &self.approvers[..self.approver_count as usize] {
+            if approver == member {
+                return Err(BondrError::AlreadyApproved.into());
+            }
+            if multisig.member_index(approver).is_some() {
+                current[count] = *approver;
+                count += 1;
+            }
+        }
+
+        // every remaining approver is a member other than `member`, so there is room
+        current[count] = *member;
+        self.approvers = current;
+        self.approver_count = count as u8 + 1;
+        Ok(())
+    }
+
+    /// Approvals from members still in `multisig`.
+    pub fn approval_count(&self, multisig: &ClientMultisig) -> u8 {
+        self.approvers[..self.approver_count as usize]
+            .iter()
+            .filter(|approver| multisig.member_index(approver).is_some())
+            .count() as u8
+    }
+}
+
+impl ProgramAccount for MultisigApproval {
+    const DISCRIMINATOR: AccountDiscriminator = AccountDiscriminator::MultisigApproval;
+    const VERSION: u8 = 1;
+
+    fn validate_address(&self, key: &Pubkey) -> Result<(), ProgramError> {
+        Self::validate_pda(self.bump, key, &self.multisig, &self.escrow)
+    }
+}
@@ -1,6 +1,7 @@
 use crate::states::DataLen;
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, shank::ShankType)]
 pub enum ReputationTier {
     Unranked = 0,
     Verified = 1,
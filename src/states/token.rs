@@ -0,0 +1,272 @@
+use pinocchio::{
+    account_info::{AccountInfo, Ref},
+    cpi::slice_invoke_signed,
+    instruction::{AccountMeta, Instruction, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
+use pinocchio_token::state::TokenAccount;
+
+use crate::{constants::BPS_DENOMINATOR, errors::BondrError};
+
+pub const TOKEN_PROGRAM_ID: Pubkey = pinocchio_token::ID;
+pub const TOKEN_2022_PROGRAM_ID: Pubkey =
+    pinocchio_pubkey::pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Upper bound on transfer-hook extra accounts forwarded with a transfer.
+pub const MAX_TRANSFER_HOOK_ACCOUNTS: usize = 8;
+
+// Mint layout shared by both token programs, followed by the Token-2022 TLV extensions.
+const MINT_BASE_LEN: usize = 82;
+const MINT_DECIMALS_OFFSET: usize = 44;
+const ACCOUNT_TYPE_OFFSET: usize = TokenAccount::LEN;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const EXTENSION_TRANSFER_FEE_CONFIG: u16 = 1;
+const TRANSFER_FEE_CONFIG_LEN: usize = 108;
+
+/// Checks that `token_program` is SPL Token or Token-2022 and owns `mint`.
+pub fn validate_token_program(token_program: &AccountInfo, mint: &AccountInfo) -> ProgramResult {
+    let key = token_program.key();
+
+    if *key != TOKEN_PROGRAM_ID && *key != TOKEN_2022_PROGRAM_ID {
+        return Err(BondrError::MissingTokenProgram.into());
+    }
+    if !mint.is_owned_by(key) {
+        return Err(BondrError::MissingTokenProgram.into());
+    }
+    Ok(())
+}
+
+/// Loads a token account of either token program; Token-2022 accounts may carry extensions.
+pub fn load_token_account<'a>(
+    account: &'a AccountInfo,
+    token_program: &Pubkey,
+) -> Result<Ref<'a, TokenAccount>, ProgramError> {
+    if !account.is_owned_by(token_program) {
+        return Err(BondrError::MissingTokenAccounts.into());
+    }
+    if account.data_len() < TokenAccount::LEN {
+        return Err(BondrError::MissingTokenAccounts.into());
+    }
+
+    Ok(Ref::map(account.try_borrow_data()?, |data| unsafe {
+        TokenAccount::from_bytes_unchecked(data)
+    }))
+}
+
+pub fn mint_decimals(mint: &AccountInfo) -> Result<u8, ProgramError> {
+    let data = mint.try_borrow_data()?;
+
+    if data.len() < MINT_BASE_LEN {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(data[MINT_DECIMALS_OFFSET])
+}
+
+/// The transfer fee of a Token-2022 mint for the current epoch.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransferFee {
+    pub maximum_fee: u64,
+    pub basis_points: u16,
+}
+
+impl TransferFee {
+    /// Fee withheld when `amount` is transferred (rounded up, capped at `maximum_fee`).
+    pub fn calculate_fee(&self, amount: u64) -> Option<u64> {
+        if self.basis_points == 0 || amount == 0 {
+            return Some(0);
+        }
+
+        let raw = (amount as u128 * self.basis_points as u128).div_ceil(BPS_DENOMINATOR as u128);
+        let fee = u64::try_from(raw).ok()?;
+
+        Some(fee.min(self.maximum_fee))
+    }
+
+    /// Amount to send so that the recipient ends up with exactly `post_fee_amount`.
+    pub fn calculate_pre_fee_amount(&self, post_fee_amount: u64) -> Option<u64> {
+        match self.basis_points {
+            0 => Some(post_fee_amount),
+            BPS_DENOMINATOR => post_fee_amount.checked_add(self.maximum_fee),
+            bps => {
+                let numerator = post_fee_amount as u128 * BPS_DENOMINATOR as u128;
+                let denominator = (BPS_DENOMINATOR - bps) as u128;
+                let raw_pre_fee = u64::try_from(numerator.div_ceil(denominator)).ok()?;
+
+                if raw_pre_fee - post_fee_amount >= self.maximum_fee {
+                    post_fee_amount.checked_add(self.maximum_fee)
+                } else {
+                    Some(raw_pre_fee)
+                }
+            }
+        }
+    }
+}
+
+/// Reads the `TransferFeeConfig` extension of a Token-2022 mint, if it has one.
+pub fn mint_transfer_fee(
+    mint: &AccountInfo,
+    epoch: u64,
+) -> Result<Option<TransferFee>, ProgramError> {
+    if !mint.is_owned_by(&TOKEN_2022_PROGRAM_ID) {
+        return Ok(None);
+    }
+
+    transfer_fee_of_mint_data(&mint.try_borrow_data()?, epoch)
+}
+
+/// [`mint_transfer_fee`] on the data of a Token-2022 mint.
+pub fn transfer_fee_of_mint_data(
+    data: &[u8],
+    epoch: u64,
+) -> Result<Option<TransferFee>, ProgramError> {
+    if data.len() <= ACCOUNT_TYPE_OFFSET || data[ACCOUNT_TYPE_OFFSET] != ACCOUNT_TYPE_MINT {
+        return Ok(None);
+    }
+
+    // walk the type-length-value extension entries
+    let mut offset = ACCOUNT_TYPE_OFFSET + 1;
+    while offset + 4 <= data.len() {
+        let ext_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let ext_len = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let value_start = offset + 4;
+
+        if value_start + ext_len > data.len() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if ext_type == EXTENSION_TRANSFER_FEE_CONFIG {
+            if ext_len != TRANSFER_FEE_CONFIG_LEN {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            // authorities (2 * 32) + withheld amount (8), then older and newer fees
+            let older = &data[value_start + 72..value_start + 90];
+            let newer = &data[value_start + 90..value_start + 108];
+            let newer_epoch = u64::from_le_bytes(newer[..8].try_into().unwrap());
+            let fee = if epoch >= newer_epoch { newer } else { older };
+
+            return Ok(Some(TransferFee {
+                maximum_fee: u64::from_le_bytes(fee[8..16].try_into().unwrap()),
+                basis_points: u16::from_le_bytes(fee[16..18].try_into().unwrap()),
+            }));
+        }
+
+        offset = value_start + ext_len;
+    }
+
+    Ok(None)
+}
+
+/// `TransferChecked` against whichever token program owns the mint, forwarding any
+/// transfer-hook extra accounts.
+pub struct TokenTransfer<'a> {
+    pub from: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub to: &'a AccountInfo,
+    pub authority: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub extra_accounts: &'a [AccountInfo],
+    pub amount: u64,
+    pub decimals: u8,
+}
+
+impl TokenTransfer<'_> {
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        if self.extra_accounts.len() > MAX_TRANSFER_HOOK_ACCOUNTS {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let fixed = [self.from, self.mint, self.to, self.authority];
+        let len = fixed.len() + self.extra_accounts.len();
+
+        let account_infos: [&AccountInfo; 4 + MAX_TRANSFER_HOOK_ACCOUNTS] =
+            core::array::from_fn(|i| match i {
+                0..=3 => fixed[i],
+                _ => self.extra_accounts.get(i - 4).unwrap_or(self.mint),
+            });
+
+        let account_metas: [AccountMeta; 4 + MAX_TRANSFER_HOOK_ACCOUNTS] =
+            core::array::from_fn(|i| match i {
+                0 => AccountMeta::writable(self.from.key()),
+                1 => AccountMeta::readonly(self.mint.key()),
+                2 => AccountMeta::writable(self.to.key()),
+                3 => AccountMeta::readonly_signer(self.authority.key()),
+                _ => AccountMeta::from(account_infos[i]),
+            });
+
+        // TransferChecked: [12] + amount (u64) + decimals (u8)
+        let mut instruction_data = [0u8; 10];
+        instruction_data[0] = 12;
+        instruction_data[1..9].copy_from_slice(&self.amount.to_le_bytes());
+        instruction_data[9] = self.decimals;
+
+        let instruction = Instruction {
+            program_id: self.token_program.key(),
+            accounts: &account_metas[..len],
+            data: &instruction_data,
+        };
+
+        slice_invoke_signed(&instruction, &account_infos[..len], signers)
+    }
+}
+
+/// Token-2022 `HarvestWithheldTokensToMint`, needed before a fee-bearing account can be closed.
+pub struct HarvestWithheldTokensToMint<'a> {
+    pub mint: &'a AccountInfo,
+    pub source: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl HarvestWithheldTokensToMint<'_> {
+    pub fn invoke(&self) -> ProgramResult {
+        let account_metas = [
+            AccountMeta::writable(self.mint.key()),
+            AccountMeta::writable(self.source.key()),
+        ];
+
+        // TransferFeeExtension (26) / HarvestWithheldTokensToMint (4)
+        let instruction = Instruction {
+            program_id: self.token_program.key(),
+            accounts: &account_metas,
+            data: &[26, 4],
+        };
+
+        slice_invoke_signed(&instruction, &[self.mint, self.source], &[])
+    }
+}
+
+/// `CloseAccount` against whichever token program owns the account.
+pub struct TokenCloseAccount<'a> {
+    pub account: &'a AccountInfo,
+    pub destination: &'a AccountInfo,
+    pub authority: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl TokenCloseAccount<'_> {
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let account_metas = [
+            AccountMeta::writable(self.account.key()),
+            AccountMeta::writable(self.destination.key()),
+            AccountMeta::readonly_signer(self.authority.key()),
+        ];
+
+        let instruction = Instruction {
+            program_id: self.token_program.key(),
+            accounts: &account_metas,
+            data: &[9],
+        };
+
+        slice_invoke_signed(
+            &instruction,
+            &[self.account, self.destination, self.authority],
+            signers,
+        )
+    }
+}
@@ -1,15 +1,160 @@
-use pinocchio::pubkey::Pubkey;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::Signer,
+    program_error::ProgramError,
+    pubkey::{try_find_program_address, Pubkey},
+    seeds,
+};
 
-use crate::states::DataLen;
+use crate::{
+    errors::BondrError,
+    states::{
+        account_version, create_pda_account, grow_account, init_account, load_account_mut,
+        AccountDiscriminator, DataLen, ProgramAccount,
+    },
+};
 
+/// Per-user counters, stored at `["user_stats", user]` and created the first time
+/// an instruction records something for the user. Freelancers build their record in
+/// `completed_escrows`; everything else is the user's record as a client, which
+/// freelancers can read before taking on a job.
 #[repr(C)]
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, shank::ShankAccount)]
 pub struct UserStats {
+    pub discriminator: u8,
+    pub version: u8,
     pub user: Pubkey,
+    #[padding]
+    pub _padding0: [u8; 2],
     pub completed_escrows: u32,
     pub bump: u8,
+    #[padding]
+    pub _padding1: [u8; 3],
+    pub disputes_won: u32,  // as the client
+    pub disputes_lost: u32, // as the client
+    // version 2
+    pub escrows_funded: u32,
+    pub escrows_released: u32, // released by the client or its multisig, not auto-released
+    pub escrows_refunded: u32, // cancelled with the funds back to the client
+    pub total_funded: u64,
+    pub total_release_seconds: u64, // from creation to release, summed over `escrows_released`
 }
 
 impl DataLen for UserStats {
     const LEN: usize = core::mem::size_of::<UserStats>();
 }
+
+impl UserStats {
+    pub const SEED: &'static str = "user_stats";
+
+    /// Only the canonical bump is accepted: stats at any other bump would give the user
+    /// a second record to send the activity they'd rather hide to.
+    pub fn validate_pda(bump: u8, pda: &Pubkey, user: &Pubkey) -> Result<(), ProgramError> {
+        let seeds = &[Self::SEED.as_bytes(), user.as_ref()];
+        let (derived, canonical) =
+            try_find_program_address(seeds, &crate::ID).ok_or(BondrError::PdaMismatch)?;
+
+        if derived != *pda || bump != canonical {
+            return Err(BondrError::PdaMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// `user`'s stats, created at `bump` with rent from `payer` if they don't exist yet.
+    ///
+    /// # Safety
+    /// Same as [`load_account_mut`].
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn load_or_create<'a>(
+        stats_acc: &'a AccountInfo,
+        user: &Pubkey,
+        bump: u8,
+        payer: &AccountInfo,
+    ) -> Result<&'a mut UserStats, ProgramError> {
+        if !stats_acc.data_is_empty() {
+            return Self::load_mut(stats_acc, user, payer);
+        }
+
+        Self::validate_pda(bump, stats_acc.key(), user)?;
+
+        let bump_ref = &[bump];
+        let seeds_arr = seeds!(Self::SEED.as_bytes(), user.as_ref(), bump_ref);
+        create_pda_account(stats_acc, payer, Self::LEN, &[Signer::from(&seeds_arr)])?;
+
+        let stats = init_account::<UserStats>(stats_acc)?;
+        *stats = UserStats {
+            discriminator: Self::DISCRIMINATOR as u8,
+            version: Self::VERSION,
+            user: *user,
+            _padding0: [0; 2],
+            completed_escrows: 0,
+            bump,
+            _padding1: [0; 3],
+            disputes_won: 0,
+            disputes_lost: 0,
+            escrows_funded: 0,
+            escrows_released: 0,
+            escrows_refunded: 0,
+            total_funded: 0,
+            total_release_seconds: 0,
+        };
+        Ok(stats)
+    }
+
+    /// `user`'s existing stats. Every escrow's client has them from `InitializeEscrow` on.
+    /// Stats from before the current layout grow into it here, `payer` covering the rent,
+    /// so they never hold up the escrows they count.
+    ///
+    /// # Safety
+    /// Same as [`load_account_mut`].
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn load_mut<'a>(
+        stats_acc: &'a AccountInfo,
+        user: &Pubkey,
+        payer: &AccountInfo,
+    ) -> Result<&'a mut UserStats, ProgramError> {
+        if stats_acc.is_owned_by(&crate::ID)
+            && account_version::<UserStats>(stats_acc)? < Self::VERSION
+            && stats_acc.data_len() < Self::LEN
+        {
+            grow_account(stats_acc, payer, Self::LEN)?;
+        }
+
+        let stats = load_account_mut::<UserStats>(stats_acc)?;
+        if stats.user != *user {
+            return Err(BondrError::InvalidOwner.into());
+        }
+        Ok(stats)
+    }
+
+    pub fn record_completed(&mut self) {
+        self.completed_escrows = self.completed_escrows.saturating_add(1);
+    }
+
+    pub fn record_funded(&mut self, amount: u64) {
+        self.escrows_funded = self.escrows_funded.saturating_add(1);
+        self.total_funded = self.total_funded.saturating_add(amount);
+    }
+
+    /// An escrow created at `created_at` was released to the freelancer at `now`.
+    pub fn record_released(&mut self, created_at: i64, now: i64) {
+        self.escrows_released = self.escrows_released.saturating_add(1);
+        self.total_release_seconds = self
+            .total_release_seconds
+            .saturating_add(now.saturating_sub(created_at).max(0) as u64);
+    }
+
+    pub fn record_refunded(&mut self) {
+        self.escrows_refunded = self.escrows_refunded.saturating_add(1);
+    }
+}
+
+impl ProgramAccount for UserStats {
+    const DISCRIMINATOR: AccountDiscriminator = AccountDiscriminator::UserStats;
+    // version 2 appended the client record, which starts from zero
+    const VERSION: u8 = 2;
+
+    fn validate_address(&self, key: &Pubkey) -> Result<(), ProgramError> {
+        Self::validate_pda(self.bump, key, &self.user)
+    }
+}
@@ -1,15 +1,29 @@
 use pinocchio::{
-    account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey::Pubkey,
-    seeds, ProgramResult,
+    account_info::AccountInfo,
+    instruction::Signer,
+    program_error::ProgramError,
+    seeds,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
 };
 
-use crate::errors::BondrError;
-use pinocchio_token::instructions::Transfer;
+use pinocchio_system::instructions::{Allocate, Assign, CreateAccount, Transfer};
+
+use crate::{
+    errors::BondrError,
+    states::{
+        load_token_account, mint_decimals, mint_transfer_fee, Config, Escrow,
+        HarvestWithheldTokensToMint, ReputationTier, TokenCloseAccount, TokenTransfer,
+    },
+};
 
 pub trait DataLen {
     const LEN: usize;
 }
 
+/// # Safety
+/// `T` must be a `#[repr(C)]` type valid for any bit pattern of its size; the
+/// length is checked but the bytes are reinterpreted as-is.
 #[inline(always)]
 pub unsafe fn load_acc_unchecked<T: DataLen>(bytes: &[u8]) -> Result<&T, ProgramError> {
     if bytes.len() != T::LEN {
@@ -18,6 +32,9 @@ pub unsafe fn load_acc_unchecked<T: DataLen>(bytes: &[u8]) -> Result<&T, Program
     Ok(&*(bytes.as_ptr() as *const T))
 }
 
+/// # Safety
+/// `T` must be a `#[repr(C)]` type valid for any bit pattern of its size; the
+/// length is checked but the bytes are reinterpreted as-is.
 #[inline(always)]
 pub unsafe fn load_acc_mut_unchecked<T: DataLen>(bytes: &mut [u8]) -> Result<&mut T, ProgramError> {
     if bytes.len() != T::LEN {
@@ -26,6 +43,10 @@ pub unsafe fn load_acc_mut_unchecked<T: DataLen>(bytes: &mut [u8]) -> Result<&mu
     Ok(&mut *(bytes.as_mut_ptr() as *mut T))
 }
 
+/// # Safety
+/// `T` must be a `#[repr(C)]` type valid for any bit pattern of its size, so without
+/// `bool` or enum fields; the length is checked but the bytes are reinterpreted as-is.
+/// Use [`load_ix_data_with_bools`] for instruction data with `bool` fields.
 #[inline(always)]
 pub unsafe fn load_ix_data<T: DataLen>(bytes: &[u8]) -> Result<&T, ProgramError> {
     if bytes.len() != T::LEN {
@@ -34,41 +55,319 @@ pub unsafe fn load_ix_data<T: DataLen>(bytes: &[u8]) -> Result<&T, ProgramError>
     Ok(&*(bytes.as_ptr() as *const T))
 }
 
+/// Like [`load_ix_data`], first checking that the byte at each of `bool_offsets` is 0
+/// or 1, the only values a `bool` may hold.
+///
+/// # Safety
+/// `T` must be a `#[repr(C)]` type whose `bool` fields are all at `bool_offsets` and
+/// whose other fields are valid for any bit pattern.
+#[inline(always)]
+pub unsafe fn load_ix_data_with_bools<'a, T: DataLen>(
+    bytes: &'a [u8],
+    bool_offsets: &[usize],
+) -> Result<&'a T, ProgramError> {
+    if bytes.len() != T::LEN || bool_offsets.iter().any(|&offset| bytes[offset] > 1) {
+        return Err(BondrError::InvalidInstructionData.into());
+    }
+    Ok(&*(bytes.as_ptr() as *const T))
+}
+
+/// # Safety
+/// `T` must be a `#[repr(C)]` type without padding bytes.
 pub unsafe fn to_bytes<T: DataLen>(data: &T) -> &[u8] {
     core::slice::from_raw_parts(data as *const T as *const u8, T::LEN)
 }
 
+/// # Safety
+/// `T` must be a `#[repr(C)]` type without padding bytes.
 pub unsafe fn to_mut_bytes<T: DataLen>(data: &mut T) -> &mut [u8] {
     core::slice::from_raw_parts_mut(data as *mut T as *mut u8, T::LEN)
 }
 
+/// Runs `f` with the escrow PDA signer seeds, matching the derivation in `Escrow::validate_pda`.
+fn invoke_as_escrow(
+    escrow_state: &Escrow,
+    f: impl FnOnce(&[Signer]) -> ProgramResult,
+) -> ProgramResult {
+    let reference_ref = &escrow_state.reference_seed.to_le_bytes();
+    let bump_ref = &[escrow_state.bump];
+
+    let seeds_arr = seeds!(
+        Escrow::SEED.as_bytes(),
+        &escrow_state.sender,
+        &escrow_state.receiver,
+        reference_ref,
+        bump_ref
+    );
+
+    f(&[Signer::from(&seeds_arr)])
+}
+
+/// The accounts that hold an escrow's funds and move them out.
+///
+/// For native SOL escrows `mint` and `token_program` are the system program and
+/// `escrow_token_acc` is unused.
+pub struct EscrowVault<'a> {
+    pub escrow_acc: &'a AccountInfo, // PDA authority account (escrow PDA)
+    pub escrow_token_acc: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub extra_accounts: &'a [AccountInfo], // transfer-hook extra metas
+}
+
+impl EscrowVault<'_> {
+    /// The mint and token program must be the ones recorded when the escrow was funded.
+    pub fn validate(&self, escrow_state: &Escrow) -> ProgramResult {
+        if escrow_state.mint != *self.mint.key() {
+            return Err(BondrError::MissingTokenAccounts.into());
+        }
+        if escrow_state.token_program != *self.token_program.key() {
+            return Err(BondrError::MissingTokenProgram.into());
+        }
+        Ok(())
+    }
+}
+
+/// Pays each `(destination token account, amount)` out of the escrow vault; zero amounts are skipped.
 pub fn transfer_spl_tokens_from_escrow(
-    escrow_token_acc: &AccountInfo,
-    receiver_token_acc: &AccountInfo,
-    escrow_acc: &AccountInfo, // PDA authority account (escrow PDA)
-    client_pub: &Pubkey,
-    freelancer_pub: &Pubkey,
-    reference_seed: u8,
-    escrow_bump: u8,
+    vault: &EscrowVault,
+    escrow_state: &Escrow,
+    payouts: &[(&AccountInfo, u64)],
+) -> ProgramResult {
+    let decimals = mint_decimals(vault.mint)?;
+
+    invoke_as_escrow(escrow_state, |signers| {
+        for &(destination, amount) in payouts {
+            if amount == 0 {
+                continue;
+            }
+
+            TokenTransfer {
+                from: vault.escrow_token_acc,
+                mint: vault.mint,
+                to: destination,
+                authority: vault.escrow_acc,
+                token_program: vault.token_program,
+                extra_accounts: vault.extra_accounts,
+                amount,
+                decimals,
+            }
+            .invoke_signed(signers)?;
+        }
+        Ok(())
+    })
+}
+
+/// Moves lamports held directly by a native SOL escrow PDA to `destination`.
+pub fn transfer_lamports_from_escrow(
+    escrow_acc: &AccountInfo,
+    destination: &AccountInfo,
     amount: u64,
 ) -> ProgramResult {
-    // Build the signer seeds exactly as your PDA derivation:
-    let pda_ref = &[reference_seed];
-    let bump_ref = &[escrow_bump];
+    if amount == 0 {
+        return Ok(());
+    }
 
-    let seeds_arr = seeds!(b"escrow", client_pub, freelancer_pub, pda_ref, bump_ref);
+    let mut escrow_lamports = escrow_acc.try_borrow_mut_lamports()?;
+    *escrow_lamports = escrow_lamports
+        .checked_sub(amount)
+        .ok_or(BondrError::InsufficientBalance)?;
 
-    let signer = Signer::from(&seeds_arr);
+    *destination.try_borrow_mut_lamports()? += amount;
+    Ok(())
+}
 
-    // Construct the Transfer struct literal
-    let ix = Transfer {
-        from: escrow_token_acc,
-        to: receiver_token_acc,
-        authority: escrow_acc,
-        amount,
-    };
+/// One recipient of an escrow payout.
+pub struct EscrowPayout<'a> {
+    pub wallet: &'a AccountInfo,        // credited for native SOL escrows
+    pub token_account: &'a AccountInfo, // credited for SPL escrows
+    pub amount: u64,
+}
 
-    // Invoke signed with the PDA signer
-    ix.invoke_signed(&[signer])?;
+/// Pays out of the escrow in whichever asset it holds.
+pub fn pay_from_escrow(
+    vault: &EscrowVault,
+    escrow_state: &Escrow,
+    payouts: &[EscrowPayout],
+) -> ProgramResult {
+    for payout in payouts {
+        if escrow_state.is_native {
+            transfer_lamports_from_escrow(vault.escrow_acc, payout.wallet, payout.amount)?;
+        } else {
+            transfer_spl_tokens_from_escrow(
+                vault,
+                escrow_state,
+                &[(payout.token_account, payout.amount)],
+            )?;
+        }
+    }
     Ok(())
 }
+
+/// Where protocol fees are sent: the treasury PDA for native SOL escrows, its token account otherwise.
+pub struct Treasury<'a> {
+    pub config: &'a Config,
+    pub treasury: &'a AccountInfo,
+    pub treasury_token_acc: &'a AccountInfo,
+}
+
+impl Treasury<'_> {
+    pub fn validate(&self, vault: &EscrowVault, escrow_state: &Escrow) -> ProgramResult {
+        Config::validate_treasury_pda(self.config.treasury_bump, self.treasury.key())?;
+
+        if !escrow_state.is_native {
+            let token_acc = load_token_account(self.treasury_token_acc, vault.token_program.key())?;
+
+            if token_acc.owner() != self.treasury.key() || token_acc.mint() != vault.mint.key() {
+                return Err(BondrError::InvalidTreasury.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Pays a freelancer out of the escrow, routing the protocol fee for their tier to the
+/// treasury. Returns the fee taken.
+pub fn pay_freelancer_from_escrow(
+    vault: &EscrowVault,
+    escrow_state: &Escrow,
+    treasury: &Treasury,
+    tier: ReputationTier,
+    payout: EscrowPayout,
+) -> Result<u64, ProgramError> {
+    let fee = treasury.config.protocol_fee(payout.amount, tier);
+
+    if fee > 0 {
+        treasury.validate(vault, escrow_state)?;
+    }
+
+    pay_from_escrow(
+        vault,
+        escrow_state,
+        &[
+            EscrowPayout {
+                amount: payout.amount - fee,
+                ..payout
+            },
+            EscrowPayout {
+                wallet: treasury.treasury,
+                token_account: treasury.treasury_token_acc,
+                amount: fee,
+            },
+        ],
+    )?;
+
+    Ok(fee)
+}
+
+/// Funds still held for the escrow: the vault balance for SPL escrows, the
+/// unclaimed amount for native SOL escrows (the PDA's own rent is not included).
+pub fn escrow_balance(vault: &EscrowVault, escrow_state: &Escrow) -> Result<u64, ProgramError> {
+    if escrow_state.is_native {
+        Ok(escrow_state.outstanding_amount())
+    } else {
+        Ok(load_token_account(vault.escrow_token_acc, vault.token_program.key())?.amount())
+    }
+}
+
+/// Closes the (empty) escrow vault token account, returning its rent to `destination`.
+pub fn close_escrow_token_account(
+    vault: &EscrowVault,
+    escrow_state: &Escrow,
+    destination: &AccountInfo,
+) -> ProgramResult {
+    // Token-2022 refuses to close an account that still holds withheld transfer fees
+    if mint_transfer_fee(vault.mint, Clock::get()?.epoch)?.is_some() {
+        HarvestWithheldTokensToMint {
+            mint: vault.mint,
+            source: vault.escrow_token_acc,
+            token_program: vault.token_program,
+        }
+        .invoke()?;
+    }
+
+    let ix = TokenCloseAccount {
+        account: vault.escrow_token_acc,
+        destination,
+        authority: vault.escrow_acc,
+        token_program: vault.token_program,
+    };
+
+    invoke_as_escrow(escrow_state, |signers| ix.invoke_signed(signers))
+}
+
+/// Closes the escrow (and its vault for SPL escrows), sending the remaining lamports to `destination`.
+pub fn close_escrow(
+    vault: &EscrowVault,
+    escrow_state: &Escrow,
+    destination: &AccountInfo,
+) -> ProgramResult {
+    if !escrow_state.is_native {
+        close_escrow_token_account(vault, escrow_state, destination)?;
+    }
+    close_program_account(vault.escrow_acc, destination)
+}
+
+/// Closes a program-owned account, moving all of its lamports to `destination`.
+pub fn close_program_account(account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    *destination.try_borrow_mut_lamports()? += account.lamports();
+    account.close()
+}
+
+/// Grows the program-owned `account` to `space` bytes, zero-extended, with `payer`
+/// topping up the rent.
+pub fn grow_account(account: &AccountInfo, payer: &AccountInfo, space: usize) -> ProgramResult {
+    let rent_exempt = Rent::get()?.minimum_balance(space);
+
+    if account.lamports() < rent_exempt {
+        Transfer {
+            from: payer,
+            to: account,
+            lamports: rent_exempt - account.lamports(),
+        }
+        .invoke()?;
+    }
+    account.resize(space)
+}
+
+/// Creates the PDA `account` with `space` bytes owned by the program, `payer` covering
+/// the rent. `signers` are the PDA's seeds.
+pub fn create_pda_account(
+    account: &AccountInfo,
+    payer: &AccountInfo,
+    space: usize,
+    signers: &[Signer],
+) -> ProgramResult {
+    let rent_exempt = Rent::get()?.minimum_balance(space);
+
+    if account.lamports() == 0 {
+        return CreateAccount {
+            from: payer,
+            to: account,
+            lamports: rent_exempt,
+            space: space as u64,
+            owner: &crate::ID,
+        }
+        .invoke_signed(signers);
+    }
+
+    // anyone can send lamports to the address first, which `CreateAccount` refuses
+    if account.lamports() < rent_exempt {
+        Transfer {
+            from: payer,
+            to: account,
+            lamports: rent_exempt - account.lamports(),
+        }
+        .invoke()?;
+    }
+    Allocate {
+        account,
+        space: space as u64,
+    }
+    .invoke_signed(signers)?;
+    Assign {
+        account,
+        owner: &crate::ID,
+    }
+    .invoke_signed(signers)
+}
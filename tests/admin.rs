@@ -0,0 +1,578 @@
+//! The config and everything the admin does with it: settings, pauses, treasury
+//! withdrawals and account migrations.
+
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use bondr_pinocchio::{
+    client::{
+        self, find_config_pda, MigrateAccountAccounts, TransferAdminAccounts,
+        UpdateFreelancerBadgeAccounts, WithdrawTreasuryAccounts, PROGRAM_ID,
+    },
+    errors::BondrError,
+    instructions::{
+        BondrInstruction, InitializeConfig, SetPause, UpdateFreelancerBadge, WithdrawTreasury,
+    },
+    states::{
+        ClientMultisig, Config, ConfigSettings, DataLen, Escrow, FreelancerBadge, ProgramAccount,
+        ReputationTier, UserStats,
+    },
+};
+use common::*;
+use solana_sdk::{
+    instruction::Instruction, program_error::ProgramError, pubkey::Pubkey, rent::Rent,
+};
+
+fn transfer_admin(env: &Env, admin: &Pubkey, new_admin: &Pubkey) -> Instruction {
+    client::transfer_admin(&TransferAdminAccounts {
+        admin: *admin,
+        new_admin: *new_admin,
+        config: env.config,
+    })
+}
+
+/// Withdraws `amount` of the treasury's tokens, or lamports if `native`, to `destination`.
+fn withdraw(env: &Env, destination: &Party, native: bool, amount: u64) -> Instruction {
+    let (token, mint, token_program) = if native {
+        (SYSTEM_PROGRAM, SYSTEM_PROGRAM, SYSTEM_PROGRAM)
+    } else {
+        (env.treasury_token, env.mint, TOKEN_PROGRAM)
+    };
+
+    client::withdraw_treasury(
+        &WithdrawTreasuryAccounts {
+            admin: env.admin,
+            config: env.config,
+            treasury: env.treasury,
+            destination: destination.wallet,
+            treasury_token_account: token,
+            destination_token_account: if native {
+                SYSTEM_PROGRAM
+            } else {
+                destination.token
+            },
+            token_mint: mint,
+            token_program,
+            system_program: SYSTEM_PROGRAM,
+        },
+        &WithdrawTreasury { amount },
+    )
+}
+
+fn migrate(env: &Env, payer: &Pubkey, account: &Pubkey) -> Instruction {
+    client::migrate_account(&MigrateAccountAccounts {
+        payer: *payer,
+        account: *account,
+        system_program: SYSTEM_PROGRAM,
+        config: env.config,
+    })
+}
+
+/// Runs a claimed escrow so the treasury holds a fee, returning the fee.
+fn collect_fee(env: &Env, native: bool) -> u64 {
+    let escrow = env.create_escrow(EscrowArgs {
+        native,
+        ..EscrowArgs::default()
+    });
+    env.ok(&env.release(&escrow));
+    env.ok(&env.claim(&escrow));
+
+    protocol_fee(ESCROW_AMOUNT, ReputationTier::Unranked as usize)
+}
+
+#[test]
+fn initialize_config_stores_the_settings() {
+    let env = Env::new();
+
+    let config = env.state::<Config>(&env.config);
+    assert_eq!(config.admin, env.admin.to_bytes());
+    assert_eq!(config.settings, env.settings());
+    assert!(!config.paused);
+    assert_eq!(config.paused_instructions, 0);
+
+    // native fees are credited straight to the treasury, which starts rent exempt
+    assert_eq!(
+        env.lamports(&env.treasury),
+        Rent::default().minimum_balance(0)
+    );
+
+    env.err_with(
+        &env.initialize_config(&env.admin, &env.settings()),
+        ProgramError::AccountAlreadyInitialized,
+    );
+}
+
+#[test]
+fn initialize_config_is_for_the_upgrade_authority() {
+    let env = Env::uninitialized();
+    let stranger = env.party().wallet;
+
+    env.err(
+        &env.initialize_config(&stranger, &env.settings()),
+        BondrError::UnauthorizedAdmin,
+    );
+    env.err(
+        &env.initialize_config(
+            &env.admin,
+            &ConfigSettings {
+                fee_bps: 1_001,
+                ..env.settings()
+            },
+        ),
+        BondrError::InvalidFee,
+    );
+    env.ok(&env.initialize_config(&env.admin, &env.settings()));
+}
+
+#[test]
+fn the_config_only_exists_at_the_canonical_bump() {
+    let env = Env::uninitialized();
+    let (config, bump) = find_config_pda();
+    let (shadow, shadow_bump) = (0..bump)
+        .rev()
+        .find_map(|b| {
+            Pubkey::create_program_address(&[Config::SEED.as_bytes(), &[b]], &PROGRAM_ID)
+                .ok()
+                .map(|shadow| (shadow, b))
+        })
+        .unwrap();
+
+    let mut create = env.initialize_config(&env.admin, &env.settings());
+    create.data[1 + core::mem::offset_of!(InitializeConfig, bump)] = shadow_bump;
+    env.err(
+        &swap_account(create, &config, &shadow),
+        BondrError::PdaMismatch,
+    );
+    env.ok(&env.initialize_config(&env.admin, &env.settings()));
+}
+
+#[test]
+fn update_config_validates_the_settings() {
+    let env = Env::new();
+    let settings = env.settings();
+
+    let cases = [
+        (
+            ConfigSettings {
+                fee_bps: 1_001,
+                ..settings
+            },
+            BondrError::InvalidFee,
+        ),
+        (
+            ConfigSettings {
+                tier_fee_discount_bps: [0, 0, 0, 10_001],
+                ..settings
+            },
+            BondrError::InvalidFee,
+        ),
+        (
+            ConfigSettings {
+                tier_thresholds: [0, 10, 25],
+                ..settings
+            },
+            BondrError::InvalidConfig,
+        ),
+        (
+            ConfigSettings {
+                tier_thresholds: [3, 25, 25],
+                ..settings
+            },
+            BondrError::InvalidConfig,
+        ),
+        (
+            ConfigSettings {
+                allowed_mint_count: 9,
+                ..settings
+            },
+            BondrError::InvalidConfig,
+        ),
+        (
+            ConfigSettings {
+                max_multisig_members: 0,
+                ..settings
+            },
+            BondrError::InvalidConfig,
+        ),
+        (
+            ConfigSettings {
+                tier_uris: [settings.tier_uris[0], [0; 128], settings.tier_uris[2]],
+                ..settings
+            },
+            BondrError::InvalidConfig,
+        ),
+    ];
+    for (settings, error) in cases {
+        env.err(&env.update_config(&settings), error);
+    }
+
+    let settings = ConfigSettings {
+        fee_bps: 250,
+        ..settings
+    };
+    env.ok(&env.update_config(&settings));
+    assert_eq!(env.state::<Config>(&env.config).settings, settings);
+}
+
+#[test]
+fn update_config_is_admin_only() {
+    let env = Env::new();
+
+    env.err(
+        &swap_account(
+            env.update_config(&env.settings()),
+            &env.admin,
+            &env.guardian,
+        ),
+        BondrError::UnauthorizedAdmin,
+    );
+}
+
+#[test]
+fn transfer_admin_hands_over_the_config() {
+    let env = Env::new();
+    let new_admin = Pubkey::new_unique();
+
+    env.err_with(
+        &unsigned(transfer_admin(&env, &env.admin, &new_admin), &new_admin),
+        ProgramError::MissingRequiredSignature,
+    );
+    env.err(
+        &transfer_admin(&env, &new_admin, &new_admin),
+        BondrError::UnauthorizedAdmin,
+    );
+
+    env.ok(&transfer_admin(&env, &env.admin, &new_admin));
+    assert_eq!(env.state::<Config>(&env.config).admin, new_admin.to_bytes());
+
+    // the old admin is locked out
+    env.err(
+        &env.update_config(&env.settings()),
+        BondrError::UnauthorizedAdmin,
+    );
+    env.ok(&swap_account(
+        env.update_config(&env.settings()),
+        &env.admin,
+        &new_admin,
+    ));
+}
+
+#[test]
+fn pausing_stops_everything_but_exits() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs::default());
+    let disputed = env.create_escrow(EscrowArgs {
+        arbiter: Pubkey::new_unique(),
+        ..EscrowArgs::default()
+    });
+    env.create_badge(&disputed.freelancer.wallet);
+
+    env.ok(&env.set_pause(&env.admin, 0, true));
+    env.err(&env.release(&escrow), BondrError::ProgramPaused);
+    env.err(
+        &migrate(&env, &env.party().wallet, &escrow.address),
+        BondrError::ProgramPaused,
+    );
+    let (_, create) = env.escrow_ix(&env.party(), &env.party(), EscrowArgs::default());
+    env.err(&create, BondrError::ProgramPaused);
+
+    // funds can still be recovered while paused
+    env.ok(&env.cancel(&escrow, true));
+    env.ok(&env.open_dispute(&disputed, &disputed.client.wallet));
+    env.ok(&env.resolve_dispute(&disputed, 5_000));
+
+    env.ok(&env.set_pause(&env.admin, 0, false));
+    env.ok(&create);
+}
+
+#[test]
+fn pausing_single_instructions() {
+    let env = Env::new();
+    let paused = 1 << BondrInstruction::InitializeEscrow as u32;
+
+    let escrow = env.create_escrow(EscrowArgs::default());
+    env.ok(&env.set_pause(&env.admin, paused, false));
+
+    let (_, create) = env.escrow_ix(&env.party(), &env.party(), EscrowArgs::default());
+    env.err(&create, BondrError::ProgramPaused);
+    env.ok(&env.release(&escrow));
+}
+
+#[test]
+fn the_guardian_can_only_pause() {
+    let env = Env::new();
+    let stranger = Pubkey::new_unique();
+
+    env.err(
+        &env.set_pause(&stranger, 0, true),
+        BondrError::UnauthorizedAdmin,
+    );
+
+    env.ok(&env.set_pause(&env.guardian, 0b10, false));
+    env.ok(&env.set_pause(&env.guardian, 0b11, true));
+    env.err(
+        &env.set_pause(&env.guardian, 0b11, false),
+        BondrError::UnauthorizedAdmin,
+    );
+    env.err(
+        &env.set_pause(&env.guardian, 0b01, true),
+        BondrError::UnauthorizedAdmin,
+    );
+
+    env.ok(&env.set_pause(&env.admin, 0, false));
+
+    let mut pause = env.set_pause(&env.admin, 0, true);
+    pause.data[1 + core::mem::offset_of!(SetPause, paused)] = 2;
+    env.err(&pause, BondrError::InvalidInstructionData);
+}
+
+#[test]
+fn gated_instructions_need_the_config() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs::default());
+
+    env.err(
+        &swap_account(env.release(&escrow), &env.config, &Pubkey::new_unique()),
+        BondrError::MissingConfig,
+    );
+
+    // a copy of the config elsewhere can't get past a pause
+    let unpaused = Pubkey::new_unique();
+    env.set_account(&unpaused, env.account(&env.config));
+    env.ok(&env.set_pause(&env.admin, 0, true));
+    env.err(
+        &swap_account(env.release(&escrow), &env.config, &unpaused),
+        BondrError::PdaMismatch,
+    );
+}
+
+#[test]
+fn withdraw_treasury_moves_collected_fees() {
+    let env = Env::new();
+    let destination = env.party();
+
+    let fee = collect_fee(&env, false);
+    env.ok(&withdraw(&env, &destination, false, fee));
+    assert_eq!(env.token_balance(&env.treasury_token), 0);
+    assert_eq!(env.token_balance(&destination.token), TOKEN_BALANCE + fee);
+
+    let fee = collect_fee(&env, true);
+    let lamports = env.lamports(&destination.wallet);
+    env.ok(&withdraw(&env, &destination, true, fee));
+    assert_eq!(env.lamports(&destination.wallet), lamports + fee);
+    assert_eq!(
+        env.lamports(&env.treasury),
+        Rent::default().minimum_balance(0)
+    );
+}
+
+#[test]
+fn withdraw_treasury_keeps_the_treasury_solvent() {
+    let env = Env::new();
+    let destination = env.party();
+    let fee = collect_fee(&env, false);
+
+    env.err(
+        &withdraw(&env, &destination, false, fee + 1),
+        BondrError::InsufficientBalance,
+    );
+    // the rent exempt minimum stays behind
+    env.err(
+        &withdraw(&env, &destination, true, 1),
+        BondrError::InsufficientBalance,
+    );
+}
+
+#[test]
+fn withdraw_treasury_checks_the_accounts() {
+    let env = Env::new();
+    let destination = env.party();
+    let fee = collect_fee(&env, false);
+
+    env.err(
+        &swap_account(
+            withdraw(&env, &destination, false, fee),
+            &env.admin,
+            &destination.wallet,
+        ),
+        BondrError::UnauthorizedAdmin,
+    );
+    env.err(
+        &swap_account(
+            withdraw(&env, &destination, false, fee),
+            &env.treasury,
+            &Pubkey::new_unique(),
+        ),
+        BondrError::InvalidTreasury,
+    );
+
+    let foreign = env.create_token_account(&Pubkey::new_unique(), fee);
+    env.err(
+        &swap_account(
+            withdraw(&env, &destination, false, fee),
+            &env.treasury_token,
+            &foreign,
+        ),
+        BondrError::InvalidTreasury,
+    );
+}
+
+#[test]
+fn an_outdated_config_is_migrated_even_while_paused() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs::default());
+
+    // a paused config written before the reputation minimums existed
+    let mut state = env.state::<Config>(&env.config);
+    state.version = 1;
+    state.paused = true;
+    env.set_state(&env.config, &state);
+    let mut account = env.account(&env.config);
+    account
+        .data
+        .truncate(core::mem::offset_of!(Config, min_reputation_amounts));
+    env.set_account(&env.config, account);
+
+    env.err(&env.release(&escrow), BondrError::AccountNeedsMigration);
+    env.err(
+        &migrate(&env, &env.party().wallet, &escrow.address),
+        BondrError::AccountNeedsMigration,
+    );
+
+    env.ok(&migrate(&env, &env.party().wallet, &env.config));
+    let state = env.state::<Config>(&env.config);
+    assert_eq!(state.version, Config::VERSION);
+    assert_eq!(state.min_reputation_amounts, [0; 8]);
+    assert!(state.paused);
+    env.err(&env.release(&escrow), BondrError::ProgramPaused);
+}
+
+#[test]
+fn migrate_account_grows_short_accounts() {
+    let env = Env::new();
+    let payer = env.party().wallet;
+    let freelancer = env.party().wallet;
+    let badge = env.create_badge(&freelancer);
+
+    // a badge written before its trailing fields existed
+    let mut account = env.account(&badge);
+    account.data.truncate(FreelancerBadge::LEN - 12);
+    env.set_account(&badge, account);
+
+    let update = client::update_freelancer_badge(
+        &UpdateFreelancerBadgeAccounts {
+            admin: env.admin,
+            badge,
+            config: env.config,
+        },
+        &UpdateFreelancerBadge { value: 1 },
+    );
+    env.err(&update, BondrError::AccountNeedsMigration);
+
+    env.ok(&migrate(&env, &payer, &badge));
+    let account = env.account(&badge);
+    assert_eq!(account.data.len(), FreelancerBadge::LEN);
+    assert!(account.lamports >= Rent::default().minimum_balance(FreelancerBadge::LEN));
+    assert_eq!(
+        env.state::<FreelancerBadge>(&badge).freelancer,
+        freelancer.to_bytes()
+    );
+
+    env.ok(&update);
+}
+
+#[test]
+fn migrate_account_starts_the_client_record_at_zero() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs::default());
+    let stats = escrow.client_stats();
+
+    // version 1 stats ended with the dispute counters
+    let mut account = env.account(&stats);
+    account.data.truncate(UserStats::LEN - 28);
+    account.data[1] = 1;
+    env.set_account(&stats, account);
+
+    env.ok(&migrate(&env, &env.party().wallet, &stats));
+    let state = env.state::<UserStats>(&stats);
+    assert_eq!(state.version, UserStats::VERSION);
+    assert_eq!((state.escrows_funded, state.total_funded), (0, 0));
+
+    env.ok(&env.release(&escrow));
+    assert_eq!(env.state::<UserStats>(&stats).escrows_released, 1);
+}
+
+#[test]
+fn version_1_client_records_grow_on_their_next_use() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs::default());
+    let stats = escrow.client_stats();
+
+    let mut account = env.account(&stats);
+    account.data.truncate(UserStats::LEN - 28);
+    account.data[1] = 1;
+    account.lamports = Rent::default().minimum_balance(account.data.len());
+    env.set_account(&stats, account);
+
+    env.ok(&env.release(&escrow));
+    let account = env.account(&stats);
+    assert_eq!(account.data.len(), UserStats::LEN);
+    assert_eq!(
+        account.lamports,
+        Rent::default().minimum_balance(UserStats::LEN)
+    );
+
+    let state = env.state::<UserStats>(&stats);
+    assert_eq!(state.version, UserStats::VERSION);
+    assert_eq!(state.escrows_released, 1);
+    assert_eq!((state.escrows_funded, state.total_funded), (0, 0));
+}
+
+#[test]
+fn migrate_account_clears_what_version_1_kept_in_the_padding() {
+    let env = Env::new();
+    let payer = env.party().wallet;
+    let client = env.party().wallet;
+    let multisig = env.create_multisig(&client, &[client, Pubkey::new_unique()], 2);
+
+    // version 1 multisigs held one escrow's approvals where there is padding now
+    let mut state = env.state::<ClientMultisig>(&multisig);
+    state.version = 1;
+    state.open_approvals = 0x0101;
+    state._padding0 = [1; 35];
+    env.set_state(&multisig, &state);
+
+    env.ok(&migrate(&env, &payer, &multisig));
+    let state = env.state::<ClientMultisig>(&multisig);
+    assert_eq!(state.version, ClientMultisig::VERSION);
+    assert_eq!((state.open_approvals, state._padding0), (0, [0; 35]));
+
+    // and version 1 escrows had no approval record
+    let escrow = env.create_escrow(EscrowArgs::default()).address;
+    let mut state = env.state::<Escrow>(&escrow);
+    state.version = 1;
+    (state.has_approval, state.approval_bump) = (true, 9);
+    env.set_state(&escrow, &state);
+
+    env.ok(&migrate(&env, &payer, &escrow));
+    let state = env.state::<Escrow>(&escrow);
+    assert_eq!(state.version, Escrow::VERSION);
+    assert_eq!((state.has_approval, state.approval_bump), (false, 0));
+}
+
+#[test]
+fn migrate_account_leaves_current_accounts_alone() {
+    let env = Env::new();
+    let payer = env.party().wallet;
+    let badge = env.create_badge(&env.party().wallet);
+    let before = env.account(&badge);
+
+    env.ok(&migrate(&env, &payer, &badge));
+    assert_eq!(env.account(&badge), before);
+
+    env.err(
+        &migrate(&env, &payer, &env.treasury_token),
+        BondrError::InvalidOwner,
+    );
+}
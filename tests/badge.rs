@@ -0,0 +1,200 @@
+//! Freelancer badges and the reputation NFTs minted through the mock mpl-core.
+
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use bondr_pinocchio::{
+    client::{self, find_badge_pda, UpdateFreelancerBadgeAccounts, PROGRAM_ID},
+    constants::{PROFESSIONAL_METADATA_URI, VERIFIED_METADATA_URI},
+    errors::BondrError,
+    instructions::{InitializeFreelancerBadge, UpdateFreelancerBadge},
+    states::{FreelancerBadge, ReputationTier},
+};
+use common::*;
+use solana_sdk::{instruction::Instruction, program_error::ProgramError, pubkey::Pubkey};
+
+fn update_badge(env: &Env, admin: &Pubkey, freelancer: &Pubkey, value: u64) -> Instruction {
+    client::update_freelancer_badge(
+        &UpdateFreelancerBadgeAccounts {
+            admin: *admin,
+            badge: find_badge_pda(freelancer).0,
+            config: env.config,
+        },
+        &UpdateFreelancerBadge { value },
+    )
+}
+
+#[test]
+fn initialize_freelancer_badge_starts_unranked() {
+    let env = Env::new();
+    let freelancer = env.party().wallet;
+    let badge = env.create_badge(&freelancer);
+
+    let state = env.state::<FreelancerBadge>(&badge);
+    assert_eq!(state.freelancer, freelancer.to_bytes());
+    assert_eq!(state.tier, ReputationTier::Unranked);
+    assert_eq!(state.completed_escrows, 0);
+    assert_eq!(state.bump, find_badge_pda(&freelancer).1);
+
+    env.err_with(
+        &env.badge_ix(&freelancer),
+        ProgramError::AccountAlreadyInitialized,
+    );
+}
+
+#[test]
+fn initialize_freelancer_badge_checks_the_freelancer() {
+    let env = Env::new();
+    let freelancer = env.party().wallet;
+
+    env.err_with(
+        &unsigned(env.badge_ix(&freelancer), &freelancer),
+        ProgramError::MissingRequiredSignature,
+    );
+
+    // someone else's badge address
+    let other = find_badge_pda(&Pubkey::new_unique()).0;
+    env.err(
+        &swap_account(
+            env.badge_ix(&freelancer),
+            &find_badge_pda(&freelancer).0,
+            &other,
+        ),
+        BondrError::PdaMismatch,
+    );
+
+    // nor a second badge at another bump
+    let (badge, bump) = find_badge_pda(&freelancer);
+    let (shadow, shadow_bump) = (0..bump)
+        .rev()
+        .find_map(|b| {
+            let seeds: &[&[u8]] = &[FreelancerBadge::SEED.as_bytes(), freelancer.as_ref(), &[b]];
+            Pubkey::create_program_address(seeds, &PROGRAM_ID)
+                .ok()
+                .map(|shadow| (shadow, b))
+        })
+        .unwrap();
+    let mut create = env.badge_ix(&freelancer);
+    create.data[1 + core::mem::offset_of!(InitializeFreelancerBadge, bump)] = shadow_bump;
+    env.err(
+        &swap_account(create, &badge, &shadow),
+        BondrError::PdaMismatch,
+    );
+}
+
+#[test]
+fn update_freelancer_badge_credits_an_escrow() {
+    let env = Env::new();
+    let freelancer = env.party().wallet;
+    let badge = env.create_badge(&freelancer);
+
+    env.ok(&update_badge(&env, &env.admin, &freelancer, 5_000));
+    env.ok(&update_badge(&env, &env.admin, &freelancer, 7_000));
+
+    let state = env.state::<FreelancerBadge>(&badge);
+    assert_eq!(state.completed_escrows, 2);
+    assert_eq!(state.total_value_completed, 12_000);
+}
+
+#[test]
+fn update_freelancer_badge_is_admin_only() {
+    let env = Env::new();
+    let freelancer = env.party().wallet;
+    env.create_badge(&freelancer);
+
+    env.err(
+        &update_badge(&env, &freelancer, &freelancer, 5_000),
+        BondrError::UnauthorizedAdmin,
+    );
+    env.err(
+        &update_badge(&env, &env.admin, &freelancer, 0),
+        BondrError::InvalidAmountZero,
+    );
+}
+
+#[test]
+fn mint_reputation_nft_mints_each_tier_once() {
+    let env = Env::new();
+    let freelancer = env.party().wallet;
+    let badge = env.create_badge(&freelancer);
+
+    env.set_completed_escrows(&freelancer, 3);
+    let asset = Pubkey::new_unique();
+    env.ok(&env.mint_nft(&freelancer, &asset));
+
+    assert_eq!(env.account(&asset).owner, MPL_CORE);
+    assert_eq!(
+        env.asset_metadata(&asset),
+        (
+            "Bondr Verified Badge".to_string(),
+            VERIFIED_METADATA_URI.to_string()
+        )
+    );
+    assert_eq!(
+        env.state::<FreelancerBadge>(&badge).tier,
+        ReputationTier::Verified
+    );
+
+    env.err(
+        &env.mint_nft(&freelancer, &Pubkey::new_unique()),
+        BondrError::NFTAlreadyMinted,
+    );
+
+    // reaching the next tier allows the next badge
+    env.set_completed_escrows(&freelancer, 10);
+    let asset = Pubkey::new_unique();
+    env.ok(&env.mint_nft(&freelancer, &asset));
+    assert_eq!(
+        env.asset_metadata(&asset).1,
+        PROFESSIONAL_METADATA_URI.to_string()
+    );
+    assert_eq!(
+        env.state::<FreelancerBadge>(&badge).tier,
+        ReputationTier::Professional
+    );
+}
+
+#[test]
+fn mint_reputation_nft_needs_enough_escrows() {
+    let env = Env::new();
+    let freelancer = env.party().wallet;
+    env.create_badge(&freelancer);
+    env.set_completed_escrows(&freelancer, 2);
+
+    env.err(
+        &env.mint_nft(&freelancer, &Pubkey::new_unique()),
+        BondrError::InsufficientEscrows,
+    );
+}
+
+#[test]
+fn mint_reputation_nft_checks_the_accounts() {
+    let env = Env::new();
+    let freelancer = env.party().wallet;
+    env.create_badge(&freelancer);
+    env.set_completed_escrows(&freelancer, 3);
+    let mint = env.mint_nft(&freelancer, &Pubkey::new_unique());
+
+    env.err(
+        &swap_account(mint.clone(), &MPL_CORE, &Pubkey::new_unique()),
+        BondrError::InvalidMplKey,
+    );
+    env.err(
+        &swap_account(mint.clone(), &env.collection, &Pubkey::new_unique()),
+        BondrError::InvalidMplKey,
+    );
+
+    // another freelancer's badge
+    let other = env.party().wallet;
+    let other_badge = env.create_badge(&other);
+    env.err(
+        &swap_account(mint.clone(), &find_badge_pda(&freelancer).0, &other_badge),
+        BondrError::UnauthorizedSender,
+    );
+
+    env.err_with(
+        &unsigned(mint, &freelancer),
+        ProgramError::MissingRequiredSignature,
+    );
+}
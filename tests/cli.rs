@@ -0,0 +1,1028 @@
+//! The `bondr` CLI, run as a binary against a stub JSON-RPC validator on localhost that
+//! serves fixed accounts and records the transactions it is sent.
+
+#![cfg(feature = "cli")]
+
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    process::{self, Command, Output},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bondr_pinocchio::{
+    client::{self, *},
+    constants::{MAX_MILESTONES, MAX_MULTISIG_MEMBERS},
+    events::{EscrowCreated, Event, EVENT_VERSION},
+    instructions::{
+        AddMultisigMember, ChangeThreshold, ClaimMilestone, ClaimPayment, InitializeEscrow,
+        InitializeFreelancerBadge, InitializeMultisigClient, ReleaseMilestone,
+        RemoveMultisigMember, RotateMultisigMember,
+    },
+    states::{
+        AccountDiscriminator, Config, ConfigSettings, DataLen, Escrow, Milestone, ProgramAccount,
+        MPL_CORE_ID, TOKEN_PROGRAM_ID,
+    },
+};
+use serde_json::{json, Value};
+use solana_instruction::Instruction;
+use solana_keypair::{write_keypair_file, Keypair};
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+const SYSVAR_RENT_ID: Pubkey =
+    Pubkey::from_str_const("SysvarRent111111111111111111111111111111111");
+const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+const SIGNATURE: &str = "1111111111111111111111111111111111111111111111111111111111111111";
+
+/// A stub validator: answers the RPC calls the CLI makes and keeps what it is sent.
+struct Validator {
+    url: String,
+    sent: Arc<Mutex<Vec<Transaction>>>,
+}
+
+#[derive(Default)]
+struct Ledger {
+    accounts: HashMap<String, (Pubkey, Vec<u8>)>, // owner and data by address
+    logs: Vec<String>,                            // of every transaction
+}
+
+impl Ledger {
+    fn with_account(mut self, address: &Pubkey, owner: &Pubkey, data: Vec<u8>) -> Self {
+        self.accounts.insert(address.to_string(), (*owner, data));
+        self
+    }
+
+    fn with_logs(mut self, logs: Vec<String>) -> Self {
+        self.logs = logs;
+        self
+    }
+
+    fn start(self) -> Validator {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let sent = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = sent.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                self.serve(stream.unwrap(), &recorded);
+            }
+        });
+
+        Validator { url, sent }
+    }
+
+    fn serve(&self, mut stream: TcpStream, sent: &Mutex<Vec<Transaction>>) {
+        let mut reader = BufReader::new(&mut stream);
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line == "\r\n" {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        let request: Value = serde_json::from_slice(&body).unwrap();
+
+        let params = &request["params"];
+        let result = match request["method"].as_str().unwrap() {
+            "getAccountInfo" => {
+                let value = self
+                    .accounts
+                    .get(params[0].as_str().unwrap())
+                    .map(|(owner, data)| {
+                        json!({
+                            "data": [STANDARD.encode(data), "base64"],
+                            "owner": owner.to_string(),
+                            "lamports": 1,
+                            "executable": false,
+                        })
+                    });
+                json!({ "context": { "slot": 1 }, "value": value })
+            }
+            "getLatestBlockhash" => json!({
+                "context": { "slot": 1 },
+                "value": { "blockhash": Pubkey::default().to_string(), "lastValidBlockHeight": 1 },
+            }),
+            "sendTransaction" => {
+                let wire = STANDARD.decode(params[0].as_str().unwrap()).unwrap();
+                sent.lock()
+                    .unwrap()
+                    .push(bincode::deserialize(&wire).unwrap());
+                json!(SIGNATURE)
+            }
+            "getSignatureStatuses" => json!({
+                "context": { "slot": 1 },
+                "value": [{ "confirmationStatus": "confirmed", "err": null }],
+            }),
+            "getTransaction" => json!({ "meta": { "logMessages": self.logs } }),
+            method => panic!("unexpected rpc call {method}"),
+        };
+
+        let body = json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }).to_string();
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+             Connection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+    }
+}
+
+impl Validator {
+    fn sent(&self) -> Vec<Transaction> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    /// The only transaction sent, and the instructions in it.
+    fn transaction(&self) -> (Transaction, Vec<Instruction>) {
+        let sent = self.sent();
+        assert_eq!(sent.len(), 1, "one transaction");
+        let transaction = sent[0].clone();
+
+        let message = &transaction.message;
+        let instructions = message
+            .instructions
+            .iter()
+            .map(|ix| Instruction {
+                program_id: message.account_keys[ix.program_id_index as usize],
+                // only the keys: signer and writable flags are merged across the message
+                accounts: ix
+                    .accounts
+                    .iter()
+                    .map(|&i| {
+                        solana_instruction::AccountMeta::new_readonly(
+                            message.account_keys[i as usize],
+                            false,
+                        )
+                    })
+                    .collect(),
+                data: ix.data.clone(),
+            })
+            .collect();
+        (transaction, instructions)
+    }
+}
+
+/// A keypair and the file the CLI reads it from.
+struct Wallet {
+    keypair: Keypair,
+    path: PathBuf,
+}
+
+impl Wallet {
+    fn new() -> Self {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let keypair = Keypair::new();
+        let path = env::temp_dir().join(format!(
+            "bondr-cli-{}-{}.json",
+            process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        write_keypair_file(&keypair, &path).unwrap();
+        Wallet { keypair, path }
+    }
+
+    fn key(&self) -> Pubkey {
+        self.keypair.pubkey()
+    }
+
+    fn path(&self) -> &str {
+        self.path.to_str().unwrap()
+    }
+}
+
+impl Drop for Wallet {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn bondr(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_bondr"))
+        .args(args)
+        .env("HOME", env::temp_dir())
+        .output()
+        .unwrap()
+}
+
+/// Runs a command against `validator`, signed by `payer`, which must succeed.
+fn send(validator: &Validator, payer: &Wallet, args: &[&str]) -> String {
+    let mut all = vec!["--url", &validator.url, "--keypair", payer.path()];
+    all.extend(args);
+
+    let output = bondr(&all);
+    assert!(
+        output.status.success(),
+        "bondr {args:?}: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Runs a command that must fail, returning its error message.
+fn fails(args: &[&str]) -> String {
+    let output = bondr(args);
+    assert!(!output.status.success(), "bondr {args:?} succeeded");
+    String::from_utf8(output.stderr).unwrap()
+}
+
+/// Checks `instruction` calls `expected.program_id` with the same accounts and data.
+fn assert_instruction(instruction: &Instruction, expected: &Instruction) {
+    assert_eq!(instruction.program_id, expected.program_id);
+    let keys = |ix: &Instruction| ix.accounts.iter().map(|a| a.pubkey).collect::<Vec<_>>();
+    assert_eq!(keys(instruction), keys(expected));
+    assert_eq!(instruction.data, expected.data);
+}
+
+fn bytes_of<T: DataLen>(state: &T) -> Vec<u8> {
+    unsafe { core::slice::from_raw_parts(state as *const T as *const u8, T::LEN) }.to_vec()
+}
+
+/// A native SOL escrow between `client` and `freelancer`, as the program stores it.
+fn escrow_data(client: &Pubkey, freelancer: &Pubkey, multisig: Option<Pubkey>) -> Vec<u8> {
+    bytes_of(&Escrow {
+        discriminator: AccountDiscriminator::Escrow as u8,
+        version: Escrow::VERSION,
+        sender: client.to_bytes(),
+        receiver: freelancer.to_bytes(),
+        mint: [0; 32],
+        token_program: [0; 32],
+        _padding0: [0; 6],
+        amount: 1_000,
+        reference_seed: 7,
+        is_released: false,
+        bump: 255,
+        client_multisig: multisig.unwrap_or_default().to_bytes(),
+        has_multisig: multisig.is_some(),
+        has_approval: multisig.is_some(),
+        approval_bump: 255,
+        _padding1: [0; 3],
+        created_at: 0,
+        deadline: 0,
+        auto_release_at: 0,
+        milestone_count: 0,
+        _padding2: [0; 7],
+        milestones: [Milestone::EMPTY; MAX_MILESTONES],
+        arbiter: [0; 32],
+        is_disputed: false,
+        is_native: true,
+        _padding3: [0; 6],
+    })
+}
+
+#[test]
+fn pda_prints_the_address_and_bump() {
+    let (client, freelancer) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (escrow, bump) = find_escrow_pda(&client, &freelancer, 7);
+
+    let output = bondr(&[
+        "pda",
+        "escrow",
+        &client.to_string(),
+        &freelancer.to_string(),
+        "7",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("{escrow} (bump {bump})\n")
+    );
+
+    // global options are accepted and ignored
+    let output = bondr(&["--url", "http://localhost:1", "pda", "config"]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("{} (bump {})\n", find_config_pda().0, find_config_pda().1)
+    );
+}
+
+#[test]
+fn help_prints_the_usage() {
+    for args in [&[][..], &["--help"], &["release", "--help"]] {
+        let output = bondr(args);
+        assert!(output.status.success());
+        assert!(String::from_utf8(output.stdout)
+            .unwrap()
+            .starts_with("usage: bondr"));
+    }
+}
+
+#[test]
+fn malformed_arguments_are_rejected() {
+    let key = Pubkey::new_unique().to_string();
+
+    assert!(fails(&["frobnicate"]).contains("unknown command `frobnicate`"));
+    assert!(fails(&["pda"]).contains("missing <target>"));
+    assert!(fails(&["pda", "vault"]).contains("unknown target `vault`"));
+    assert!(fails(&["pda", "badge"]).contains("missing <freelancer>"));
+    assert!(fails(&["pda", "badge", "not-a-key"]).contains("invalid <freelancer> `not-a-key`"));
+    assert!(fails(&["pda", "badge", &key, "extra"]).contains("unexpected argument `extra`"));
+    assert!(fails(&["pda", "config", "--bump", "1"]).contains("unexpected option --bump"));
+    assert!(fails(&["pda", "escrow", &key, &key, "-1"]).contains("invalid <reference> `-1`"));
+    assert!(fails(&["show", "config", "--url"]).contains("--url needs a value"));
+}
+
+#[test]
+fn only_plain_http_urls_are_supported() {
+    let error = fails(&["--url", "https://api.devnet.solana.com", "show", "config"]);
+    assert!(error.contains("https is not supported"), "{error}");
+
+    let error = fails(&["--url", "ws://127.0.0.1:8900", "show", "config"]);
+    assert!(error.contains("only http:// urls are supported"), "{error}");
+}
+
+#[test]
+fn nothing_is_sent_while_an_argument_is_unread() {
+    let validator = Ledger::default().start();
+    let payer = Wallet::new();
+
+    let output = bondr(&[
+        "--url",
+        &validator.url,
+        "--keypair",
+        payer.path(),
+        "release",
+        &Pubkey::new_unique().to_string(),
+        "7",
+        "--milestnoe",
+        "1",
+    ]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("unexpected option --milestnoe"));
+    assert!(validator.sent().is_empty());
+}
+
+#[test]
+fn create_escrow_builds_initialize_escrow_and_prints_its_event() {
+    let payer = Wallet::new();
+    let (freelancer, arbiter) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (escrow, bump) = find_escrow_pda(&payer.key(), &freelancer, 7);
+    let (sender_stats, stats_bump) = find_user_stats_pda(&payer.key());
+
+    let event = EscrowCreated {
+        escrow: escrow.to_bytes(),
+        sender: payer.key().to_bytes(),
+        receiver: freelancer.to_bytes(),
+        mint: [0; 32],
+        amount: 1_000,
+        reference_seed: 7,
+        deadline: 1_800_000_000,
+        auto_release_at: 0,
+        milestone_count: 2,
+        has_multisig: false,
+        is_native: true,
+    };
+    let event_bytes = unsafe {
+        core::slice::from_raw_parts(
+            &event as *const EscrowCreated as *const u8,
+            core::mem::size_of::<EscrowCreated>(),
+        )
+    };
+    let validator = Ledger::default()
+        .with_logs(vec![
+            format!("Program {PROGRAM_ID} invoke [1]"),
+            format!(
+                "Program data: {} {}",
+                STANDARD.encode([EVENT_VERSION, EscrowCreated::KIND as u8]),
+                STANDARD.encode(event_bytes)
+            ),
+            format!("Program {PROGRAM_ID} success"),
+        ])
+        .start();
+
+    let stdout = send(
+        &validator,
+        &payer,
+        &[
+            "create-escrow",
+            &freelancer.to_string(),
+            "1000",
+            "7",
+            "--deadline",
+            "1800000000",
+            "--arbiter",
+            &arbiter.to_string(),
+            "--milestones",
+            "400,600",
+        ],
+    );
+    assert!(stdout.contains(&format!("escrow {escrow}")));
+    assert!(stdout.contains(SIGNATURE));
+    assert!(stdout.contains("\"event\": \"EscrowCreated\""), "{stdout}");
+
+    let mut milestone_amounts = [0; MAX_MILESTONES];
+    milestone_amounts[..2].copy_from_slice(&[400, 600]);
+    let (transaction, instructions) = validator.transaction();
+    assert_eq!(transaction.message.account_keys[0], payer.key());
+    assert_eq!(instructions.len(), 1);
+    assert_instruction(
+        &instructions[0],
+        &client::initialize_escrow(
+            &InitializeEscrowAccounts {
+                sender: payer.key(),
+                receiver: freelancer,
+                escrow,
+                sender_stats,
+                client_multisig: Pubkey::default(),
+                multisig_approval: Pubkey::default(),
+                sender_token_account: Pubkey::default(),
+                escrow_token_account: Pubkey::default(),
+                token_mint: Pubkey::default(),
+                sysvar_rent: SYSVAR_RENT_ID,
+                token_program: Pubkey::default(),
+                system_program: Pubkey::default(),
+                config: find_config_pda().0,
+            },
+            &InitializeEscrow {
+                amount: 1_000,
+                reference_seed: 7,
+                deadline: 1_800_000_000,
+                auto_release_at: 0,
+                milestone_amounts,
+                arbiter: arbiter.to_bytes(),
+                milestone_count: 2,
+                bump,
+                stats_bump,
+                is_multisig: false,
+                is_native: true,
+                approval_bump: 0,
+                _padding: [0; 2],
+            },
+        ),
+    );
+}
+
+#[test]
+fn create_escrow_in_a_token_creates_the_vault_first() {
+    let token_program = Pubkey::new_from_array(TOKEN_PROGRAM_ID);
+    let mint = Pubkey::new_unique();
+    let validator = Ledger::default()
+        .with_account(&mint, &token_program, vec![0; 82])
+        .start();
+    let payer = Wallet::new();
+    let freelancer = Pubkey::new_unique();
+    let (escrow, _) = find_escrow_pda(&payer.key(), &freelancer, 7);
+
+    send(
+        &validator,
+        &payer,
+        &[
+            "create-escrow",
+            &freelancer.to_string(),
+            "1000",
+            "7",
+            "--mint",
+            &mint.to_string(),
+        ],
+    );
+
+    let ata = |owner: &Pubkey| {
+        Pubkey::find_program_address(
+            &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+            &ASSOCIATED_TOKEN_PROGRAM_ID,
+        )
+        .0
+    };
+    let (_, instructions) = validator.transaction();
+    assert_eq!(instructions.len(), 2);
+    assert_eq!(instructions[0].program_id, ASSOCIATED_TOKEN_PROGRAM_ID);
+    assert_eq!(instructions[0].accounts[1].pubkey, ata(&escrow));
+    assert_eq!(instructions[0].data, [1]);
+
+    let accounts: Vec<Pubkey> = instructions[1].accounts.iter().map(|a| a.pubkey).collect();
+    assert_eq!(accounts[6], ata(&payer.key())); // sender_token_account
+    assert_eq!(accounts[7], ata(&escrow)); // escrow_token_account
+    assert_eq!(accounts[8], mint);
+    assert_eq!(accounts[10], token_program);
+}
+
+#[test]
+fn release_builds_release_payment_or_milestone() {
+    let validator = Ledger::default().start();
+    let payer = Wallet::new();
+    let freelancer = Pubkey::new_unique();
+    let escrow = find_escrow_pda(&payer.key(), &freelancer, 7).0;
+    let sender_stats = find_user_stats_pda(&payer.key()).0;
+
+    send(
+        &validator,
+        &payer,
+        &["release", &freelancer.to_string(), "7"],
+    );
+    send(
+        &validator,
+        &payer,
+        &["release", &freelancer.to_string(), "7", "--milestone", "2"],
+    );
+
+    let sent = validator.sent();
+    assert_eq!(sent.len(), 2);
+    let instruction = |transaction: &Transaction| {
+        let message = &transaction.message;
+        let ix = &message.instructions[0];
+        (
+            ix.accounts
+                .iter()
+                .map(|&i| message.account_keys[i as usize])
+                .collect::<Vec<_>>(),
+            ix.data.clone(),
+        )
+    };
+    let keys = |ix: Instruction| -> (Vec<Pubkey>, Vec<u8>) {
+        (ix.accounts.iter().map(|a| a.pubkey).collect(), ix.data)
+    };
+
+    assert_eq!(
+        instruction(&sent[0]),
+        keys(client::release_payment(&ReleasePaymentAccounts {
+            client: payer.key(),
+            escrow,
+            sender_stats,
+            system_program: Pubkey::default(),
+            config: find_config_pda().0,
+        }))
+    );
+    assert_eq!(
+        instruction(&sent[1]),
+        keys(client::release_milestone(
+            &ReleaseMilestoneAccounts {
+                client: payer.key(),
+                escrow,
+                sender_stats,
+                system_program: Pubkey::default(),
+                config: find_config_pda().0,
+            },
+            &ReleaseMilestone { index: 2 },
+        ))
+    );
+}
+
+#[test]
+fn approve_builds_approve_multisig_release() {
+    let (client, freelancer, multisig) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let escrow = find_escrow_pda(&client, &freelancer, 7).0;
+    let validator = Ledger::default()
+        .with_account(
+            &escrow,
+            &PROGRAM_ID,
+            escrow_data(&client, &freelancer, Some(multisig)),
+        )
+        .start();
+    let member = Wallet::new();
+
+    send(
+        &validator,
+        &member,
+        &["approve", &client.to_string(), &freelancer.to_string(), "7"],
+    );
+
+    let (_, instructions) = validator.transaction();
+    assert_instruction(
+        &instructions[0],
+        &client::approve_multisig_release(&ApproveMultisigReleaseAccounts {
+            member: member.key(),
+            multisig,
+            escrow,
+            multisig_approval: find_multisig_approval_pda(&multisig, &escrow).0,
+            sender_stats: find_user_stats_pda(&client).0,
+            system_program: Pubkey::default(),
+            config: find_config_pda().0,
+        }),
+    );
+
+    // an escrow without a multisig has nothing to approve
+    let other = find_escrow_pda(&client, &freelancer, 8).0;
+    let validator = Ledger::default()
+        .with_account(&other, &PROGRAM_ID, escrow_data(&client, &freelancer, None))
+        .start();
+    let output = bondr(&[
+        "--url",
+        &validator.url,
+        "--keypair",
+        member.path(),
+        "approve",
+        &client.to_string(),
+        &freelancer.to_string(),
+        "8",
+    ]);
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("has no multisig"));
+}
+
+#[test]
+fn claim_builds_claim_payment_or_milestone() {
+    let freelancer = Wallet::new();
+    let client = Pubkey::new_unique();
+    let escrow = find_escrow_pda(&client, &freelancer.key(), 7).0;
+    let validator = Ledger::default()
+        .with_account(
+            &escrow,
+            &PROGRAM_ID,
+            escrow_data(&client, &freelancer.key(), None),
+        )
+        .start();
+    let (receiver_stats, receiver_stats_bump) = find_user_stats_pda(&freelancer.key());
+
+    send(
+        &validator,
+        &freelancer,
+        &["claim", &client.to_string(), "7"],
+    );
+    let (_, instructions) = validator.transaction();
+    assert_eq!(
+        instructions.len(),
+        1,
+        "native escrows need no token accounts"
+    );
+    assert_instruction(
+        &instructions[0],
+        &client::claim_payment(
+            &ClaimPaymentAccounts {
+                client,
+                freelancer: freelancer.key(),
+                escrow,
+                receiver_stats,
+                multisig: Pubkey::default(),
+                multisig_approval: find_multisig_approval_pda(&Pubkey::default(), &escrow).0,
+                escrow_token_account: Pubkey::default(),
+                receiver_token_account: Pubkey::default(),
+                token_mint: Pubkey::default(),
+                token_program: Pubkey::default(),
+                system_program: Pubkey::default(),
+                config: find_config_pda().0,
+                treasury: find_treasury_pda().0,
+                treasury_token_account: Pubkey::default(),
+                receiver_badge: find_badge_pda(&freelancer.key()).0,
+            },
+            &ClaimPayment {
+                receiver_stats_bump,
+            },
+        ),
+    );
+
+    let validator = Ledger::default()
+        .with_account(
+            &escrow,
+            &PROGRAM_ID,
+            escrow_data(&client, &freelancer.key(), None),
+        )
+        .start();
+    send(
+        &validator,
+        &freelancer,
+        &["claim", &client.to_string(), "7", "--milestone", "1"],
+    );
+    let (_, instructions) = validator.transaction();
+    assert_instruction(
+        &instructions[0],
+        &client::claim_milestone(
+            &ClaimMilestoneAccounts {
+                client,
+                freelancer: freelancer.key(),
+                escrow,
+                receiver_stats,
+                escrow_token_account: Pubkey::default(),
+                receiver_token_account: Pubkey::default(),
+                token_mint: Pubkey::default(),
+                token_program: Pubkey::default(),
+                system_program: Pubkey::default(),
+                config: find_config_pda().0,
+                treasury: find_treasury_pda().0,
+                treasury_token_account: Pubkey::default(),
+                receiver_badge: find_badge_pda(&freelancer.key()).0,
+            },
+            &ClaimMilestone {
+                index: 1,
+                receiver_stats_bump,
+            },
+        ),
+    );
+}
+
+#[test]
+fn cancel_builds_cancel_escrow_cosigned_by_the_freelancer() {
+    let (payer, freelancer) = (Wallet::new(), Wallet::new());
+    let escrow = find_escrow_pda(&payer.key(), &freelancer.key(), 7).0;
+    let validator = Ledger::default()
+        .with_account(
+            &escrow,
+            &PROGRAM_ID,
+            escrow_data(&payer.key(), &freelancer.key(), None),
+        )
+        .start();
+
+    send(
+        &validator,
+        &payer,
+        &[
+            "cancel",
+            &freelancer.key().to_string(),
+            "7",
+            "--receiver-keypair",
+            freelancer.path(),
+        ],
+    );
+
+    let (transaction, instructions) = validator.transaction();
+    assert_eq!(transaction.signatures.len(), 2);
+    assert!(transaction.message.is_signer(
+        transaction
+            .message
+            .account_keys
+            .iter()
+            .position(|key| *key == freelancer.key())
+            .unwrap()
+    ));
+    assert_instruction(
+        &instructions[0],
+        &client::cancel_escrow(
+            &CancelEscrowAccounts {
+                sender: payer.key(),
+                receiver: freelancer.key(),
+                escrow,
+                sender_stats: find_user_stats_pda(&payer.key()).0,
+                multisig: Pubkey::default(),
+                multisig_approval: find_multisig_approval_pda(&Pubkey::default(), &escrow).0,
+                escrow_token_account: Pubkey::default(),
+                sender_token_account: Pubkey::default(),
+                token_mint: Pubkey::default(),
+                token_program: Pubkey::default(),
+                system_program: Pubkey::default(),
+            },
+            true,
+        ),
+    );
+
+    // the co-signer must be the escrow's freelancer
+    let stranger = Wallet::new();
+    let error = fails(&[
+        "--url",
+        &validator.url,
+        "--keypair",
+        payer.path(),
+        "cancel",
+        &freelancer.key().to_string(),
+        "7",
+        "--receiver-keypair",
+        stranger.path(),
+    ]);
+    assert!(error.contains(&format!("--receiver-keypair is not {}", freelancer.key())));
+}
+
+#[test]
+fn init_badge_builds_initialize_freelancer_badge() {
+    let validator = Ledger::default().start();
+    let freelancer = Wallet::new();
+    let (badge, bump) = find_badge_pda(&freelancer.key());
+
+    let stdout = send(&validator, &freelancer, &["init-badge"]);
+    assert!(stdout.contains(&format!("badge {badge}")));
+
+    let (_, instructions) = validator.transaction();
+    assert_instruction(
+        &instructions[0],
+        &client::initialize_freelancer_badge(
+            &InitializeFreelancerBadgeAccounts {
+                freelancer: freelancer.key(),
+                badge,
+                system_program: Pubkey::default(),
+                sysvar_rent: SYSVAR_RENT_ID,
+                config: find_config_pda().0,
+            },
+            &InitializeFreelancerBadge { bump },
+        ),
+    );
+}
+
+#[test]
+fn mint_nft_builds_mint_reputation_nft_into_the_configured_collection() {
+    let collection = Pubkey::new_unique();
+    let config = find_config_pda().0;
+    let settings = ConfigSettings {
+        guardian: [0; 32],
+        fee_bps: 0,
+        tier_fee_discount_bps: [0; 4],
+        _padding0: [0; 2],
+        tier_thresholds: [1, 2, 3],
+        badge_collection: collection.to_bytes(),
+        allowed_mints: [[0; 32]; 8],
+        allowed_mint_count: 0,
+        max_multisig_members: MAX_MULTISIG_MEMBERS as u8,
+        tier_uris: [[0; 128]; 3],
+        _padding1: [0; 2],
+    };
+    let validator = Ledger::default()
+        .with_account(
+            &config,
+            &PROGRAM_ID,
+            bytes_of(&Config {
+                discriminator: AccountDiscriminator::Config as u8,
+                version: Config::VERSION,
+                admin: [1; 32],
+                _padding0: [0; 2],
+                settings,
+                paused_instructions: 0,
+                paused: false,
+                bump: find_config_pda().1,
+                treasury_bump: find_treasury_pda().1,
+                _padding1: [0; 1],
+                min_reputation_amounts: [0; 8],
+            }),
+        )
+        .start();
+    let freelancer = Wallet::new();
+
+    let stdout = send(&validator, &freelancer, &["mint-nft"]);
+    let asset: Pubkey = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("asset "))
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    let (transaction, instructions) = validator.transaction();
+    assert_eq!(transaction.signatures.len(), 2, "the new asset signs");
+    assert_instruction(
+        &instructions[0],
+        &client::mint_reputation_nft(&MintReputationNftAccounts {
+            freelancer: freelancer.key(),
+            badge: find_badge_pda(&freelancer.key()).0,
+            asset,
+            collection,
+            config,
+            mpl_core_program: Pubkey::new_from_array(MPL_CORE_ID),
+            system_program: Pubkey::default(),
+        }),
+    );
+}
+
+#[test]
+fn init_multisig_builds_initialize_multisig_client() {
+    let validator = Ledger::default().start();
+    let client = Wallet::new();
+    let members = [Pubkey::new_unique(), Pubkey::new_unique(), client.key()];
+    let (multisig, bump) = find_multisig_pda(&client.key());
+
+    let stdout = send(
+        &validator,
+        &client,
+        &[
+            "init-multisig",
+            "2",
+            &members[0].to_string(),
+            &members[1].to_string(),
+            &members[2].to_string(),
+        ],
+    );
+    assert!(stdout.contains(&format!("multisig {multisig}")));
+
+    let mut member_keys = [[0; 32]; MAX_MULTISIG_MEMBERS];
+    for (slot, member) in member_keys.iter_mut().zip(&members) {
+        *slot = member.to_bytes();
+    }
+    let (_, instructions) = validator.transaction();
+    assert_instruction(
+        &instructions[0],
+        &client::initialize_multisig_client(
+            &InitializeMultisigClientAccounts {
+                client: client.key(),
+                multisig,
+                system_program: Pubkey::default(),
+                sysvar_rent: SYSVAR_RENT_ID,
+                config: find_config_pda().0,
+            },
+            &InitializeMultisigClient {
+                members: member_keys,
+                member_count: 3,
+                threshold: 2,
+                bump,
+            },
+        ),
+    );
+
+    let too_many: Vec<String> = (0..=MAX_MULTISIG_MEMBERS)
+        .map(|_| Pubkey::new_unique().to_string())
+        .collect();
+    let mut args = vec![
+        "--url",
+        &validator.url,
+        "--keypair",
+        client.path(),
+        "init-multisig",
+        "2",
+    ];
+    args.extend(too_many.iter().map(String::as_str));
+    assert!(fails(&args).contains("at most 5 members"));
+}
+
+#[test]
+fn multisig_changes_are_signed_by_every_approver() {
+    let payer = Wallet::new();
+    let approver = Wallet::new();
+    let client = Pubkey::new_unique();
+    let multisig = find_multisig_pda(&client).0;
+    let (old_member, new_member) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = || (multisig, find_config_pda().0);
+    let signers = [payer.key(), approver.key()];
+
+    let cases: [(Vec<String>, Instruction); 4] = [
+        (
+            vec!["multisig-add".into(), new_member.to_string()],
+            client::add_multisig_member(
+                &AddMultisigMemberAccounts {
+                    multisig: accounts().0,
+                    config: accounts().1,
+                },
+                &AddMultisigMember {
+                    member: new_member.to_bytes(),
+                },
+                &signers,
+            ),
+        ),
+        (
+            vec!["multisig-remove".into(), old_member.to_string()],
+            client::remove_multisig_member(
+                &RemoveMultisigMemberAccounts {
+                    multisig: accounts().0,
+                    config: accounts().1,
+                },
+                &RemoveMultisigMember {
+                    member: old_member.to_bytes(),
+                },
+                &signers,
+            ),
+        ),
+        (
+            vec![
+                "multisig-rotate".into(),
+                old_member.to_string(),
+                new_member.to_string(),
+            ],
+            client::rotate_multisig_member(
+                &RotateMultisigMemberAccounts {
+                    multisig: accounts().0,
+                    config: accounts().1,
+                },
+                &RotateMultisigMember {
+                    old_member: old_member.to_bytes(),
+                    new_member: new_member.to_bytes(),
+                },
+                &signers,
+            ),
+        ),
+        (
+            vec!["multisig-threshold".into(), "3".into()],
+            client::change_threshold(
+                &ChangeThresholdAccounts {
+                    multisig: accounts().0,
+                    config: accounts().1,
+                },
+                &ChangeThreshold { threshold: 3 },
+                &signers,
+            ),
+        ),
+    ];
+
+    for (command, expected) in cases {
+        let validator = Ledger::default().start();
+        let client = client.to_string();
+        let mut args = vec![command[0].as_str(), client.as_str()];
+        args.extend(command[1..].iter().map(String::as_str));
+        args.extend(["--approvers", approver.path()]);
+
+        send(&validator, &payer, &args);
+
+        let (transaction, instructions) = validator.transaction();
+        assert_eq!(transaction.signatures.len(), 2, "{command:?}");
+        assert_instruction(&instructions[0], &expected);
+    }
+}
@@ -0,0 +1,100 @@
+#![cfg(feature = "client")]
+
+use bondr_pinocchio::client::*;
+use bondr_pinocchio::instructions::BondrInstruction;
+
+/// Account names a handler destructures, without the trailing `rest @ ..`.
+fn handler_accounts(source: &str) -> Vec<String> {
+    let start = source
+        .find("let [")
+        .expect("handler destructures its accounts")
+        + 5;
+    let end = start + source[start..].find(']').unwrap();
+
+    source[start..end]
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty() && !name.contains('@'))
+        .map(normalize)
+        .collect()
+}
+
+/// `_config_acc`, `config_acc` and `config` all name the same account.
+fn normalize(name: &str) -> String {
+    let name = name.trim_start_matches('_');
+    let name = name
+        .strip_suffix("_account")
+        .or_else(|| name.strip_suffix("_acc"))
+        .unwrap_or(name);
+    name.to_string()
+}
+
+macro_rules! check_accounts {
+    ($($accounts:ident => $file:literal,)*) => {
+        $(
+            let expected = handler_accounts(include_str!(concat!("../src/instructions/", $file)));
+            let actual: Vec<String> = $accounts::NAMES.iter().map(|n| normalize(n)).collect();
+            assert_eq!(actual, expected, "{} does not match {}", stringify!($accounts), $file);
+        )*
+    };
+}
+
+#[test]
+fn builder_accounts_match_handlers() {
+    check_accounts! {
+        InitializeEscrowAccounts => "initialize_escrow.rs",
+        InitializeFreelancerBadgeAccounts => "initialize_freelancer_badge.rs",
+        InitializeMultisigClientAccounts => "initialize_multisig_client.rs",
+        ReleasePaymentAccounts => "release_payment.rs",
+        ClaimPaymentAccounts => "claim_payment.rs",
+        ApproveMultisigReleaseAccounts => "approve_multisig_release.rs",
+        UpdateFreelancerBadgeAccounts => "update_freelancer_badge.rs",
+        MintReputationNftAccounts => "mint_reputation_nft.rs",
+        CancelEscrowAccounts => "cancel_escrow.rs",
+        ReleaseMilestoneAccounts => "release_milestone.rs",
+        ClaimMilestoneAccounts => "claim_milestone.rs",
+        OpenDisputeAccounts => "open_dispute.rs",
+        ResolveDisputeAccounts => "resolve_dispute.rs",
+        InitializeConfigAccounts => "initialize_config.rs",
+        WithdrawTreasuryAccounts => "withdraw_treasury.rs",
+        UpdateConfigAccounts => "update_config.rs",
+        TransferAdminAccounts => "transfer_admin.rs",
+        SetPauseAccounts => "set_pause.rs",
+        MigrateAccountAccounts => "migrate_account.rs",
+        AddMultisigMemberAccounts => "add_multisig_member.rs",
+        RemoveMultisigMemberAccounts => "remove_multisig_member.rs",
+        RotateMultisigMemberAccounts => "rotate_multisig_member.rs",
+        ChangeThresholdAccounts => "change_threshold.rs",
+    }
+}
+
+#[test]
+fn builders_prefix_the_instruction() {
+    let key = solana_pubkey::Pubkey::new_unique();
+
+    let ix = open_dispute(&OpenDisputeAccounts {
+        party: key,
+        escrow: key,
+    });
+    assert_eq!(ix.program_id, PROGRAM_ID);
+    assert_eq!(ix.data, vec![BondrInstruction::OpenDispute as u8]);
+    assert!(ix.accounts[0].is_signer && !ix.accounts[0].is_writable);
+    assert!(ix.accounts[1].is_writable);
+
+    let ix = withdraw_treasury(
+        &WithdrawTreasuryAccounts {
+            admin: key,
+            config: key,
+            treasury: key,
+            destination: key,
+            treasury_token_account: key,
+            destination_token_account: key,
+            token_mint: key,
+            token_program: key,
+            system_program: key,
+        },
+        &bondr_pinocchio::instructions::WithdrawTreasury { amount: 42 },
+    );
+    assert_eq!(ix.data[0], BondrInstruction::WithdrawTreasury as u8);
+    assert_eq!(ix.data[1..], 42u64.to_le_bytes());
+}
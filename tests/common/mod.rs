@@ -0,0 +1,918 @@
+//! Shared fixtures for the Mollusk suite: a program environment with an initialized
+//! config, funded parties holding SPL tokens, and builders for escrows, multisigs and
+//! badges. Every instruction goes through `MolluskContext`, so accounts persist
+//! between the steps of a test.
+
+#![allow(dead_code)] // each suite uses a different part of the fixtures
+
+use std::collections::HashMap;
+
+use bondr_pinocchio::{
+    client::{self, *},
+    constants::{
+        DEFAULT_TIER_THRESHOLDS, ELITE_METADATA_URI, MAX_METADATA_URI_LEN, MAX_MILESTONES,
+        MAX_MULTISIG_MEMBERS, PROFESSIONAL_METADATA_URI, VERIFIED_METADATA_URI,
+    },
+    decoder::{decode_program_data, DecodedEvent},
+    errors::BondrError,
+    instructions::{
+        AddMultisigMember, ChangeThreshold, ClaimMilestone, ClaimPayment, InitializeConfig,
+        InitializeEscrow, InitializeFreelancerBadge, InitializeMultisigClient, ReleaseMilestone,
+        RemoveMultisigMember, ResolveDispute, RotateMultisigMember, SetPause, UpdateConfig,
+    },
+    states::{
+        to_bytes, Config, ConfigSettings, DataLen, FreelancerBadge, ProgramAccount, TransferFee,
+        MPL_CORE_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    },
+};
+use mollusk_svm::{
+    program::loader_keys::LOADER_V3,
+    result::{Check, InstructionResult},
+    Mollusk, MolluskContext,
+};
+use solana_sdk::{
+    account::Account, instruction::Instruction, native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError, pubkey::Pubkey, rent::Rent,
+};
+use solana_svm_log_collector::LogCollector;
+
+/// Clock time every test starts at.
+pub const NOW: i64 = 1_750_000_000;
+pub const DAY: i64 = 86_400;
+
+pub const FEE_BPS: u16 = 100;
+/// Share of the fee waived per `ReputationTier`.
+pub const TIER_FEE_DISCOUNT_BPS: [u16; 4] = [0, 2_500, 5_000, 10_000];
+
+pub const DECIMALS: u8 = 6;
+/// Tokens and lamports each party starts with.
+pub const TOKEN_BALANCE: u64 = 1_000_000_000;
+pub const WALLET_LAMPORTS: u64 = 100 * LAMPORTS_PER_SOL;
+pub const ESCROW_AMOUNT: u64 = 10_000_000;
+
+pub const SYSTEM_PROGRAM: Pubkey = Pubkey::new_from_array([0; 32]);
+pub const TOKEN_PROGRAM: Pubkey = Pubkey::new_from_array(TOKEN_PROGRAM_ID);
+pub const TOKEN_2022: Pubkey = Pubkey::new_from_array(TOKEN_2022_PROGRAM_ID);
+pub const MPL_CORE: Pubkey = Pubkey::new_from_array(MPL_CORE_ID);
+pub const RENT_SYSVAR: Pubkey = solana_sdk::sysvar::rent::ID;
+
+const MINT_LEN: usize = 82;
+const TOKEN_ACCOUNT_LEN: usize = 165;
+
+pub struct Env {
+    pub ctx: MolluskContext<HashMap<Pubkey, Account>>,
+    pub admin: Pubkey,
+    pub guardian: Pubkey,
+    pub mint: Pubkey, // allow-listed SPL mint
+    pub collection: Pubkey,
+    pub config: Pubkey,
+    pub treasury: Pubkey,
+    pub treasury_token: Pubkey,
+}
+
+/// A wallet with lamports and a token account of the environment's mint.
+#[derive(Clone, Copy, Debug)]
+pub struct Party {
+    pub wallet: Pubkey,
+    pub token: Pubkey,
+}
+
+#[derive(Clone, Debug)]
+pub struct EscrowArgs {
+    pub amount: u64,
+    pub reference_seed: u64,
+    pub deadline: i64,
+    pub auto_release_at: i64,
+    pub milestones: Vec<u64>,
+    pub arbiter: Pubkey,
+    pub multisig: Option<Pubkey>,
+    pub native: bool,
+}
+
+impl Default for EscrowArgs {
+    fn default() -> Self {
+        EscrowArgs {
+            amount: ESCROW_AMOUNT,
+            reference_seed: 1,
+            deadline: 0,
+            auto_release_at: 0,
+            milestones: Vec::new(),
+            arbiter: Pubkey::default(),
+            multisig: None,
+            native: false,
+        }
+    }
+}
+
+/// An escrow between two parties, created or about to be.
+#[derive(Clone, Debug)]
+pub struct TestEscrow {
+    pub address: Pubkey,
+    pub bump: u8,
+    pub vault: Pubkey,    // the system program for native escrows
+    pub spl_mint: Pubkey, // the environment's mint, used unless the escrow is native
+    pub client: Party,
+    pub freelancer: Party,
+    pub args: EscrowArgs,
+}
+
+impl TestEscrow {
+    pub fn mint(&self) -> Pubkey {
+        if self.args.native {
+            SYSTEM_PROGRAM
+        } else {
+            self.spl_mint
+        }
+    }
+
+    pub fn token_program(&self) -> Pubkey {
+        if self.args.native {
+            SYSTEM_PROGRAM
+        } else {
+            TOKEN_PROGRAM
+        }
+    }
+
+    /// The client's `UserStats`, created along with their first escrow.
+    pub fn client_stats(&self) -> Pubkey {
+        find_user_stats_pda(&self.client.wallet).0
+    }
+
+    /// The multisig's `MultisigApproval` for this escrow, and its bump; the default
+    /// key for escrows without a multisig.
+    pub fn approval(&self) -> (Pubkey, u8) {
+        self.args
+            .multisig
+            .map(|multisig| find_multisig_approval_pda(&multisig, &self.address))
+            .unwrap_or_default()
+    }
+
+    /// `party`'s token account, or the system program for native escrows.
+    pub fn token_of(&self, party: &Party) -> Pubkey {
+        if self.args.native {
+            SYSTEM_PROGRAM
+        } else {
+            party.token
+        }
+    }
+}
+
+impl Env {
+    /// Loads Bondr, both token programs and the mock mpl-core, then runs `InitializeConfig`.
+    pub fn new() -> Self {
+        let env = Self::uninitialized();
+        env.ok(&env.initialize_config(&env.admin, &env.settings()));
+        env
+    }
+
+    /// The same environment before the config exists. The admin is the program's
+    /// upgrade authority.
+    pub fn uninitialized() -> Self {
+        let mut mollusk = Mollusk::new(&PROGRAM_ID, "bondr_pinocchio");
+        mollusk.add_program(&TOKEN_PROGRAM, "spl_token", &LOADER_V3);
+        mollusk.add_program(&TOKEN_2022, "spl_token_2022", &LOADER_V3);
+        mollusk.add_program(&MPL_CORE, "mock_mpl_core", &LOADER_V3);
+        mollusk.sysvars.clock.unix_timestamp = NOW;
+        mollusk.logger = Some(LogCollector::new_ref());
+
+        let env = Env {
+            ctx: mollusk.with_context(HashMap::new()),
+            admin: Pubkey::new_unique(),
+            guardian: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            collection: Pubkey::new_unique(),
+            config: find_config_pda().0,
+            treasury: find_treasury_pda().0,
+            treasury_token: Pubkey::new_unique(),
+        };
+
+        env.fund(&env.admin);
+        env.set_account(
+            &find_program_data_address(),
+            program_data_account(&env.admin),
+        );
+        env.set_account(&env.mint, mint_account());
+        env.set_account(
+            &env.treasury_token,
+            token_account(&env.mint, &env.treasury, 0),
+        );
+        env
+    }
+
+    /// Native SOL and the environment's mint are allowed, at the default tiers.
+    pub fn settings(&self) -> ConfigSettings {
+        let mut allowed_mints = [[0; 32]; 8];
+        allowed_mints[1] = self.mint.to_bytes();
+
+        ConfigSettings {
+            guardian: self.guardian.to_bytes(),
+            fee_bps: FEE_BPS,
+            tier_fee_discount_bps: TIER_FEE_DISCOUNT_BPS,
+            _padding0: [0; 2],
+            tier_thresholds: DEFAULT_TIER_THRESHOLDS,
+            badge_collection: self.collection.to_bytes(),
+            allowed_mints,
+            allowed_mint_count: 2,
+            max_multisig_members: MAX_MULTISIG_MEMBERS as u8,
+            tier_uris: [
+                metadata_uri(VERIFIED_METADATA_URI),
+                metadata_uri(PROFESSIONAL_METADATA_URI),
+                metadata_uri(ELITE_METADATA_URI),
+            ],
+            _padding1: [0; 2],
+        }
+    }
+
+    pub fn process(&self, instruction: &Instruction) -> InstructionResult {
+        self.ctx.process_instruction(instruction)
+    }
+
+    pub fn ok(&self, instruction: &Instruction) -> InstructionResult {
+        self.ctx
+            .process_and_validate_instruction(instruction, &[Check::success()])
+    }
+
+    /// Runs `instruction`, which must succeed, and decodes the events it logged.
+    pub fn events(&self, instruction: &Instruction) -> Vec<DecodedEvent> {
+        let logger = self.ctx.mollusk.logger.as_ref().unwrap();
+        *logger.borrow_mut() = LogCollector::default();
+        self.ok(instruction);
+
+        // the token programs and mock mpl-core never log data, so every line is Bondr's
+        logger
+            .borrow()
+            .get_recorded_content()
+            .iter()
+            .filter(|line| line.starts_with("Program data: "))
+            .map(|line| decode_program_data(line).unwrap())
+            .collect()
+    }
+
+    pub fn err(&self, instruction: &Instruction, error: BondrError) {
+        self.err_with(instruction, ProgramError::Custom(error as u32));
+    }
+
+    pub fn err_with(&self, instruction: &Instruction, error: ProgramError) {
+        self.ctx
+            .process_and_validate_instruction(instruction, &[Check::err(error)]);
+    }
+
+    pub fn warp_to(&mut self, unix_timestamp: i64) {
+        self.ctx.mollusk.sysvars.clock.unix_timestamp = unix_timestamp;
+    }
+
+    pub fn account(&self, key: &Pubkey) -> Account {
+        self.ctx
+            .account_store
+            .borrow()
+            .get(key)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn set_account(&self, key: &Pubkey, account: Account) {
+        self.ctx.account_store.borrow_mut().insert(*key, account);
+    }
+
+    pub fn exists(&self, key: &Pubkey) -> bool {
+        self.account(key).lamports > 0
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.account(key).lamports
+    }
+
+    pub fn fund(&self, key: &Pubkey) {
+        self.set_account(key, Account::new(WALLET_LAMPORTS, 0, &SYSTEM_PROGRAM));
+    }
+
+    /// Reads a program account as `T`.
+    pub fn state<T: DataLen + Copy>(&self, key: &Pubkey) -> T {
+        let data = self.account(key).data;
+        assert_eq!(
+            data.len(),
+            T::LEN,
+            "{key} is not a {}",
+            std::any::type_name::<T>()
+        );
+
+        unsafe { std::ptr::read_unaligned(data.as_ptr() as *const T) }
+    }
+
+    /// Overwrites (or creates) a rent-exempt program account holding `state`.
+    pub fn set_state<T: DataLen>(&self, key: &Pubkey, state: &T) {
+        let data = unsafe { to_bytes(state) }.to_vec();
+
+        self.set_account(
+            key,
+            Account {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: PROGRAM_ID,
+                ..Account::default()
+            },
+        );
+    }
+
+    pub fn token_balance(&self, key: &Pubkey) -> u64 {
+        let data = self.account(key).data;
+        u64::from_le_bytes(data[64..72].try_into().unwrap())
+    }
+
+    pub fn create_token_account(&self, owner: &Pubkey, amount: u64) -> Pubkey {
+        let key = Pubkey::new_unique();
+        self.set_account(&key, token_account(&self.mint, owner, amount));
+        key
+    }
+
+    pub fn party(&self) -> Party {
+        let wallet = Pubkey::new_unique();
+        self.fund(&wallet);
+
+        Party {
+            wallet,
+            token: self.create_token_account(&wallet, TOKEN_BALANCE),
+        }
+    }
+
+    pub fn initialize_config(&self, admin: &Pubkey, settings: &ConfigSettings) -> Instruction {
+        client::initialize_config(
+            &InitializeConfigAccounts {
+                admin: *admin,
+                config: self.config,
+                treasury: self.treasury,
+                program: PROGRAM_ID,
+                program_data: find_program_data_address(),
+                system_program: SYSTEM_PROGRAM,
+                sysvar_rent: RENT_SYSVAR,
+            },
+            &InitializeConfig {
+                settings: *settings,
+                bump: find_config_pda().1,
+                treasury_bump: find_treasury_pda().1,
+                _padding: [0; 2],
+                min_reputation_amounts: [0; 8],
+            },
+        )
+    }
+
+    pub fn update_config(&self, settings: &ConfigSettings) -> Instruction {
+        let min_reputation_amounts = self.state::<Config>(&self.config).min_reputation_amounts;
+        self.update_config_with(settings, min_reputation_amounts)
+    }
+
+    pub fn update_config_with(
+        &self,
+        settings: &ConfigSettings,
+        min_reputation_amounts: [u64; 8],
+    ) -> Instruction {
+        client::update_config(
+            &UpdateConfigAccounts {
+                admin: self.admin,
+                config: self.config,
+            },
+            &UpdateConfig {
+                settings: *settings,
+                _padding: [0; 4],
+                min_reputation_amounts,
+            },
+        )
+    }
+
+    pub fn set_pause(
+        &self,
+        authority: &Pubkey,
+        paused_instructions: u32,
+        paused: bool,
+    ) -> Instruction {
+        client::set_pause(
+            &SetPauseAccounts {
+                authority: *authority,
+                config: self.config,
+            },
+            &SetPause {
+                paused_instructions,
+                paused,
+                _padding: [0; 3],
+            },
+        )
+    }
+
+    /// Sets up the vault for an escrow between `client` and `freelancer` and builds the
+    /// instruction that creates it.
+    pub fn escrow_ix(
+        &self,
+        client: &Party,
+        freelancer: &Party,
+        args: EscrowArgs,
+    ) -> (TestEscrow, Instruction) {
+        let (address, bump) =
+            find_escrow_pda(&client.wallet, &freelancer.wallet, args.reference_seed);
+
+        let vault = if args.native {
+            SYSTEM_PROGRAM
+        } else {
+            self.create_token_account(&address, 0)
+        };
+
+        let escrow = TestEscrow {
+            address,
+            bump,
+            vault,
+            spl_mint: self.mint,
+            client: *client,
+            freelancer: *freelancer,
+            args,
+        };
+
+        let mut milestone_amounts = [0; MAX_MILESTONES];
+        for (slot, &amount) in milestone_amounts.iter_mut().zip(&escrow.args.milestones) {
+            *slot = amount;
+        }
+
+        let (sender_stats, stats_bump) = find_user_stats_pda(&client.wallet);
+        let (multisig_approval, approval_bump) = escrow.approval();
+        let instruction = client::initialize_escrow(
+            &InitializeEscrowAccounts {
+                sender: client.wallet,
+                receiver: freelancer.wallet,
+                escrow: address,
+                sender_stats,
+                client_multisig: escrow.args.multisig.unwrap_or_default(),
+                multisig_approval,
+                sender_token_account: escrow.token_of(client),
+                escrow_token_account: vault,
+                token_mint: escrow.mint(),
+                sysvar_rent: RENT_SYSVAR,
+                token_program: escrow.token_program(),
+                system_program: SYSTEM_PROGRAM,
+                config: self.config,
+            },
+            &InitializeEscrow {
+                amount: escrow.args.amount,
+                reference_seed: escrow.args.reference_seed,
+                deadline: escrow.args.deadline,
+                auto_release_at: escrow.args.auto_release_at,
+                milestone_amounts,
+                arbiter: escrow.args.arbiter.to_bytes(),
+                milestone_count: escrow.args.milestones.len() as u8,
+                bump,
+                stats_bump,
+                approval_bump,
+                is_multisig: escrow.args.multisig.is_some(),
+                is_native: escrow.args.native,
+                _padding: [0; 2],
+            },
+        );
+
+        (escrow, instruction)
+    }
+
+    /// Creates an escrow between two new parties.
+    pub fn create_escrow(&self, args: EscrowArgs) -> TestEscrow {
+        let (client, freelancer) = (self.party(), self.party());
+        self.create_escrow_between(&client, &freelancer, args)
+    }
+
+    pub fn create_escrow_between(
+        &self,
+        client: &Party,
+        freelancer: &Party,
+        args: EscrowArgs,
+    ) -> TestEscrow {
+        let (escrow, instruction) = self.escrow_ix(client, freelancer, args);
+        self.ok(&instruction);
+        escrow
+    }
+
+    pub fn release(&self, escrow: &TestEscrow) -> Instruction {
+        client::release_payment(&ReleasePaymentAccounts {
+            client: escrow.client.wallet,
+            escrow: escrow.address,
+            sender_stats: escrow.client_stats(),
+            system_program: SYSTEM_PROGRAM,
+            config: self.config,
+        })
+    }
+
+    pub fn claim(&self, escrow: &TestEscrow) -> Instruction {
+        let (receiver_stats, receiver_stats_bump) = find_user_stats_pda(&escrow.freelancer.wallet);
+
+        client::claim_payment(
+            &ClaimPaymentAccounts {
+                client: escrow.client.wallet,
+                freelancer: escrow.freelancer.wallet,
+                escrow: escrow.address,
+                receiver_stats,
+                multisig: escrow.args.multisig.unwrap_or_default(),
+                multisig_approval: escrow.approval().0,
+                escrow_token_account: escrow.vault,
+                receiver_token_account: escrow.token_of(&escrow.freelancer),
+                token_mint: escrow.mint(),
+                token_program: escrow.token_program(),
+                system_program: SYSTEM_PROGRAM,
+                config: self.config,
+                treasury: self.treasury,
+                treasury_token_account: self.treasury_token_of(escrow),
+                receiver_badge: find_badge_pda(&escrow.freelancer.wallet).0,
+            },
+            &ClaimPayment {
+                receiver_stats_bump,
+            },
+        )
+    }
+
+    pub fn cancel(&self, escrow: &TestEscrow, receiver_signs: bool) -> Instruction {
+        client::cancel_escrow(
+            &CancelEscrowAccounts {
+                sender: escrow.client.wallet,
+                receiver: escrow.freelancer.wallet,
+                escrow: escrow.address,
+                sender_stats: escrow.client_stats(),
+                multisig: escrow.args.multisig.unwrap_or_default(),
+                multisig_approval: escrow.approval().0,
+                escrow_token_account: escrow.vault,
+                sender_token_account: escrow.token_of(&escrow.client),
+                token_mint: escrow.mint(),
+                token_program: escrow.token_program(),
+                system_program: SYSTEM_PROGRAM,
+            },
+            receiver_signs,
+        )
+    }
+
+    pub fn release_milestone(&self, escrow: &TestEscrow, index: u8) -> Instruction {
+        client::release_milestone(
+            &ReleaseMilestoneAccounts {
+                client: escrow.client.wallet,
+                escrow: escrow.address,
+                sender_stats: escrow.client_stats(),
+                system_program: SYSTEM_PROGRAM,
+                config: self.config,
+            },
+            &ReleaseMilestone { index },
+        )
+    }
+
+    pub fn claim_milestone(&self, escrow: &TestEscrow, index: u8) -> Instruction {
+        let (receiver_stats, receiver_stats_bump) = find_user_stats_pda(&escrow.freelancer.wallet);
+
+        client::claim_milestone(
+            &ClaimMilestoneAccounts {
+                client: escrow.client.wallet,
+                freelancer: escrow.freelancer.wallet,
+                escrow: escrow.address,
+                receiver_stats,
+                escrow_token_account: escrow.vault,
+                receiver_token_account: escrow.token_of(&escrow.freelancer),
+                token_mint: escrow.mint(),
+                token_program: escrow.token_program(),
+                system_program: SYSTEM_PROGRAM,
+                config: self.config,
+                treasury: self.treasury,
+                treasury_token_account: self.treasury_token_of(escrow),
+                receiver_badge: find_badge_pda(&escrow.freelancer.wallet).0,
+            },
+            &ClaimMilestone {
+                index,
+                receiver_stats_bump,
+            },
+        )
+    }
+
+    pub fn open_dispute(&self, escrow: &TestEscrow, party: &Pubkey) -> Instruction {
+        client::open_dispute(&OpenDisputeAccounts {
+            party: *party,
+            escrow: escrow.address,
+        })
+    }
+
+    pub fn resolve_dispute(&self, escrow: &TestEscrow, receiver_bps: u16) -> Instruction {
+        client::resolve_dispute(
+            &ResolveDisputeAccounts {
+                arbiter: escrow.args.arbiter,
+                sender: escrow.client.wallet,
+                receiver: escrow.freelancer.wallet,
+                escrow: escrow.address,
+                escrow_token_account: escrow.vault,
+                sender_token_account: escrow.token_of(&escrow.client),
+                receiver_token_account: escrow.token_of(&escrow.freelancer),
+                token_mint: escrow.mint(),
+                receiver_badge: find_badge_pda(&escrow.freelancer.wallet).0,
+                sender_stats: escrow.client_stats(),
+                multisig: escrow.args.multisig.unwrap_or_default(),
+                multisig_approval: escrow.approval().0,
+                config: self.config,
+                treasury: self.treasury,
+                treasury_token_account: self.treasury_token_of(escrow),
+                token_program: escrow.token_program(),
+                system_program: SYSTEM_PROGRAM,
+            },
+            &ResolveDispute { receiver_bps },
+        )
+    }
+
+    fn treasury_token_of(&self, escrow: &TestEscrow) -> Pubkey {
+        if escrow.args.native {
+            SYSTEM_PROGRAM
+        } else {
+            self.treasury_token
+        }
+    }
+
+    pub fn multisig_ix(&self, client: &Pubkey, members: &[Pubkey], threshold: u8) -> Instruction {
+        let (multisig, bump) = find_multisig_pda(client);
+
+        let mut padded = [[0; 32]; MAX_MULTISIG_MEMBERS];
+        for (slot, member) in padded.iter_mut().zip(members) {
+            *slot = member.to_bytes();
+        }
+
+        client::initialize_multisig_client(
+            &InitializeMultisigClientAccounts {
+                client: *client,
+                multisig,
+                system_program: SYSTEM_PROGRAM,
+                sysvar_rent: RENT_SYSVAR,
+                config: self.config,
+            },
+            &InitializeMultisigClient {
+                members: padded,
+                member_count: members.len() as u8,
+                threshold,
+                bump,
+            },
+        )
+    }
+
+    /// Creates `client`'s multisig; `members` should include the client.
+    pub fn create_multisig(&self, client: &Pubkey, members: &[Pubkey], threshold: u8) -> Pubkey {
+        self.ok(&self.multisig_ix(client, members, threshold));
+        find_multisig_pda(client).0
+    }
+
+    pub fn approve(&self, escrow: &TestEscrow, member: &Pubkey) -> Instruction {
+        client::approve_multisig_release(&ApproveMultisigReleaseAccounts {
+            member: *member,
+            multisig: escrow.args.multisig.unwrap_or_default(),
+            escrow: escrow.address,
+            multisig_approval: escrow.approval().0,
+            sender_stats: escrow.client_stats(),
+            system_program: SYSTEM_PROGRAM,
+            config: self.config,
+        })
+    }
+
+    pub fn add_member(
+        &self,
+        multisig: &Pubkey,
+        member: &Pubkey,
+        approvers: &[Pubkey],
+    ) -> Instruction {
+        client::add_multisig_member(
+            &AddMultisigMemberAccounts {
+                multisig: *multisig,
+                config: self.config,
+            },
+            &AddMultisigMember {
+                member: member.to_bytes(),
+            },
+            approvers,
+        )
+    }
+
+    pub fn remove_member(
+        &self,
+        multisig: &Pubkey,
+        member: &Pubkey,
+        approvers: &[Pubkey],
+    ) -> Instruction {
+        client::remove_multisig_member(
+            &RemoveMultisigMemberAccounts {
+                multisig: *multisig,
+                config: self.config,
+            },
+            &RemoveMultisigMember {
+                member: member.to_bytes(),
+            },
+            approvers,
+        )
+    }
+
+    pub fn rotate_member(
+        &self,
+        multisig: &Pubkey,
+        old_member: &Pubkey,
+        new_member: &Pubkey,
+        approvers: &[Pubkey],
+    ) -> Instruction {
+        client::rotate_multisig_member(
+            &RotateMultisigMemberAccounts {
+                multisig: *multisig,
+                config: self.config,
+            },
+            &RotateMultisigMember {
+                old_member: old_member.to_bytes(),
+                new_member: new_member.to_bytes(),
+            },
+            approvers,
+        )
+    }
+
+    pub fn change_threshold(
+        &self,
+        multisig: &Pubkey,
+        threshold: u8,
+        approvers: &[Pubkey],
+    ) -> Instruction {
+        client::change_threshold(
+            &ChangeThresholdAccounts {
+                multisig: *multisig,
+                config: self.config,
+            },
+            &ChangeThreshold { threshold },
+            approvers,
+        )
+    }
+
+    pub fn badge_ix(&self, freelancer: &Pubkey) -> Instruction {
+        let (badge, bump) = find_badge_pda(freelancer);
+
+        client::initialize_freelancer_badge(
+            &InitializeFreelancerBadgeAccounts {
+                freelancer: *freelancer,
+                badge,
+                system_program: SYSTEM_PROGRAM,
+                sysvar_rent: RENT_SYSVAR,
+                config: self.config,
+            },
+            &InitializeFreelancerBadge { bump },
+        )
+    }
+
+    pub fn create_badge(&self, freelancer: &Pubkey) -> Pubkey {
+        self.ok(&self.badge_ix(freelancer));
+        find_badge_pda(freelancer).0
+    }
+
+    /// Rewrites a badge's completed escrow count, skipping the escrows it would take.
+    pub fn set_completed_escrows(&self, freelancer: &Pubkey, completed_escrows: u32) {
+        let badge = find_badge_pda(freelancer).0;
+        let state = FreelancerBadge {
+            completed_escrows,
+            ..self.state::<FreelancerBadge>(&badge)
+        };
+        assert_eq!(state.version, FreelancerBadge::VERSION);
+        self.set_state(&badge, &state);
+    }
+
+    pub fn mint_nft(&self, freelancer: &Pubkey, asset: &Pubkey) -> Instruction {
+        client::mint_reputation_nft(&MintReputationNftAccounts {
+            freelancer: *freelancer,
+            badge: find_badge_pda(freelancer).0,
+            asset: *asset,
+            collection: self.collection,
+            config: self.config,
+            mpl_core_program: MPL_CORE,
+            system_program: SYSTEM_PROGRAM,
+        })
+    }
+
+    /// Name and uri of an asset created by the mock mpl-core.
+    pub fn asset_metadata(&self, asset: &Pubkey) -> (String, String) {
+        let data = self.account(asset).data;
+        // DataState, then borsh strings
+        let mut rest = &data[1..];
+        let mut read = || {
+            let len = u32::from_le_bytes(rest[..4].try_into().unwrap()) as usize;
+            let value = String::from_utf8(rest[4..4 + len].to_vec()).unwrap();
+            rest = &rest[4 + len..];
+            value
+        };
+        let name = read();
+        (name, read())
+    }
+}
+
+pub fn metadata_uri(uri: &str) -> [u8; MAX_METADATA_URI_LEN] {
+    let mut padded = [0; MAX_METADATA_URI_LEN];
+    padded[..uri.len()].copy_from_slice(uri.as_bytes());
+    padded
+}
+
+/// An initialized SPL Token mint with no authorities.
+pub fn mint_account() -> Account {
+    let mut data = vec![0; MINT_LEN];
+    data[36..44].copy_from_slice(&u64::MAX.to_le_bytes()); // supply
+    data[44] = DECIMALS;
+    data[45] = 1; // is_initialized
+
+    Account {
+        lamports: Rent::default().minimum_balance(MINT_LEN),
+        data,
+        owner: TOKEN_PROGRAM,
+        ..Account::default()
+    }
+}
+
+/// An initialized SPL Token account with no delegate or close authority.
+pub fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    let mut data = vec![0; TOKEN_ACCOUNT_LEN];
+    data[..32].copy_from_slice(mint.as_ref());
+    data[32..64].copy_from_slice(owner.as_ref());
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    data[108] = 1; // AccountState::Initialized
+
+    Account {
+        lamports: Rent::default().minimum_balance(TOKEN_ACCOUNT_LEN),
+        data,
+        owner: TOKEN_PROGRAM,
+        ..Account::default()
+    }
+}
+
+/// A Token-2022 mint charging `fee` on every transfer, from epoch 0 on.
+pub fn fee_mint_account(fee: TransferFee) -> Account {
+    let mut data = mint_account().data;
+    data.resize(TOKEN_ACCOUNT_LEN, 0);
+    data.push(1); // AccountType::Mint
+
+    // TransferFeeConfig: no authorities, nothing withheld, the same older and newer fee
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&108u16.to_le_bytes());
+    data.extend_from_slice(&[0; 72]);
+    for _ in 0..2 {
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&fee.maximum_fee.to_le_bytes());
+        data.extend_from_slice(&fee.basis_points.to_le_bytes());
+    }
+
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: TOKEN_2022,
+        ..Account::default()
+    }
+}
+
+/// A Token-2022 account of a [`fee_mint_account`], with room for its withheld fees.
+pub fn fee_token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    let mut data = token_account(mint, owner, amount).data;
+    data.push(2); // AccountType::Account
+
+    // TransferFeeAmount, nothing withheld yet
+    data.extend_from_slice(&2u16.to_le_bytes());
+    data.extend_from_slice(&8u16.to_le_bytes());
+    data.extend_from_slice(&0u64.to_le_bytes());
+
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: TOKEN_2022,
+        ..Account::default()
+    }
+}
+
+/// The upgradeable loader's `ProgramData` header naming `authority`; the ELF is not needed.
+pub fn program_data_account(authority: &Pubkey) -> Account {
+    let mut data = vec![0; 45];
+    data[..4].copy_from_slice(&3u32.to_le_bytes());
+    data[12] = 1; // Some(upgrade authority)
+    data[13..45].copy_from_slice(authority.as_ref());
+
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: LOADER_V3,
+        ..Account::default()
+    }
+}
+
+/// `instruction` with the `from` account swapped for `to`, keeping its flags.
+pub fn swap_account(mut instruction: Instruction, from: &Pubkey, to: &Pubkey) -> Instruction {
+    let meta = instruction
+        .accounts
+        .iter_mut()
+        .find(|meta| meta.pubkey == *from)
+        .expect("account is part of the instruction");
+
+    meta.pubkey = *to;
+    instruction
+}
+
+/// `instruction` without `signer`'s signature.
+pub fn unsigned(mut instruction: Instruction, signer: &Pubkey) -> Instruction {
+    for meta in &mut instruction.accounts {
+        if meta.pubkey == *signer {
+            meta.is_signer = false;
+        }
+    }
+    instruction
+}
+
+/// The protocol fee on `amount` for a freelancer of the given tier (index into
+/// `TIER_FEE_DISCOUNT_BPS`), computed like `Config::protocol_fee`.
+pub fn protocol_fee(amount: u64, tier: usize) -> u64 {
+    let fee = amount as u128 * FEE_BPS as u128 / 10_000;
+    (fee - fee * TIER_FEE_DISCOUNT_BPS[tier] as u128 / 10_000) as u64
+}
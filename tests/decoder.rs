@@ -0,0 +1,886 @@
+//! Off-chain decoding of account, instruction and event data.
+
+#![cfg(feature = "std")]
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bondr_pinocchio::{
+    constants::{MAX_ALLOWED_MINTS, MAX_METADATA_URI_LEN, MAX_MILESTONES},
+    decoder::*,
+    events::{Event, EventKind, EVENT_VERSION},
+    instructions::*,
+    states::{
+        AccountDiscriminator, ClientMultisig, Config, ConfigSettings, DataLen, Escrow,
+        FreelancerBadge, Milestone, MilestoneState, MultisigApproval, ProgramAccount,
+        ReputationTier, UserStats,
+    },
+};
+
+/// The in-memory bytes of a `#[repr(C)]` state struct, as the program writes them.
+fn bytes_of<T: DataLen>(state: &T) -> Vec<u8> {
+    unsafe { core::slice::from_raw_parts(state as *const T as *const u8, T::LEN) }.to_vec()
+}
+
+fn user_stats() -> UserStats {
+    UserStats {
+        discriminator: AccountDiscriminator::UserStats as u8,
+        version: UserStats::VERSION,
+        user: [7; 32],
+        _padding0: [0; 2],
+        completed_escrows: 3,
+        bump: 254,
+        _padding1: [0; 3],
+        disputes_won: 1,
+        disputes_lost: 2,
+        escrows_funded: 5,
+        escrows_released: 4,
+        escrows_refunded: 1,
+        total_funded: 5_000,
+        total_release_seconds: 400,
+    }
+}
+
+#[test]
+fn version_1_user_stats_decode_with_an_empty_client_record() {
+    let mut data = bytes_of(&user_stats());
+    data.truncate(core::mem::offset_of!(UserStats, escrows_funded));
+    data[1] = 1;
+
+    let DecodedAccount::UserStats(stats) = decode_account(&data).unwrap() else {
+        panic!("not user stats");
+    };
+    assert_eq!(stats.version, 1);
+    assert_eq!(stats.user, [7; 32]);
+    assert_eq!(stats.completed_escrows, 3);
+    assert_eq!((stats.disputes_won, stats.disputes_lost), (1, 2));
+    assert_eq!((stats.escrows_funded, stats.escrows_released), (0, 0));
+    assert_eq!(
+        (stats.total_funded, stats.average_release_seconds),
+        (0, None)
+    );
+
+    // a version 1 header on a current-size account is neither layout
+    let mut data = bytes_of(&user_stats());
+    data[1] = 1;
+    assert_eq!(
+        decode_account(&data),
+        Err(DecodeError::InvalidLength {
+            expected: 52,
+            actual: UserStats::LEN,
+        })
+    );
+}
+
+/// The bytes `Event::emit` logs: `[EVENT_VERSION, kind]` followed by the packed event.
+fn logged<E: Event>(event: &E) -> Vec<u8> {
+    let bytes = unsafe {
+        core::slice::from_raw_parts(event as *const E as *const u8, core::mem::size_of::<E>())
+    };
+    [&[EVENT_VERSION, E::KIND as u8], bytes].concat()
+}
+
+/// Instruction data as a client sends it: the `BondrInstruction` byte, then the payload.
+fn instruction_data<T: DataLen>(instruction: BondrInstruction, payload: &T) -> Vec<u8> {
+    [&[instruction as u8][..], &bytes_of(payload)].concat()
+}
+
+fn uri(s: &str) -> [u8; MAX_METADATA_URI_LEN] {
+    let mut uri = [0; MAX_METADATA_URI_LEN];
+    uri[..s.len()].copy_from_slice(s.as_bytes());
+    uri
+}
+
+fn config_settings() -> ConfigSettings {
+    let mut allowed_mints = [[0; 32]; MAX_ALLOWED_MINTS];
+    allowed_mints[0] = [4; 32];
+    allowed_mints[1] = [0; 32]; // native SOL
+
+    ConfigSettings {
+        guardian: [2; 32],
+        fee_bps: 150,
+        tier_fee_discount_bps: [0, 2_500, 5_000, 10_000],
+        _padding0: [0; 2],
+        tier_thresholds: [5, 20, 50],
+        badge_collection: [3; 32],
+        allowed_mints,
+        allowed_mint_count: 2,
+        max_multisig_members: 5,
+        tier_uris: [
+            uri("https://bondr.example/verified.json"),
+            uri("https://bondr.example/professional.json"),
+            uri(""),
+        ],
+        _padding1: [0; 2],
+    }
+}
+
+fn decoded_settings() -> Settings {
+    Settings {
+        guardian: [2; 32],
+        fee_bps: 150,
+        tier_fee_discount_bps: [0, 2_500, 5_000, 10_000],
+        tier_thresholds: [5, 20, 50],
+        badge_collection: [3; 32],
+        allowed_mints: vec![[4; 32], [0; 32]],
+        max_multisig_members: 5,
+        tier_uris: vec![
+            "https://bondr.example/verified.json".to_string(),
+            "https://bondr.example/professional.json".to_string(),
+            String::new(),
+        ],
+    }
+}
+
+fn escrow() -> Escrow {
+    let mut milestones = [Milestone::EMPTY; MAX_MILESTONES];
+    milestones[0].amount = 600;
+    milestones[0].state = MilestoneState::Claimed;
+    milestones[1].amount = 400;
+    milestones[1].state = MilestoneState::Released;
+
+    Escrow {
+        discriminator: AccountDiscriminator::Escrow as u8,
+        version: Escrow::VERSION,
+        sender: [1; 32],
+        receiver: [2; 32],
+        mint: [3; 32],
+        token_program: [4; 32],
+        _padding0: [0; 6],
+        amount: 1_000,
+        reference_seed: 42,
+        is_released: false,
+        bump: 253,
+        client_multisig: [5; 32],
+        has_multisig: true,
+        has_approval: true,
+        approval_bump: 252,
+        _padding1: [0; 3],
+        created_at: 1_700_000_000,
+        deadline: 1_700_086_400,
+        auto_release_at: 1_700_172_800,
+        milestone_count: 2,
+        _padding2: [0; 7],
+        milestones,
+        arbiter: [6; 32],
+        is_disputed: true,
+        is_native: false,
+        _padding3: [0; 6],
+    }
+}
+
+#[test]
+fn accounts_decode_to_what_the_program_wrote() {
+    let config = Config {
+        discriminator: AccountDiscriminator::Config as u8,
+        version: Config::VERSION,
+        admin: [1; 32],
+        _padding0: [0; 2],
+        settings: config_settings(),
+        paused_instructions: 0b101,
+        paused: false,
+        bump: 255,
+        treasury_bump: 254,
+        _padding1: [0; 1],
+        min_reputation_amounts: [1_000, 500_000, 0, 0, 0, 0, 0, 0],
+    };
+    assert_eq!(
+        decode_account(&bytes_of(&config)),
+        Ok(DecodedAccount::Config(ConfigAccount {
+            version: Config::VERSION,
+            admin: [1; 32],
+            settings: decoded_settings(),
+            paused_instructions: 0b101,
+            paused: false,
+            bump: 255,
+            treasury_bump: 254,
+            min_reputation_amounts: vec![1_000, 500_000],
+        }))
+    );
+
+    assert_eq!(
+        decode_account(&bytes_of(&escrow())),
+        Ok(DecodedAccount::Escrow(EscrowAccount {
+            version: Escrow::VERSION,
+            sender: [1; 32],
+            receiver: [2; 32],
+            mint: [3; 32],
+            token_program: [4; 32],
+            amount: 1_000,
+            reference_seed: 42,
+            is_released: false,
+            bump: 253,
+            client_multisig: [5; 32],
+            has_multisig: true,
+            has_approval: true,
+            approval_bump: 252,
+            created_at: 1_700_000_000,
+            deadline: 1_700_086_400,
+            auto_release_at: 1_700_172_800,
+            milestones: vec![
+                Milestone {
+                    amount: 600,
+                    state: MilestoneState::Claimed,
+                    ..Milestone::EMPTY
+                },
+                Milestone {
+                    amount: 400,
+                    state: MilestoneState::Released,
+                    ..Milestone::EMPTY
+                },
+            ],
+            arbiter: [6; 32],
+            is_disputed: true,
+            is_native: false,
+        }))
+    );
+
+    let multisig = ClientMultisig {
+        discriminator: AccountDiscriminator::ClientMultisig as u8,
+        version: ClientMultisig::VERSION,
+        members: [[1; 32], [2; 32], [3; 32], [0; 32], [0; 32]],
+        member_count: 3,
+        threshold: 2,
+        open_approvals: 4,
+        _padding0: [0; 35],
+        bump: 251,
+        client: [9; 32],
+    };
+    assert_eq!(
+        decode_account(&bytes_of(&multisig)),
+        Ok(DecodedAccount::ClientMultisig(ClientMultisigAccount {
+            version: ClientMultisig::VERSION,
+            client: [9; 32],
+            members: vec![[1; 32], [2; 32], [3; 32]],
+            threshold: 2,
+            open_approvals: 4,
+            bump: 251,
+        }))
+    );
+
+    let badge = FreelancerBadge {
+        discriminator: AccountDiscriminator::FreelancerBadge as u8,
+        version: FreelancerBadge::VERSION,
+        tier: ReputationTier::Professional,
+        _padding0: [0; 1],
+        completed_escrows: 21,
+        total_value_completed: 987_654,
+        freelancer: [8; 32],
+        bump: 250,
+        _padding1: [0; 3],
+        disputes_won: 2,
+        disputes_lost: 1,
+        _padding2: [0; 4],
+    };
+    assert_eq!(
+        decode_account(&bytes_of(&badge)),
+        Ok(DecodedAccount::FreelancerBadge(FreelancerBadgeAccount {
+            version: FreelancerBadge::VERSION,
+            freelancer: [8; 32],
+            tier: ReputationTier::Professional,
+            completed_escrows: 21,
+            total_value_completed: 987_654,
+            bump: 250,
+            disputes_won: 2,
+            disputes_lost: 1,
+        }))
+    );
+
+    assert_eq!(
+        decode_account(&bytes_of(&user_stats())),
+        Ok(DecodedAccount::UserStats(UserStatsAccount {
+            version: UserStats::VERSION,
+            user: [7; 32],
+            completed_escrows: 3,
+            bump: 254,
+            disputes_won: 1,
+            disputes_lost: 2,
+            escrows_funded: 5,
+            escrows_released: 4,
+            escrows_refunded: 1,
+            total_funded: 5_000,
+            total_release_seconds: 400,
+            average_release_seconds: Some(100),
+        }))
+    );
+
+    let approval = MultisigApproval {
+        discriminator: AccountDiscriminator::MultisigApproval as u8,
+        version: MultisigApproval::VERSION,
+        multisig: [5; 32],
+        escrow: [6; 32],
+        approvers: [[1; 32], [3; 32], [0; 32], [0; 32], [0; 32]],
+        approver_count: 2,
+        bump: 249,
+    };
+    assert_eq!(
+        decode_account(&bytes_of(&approval)),
+        Ok(DecodedAccount::MultisigApproval(MultisigApprovalAccount {
+            version: MultisigApproval::VERSION,
+            multisig: [5; 32],
+            escrow: [6; 32],
+            approvers: vec![[1; 32], [3; 32]],
+            bump: 249,
+        }))
+    );
+}
+
+#[test]
+fn accounts_with_a_bad_header_or_length_are_rejected() {
+    assert_eq!(
+        decode_account(&[]),
+        Err(DecodeError::InvalidLength {
+            expected: 1,
+            actual: 0,
+        })
+    );
+
+    let mut data = bytes_of(&escrow());
+    data[0] = 42;
+    assert_eq!(decode_account(&data), Err(DecodeError::UnknownAccount(42)));
+    data[0] = AccountDiscriminator::Uninitialized as u8;
+    assert_eq!(decode_account(&data), Err(DecodeError::UnknownAccount(0)));
+
+    let mut data = bytes_of(&escrow());
+    data[1] = Escrow::VERSION + 1;
+    assert_eq!(
+        decode_account(&data),
+        Err(DecodeError::UnsupportedAccountVersion {
+            discriminator: AccountDiscriminator::Escrow as u8,
+            version: Escrow::VERSION + 1,
+        })
+    );
+
+    let mut data = bytes_of(&escrow());
+    data.pop();
+    assert_eq!(
+        decode_account(&data),
+        Err(DecodeError::InvalidLength {
+            expected: Escrow::LEN,
+            actual: Escrow::LEN - 1,
+        })
+    );
+
+    // only the header
+    assert_eq!(
+        decode_account(&[AccountDiscriminator::Escrow as u8]),
+        Err(DecodeError::InvalidLength {
+            expected: Escrow::LEN,
+            actual: 1,
+        })
+    );
+}
+
+#[test]
+fn accounts_with_out_of_range_fields_are_rejected() {
+    let mut data = bytes_of(&escrow());
+    data[core::mem::offset_of!(Escrow, is_disputed)] = 2;
+    assert_eq!(
+        decode_account(&data),
+        Err(DecodeError::InvalidField("is_disputed"))
+    );
+
+    let mut data = bytes_of(&escrow());
+    data[core::mem::offset_of!(Escrow, milestone_count)] = MAX_MILESTONES as u8 + 1;
+    assert_eq!(
+        decode_account(&data),
+        Err(DecodeError::InvalidField("milestone_count"))
+    );
+
+    let mut data = bytes_of(&escrow());
+    data[core::mem::offset_of!(Escrow, milestones) + core::mem::offset_of!(Milestone, state)] = 3;
+    assert_eq!(
+        decode_account(&data),
+        Err(DecodeError::InvalidField("state"))
+    );
+}
+
+#[test]
+fn instructions_decode_to_what_the_client_sent() {
+    let mut milestone_amounts = [0; MAX_MILESTONES];
+    milestone_amounts[..3].copy_from_slice(&[100, 200, 300]);
+
+    let cases = [
+        (
+            instruction_data(
+                BondrInstruction::InitializeEscrow,
+                &InitializeEscrow {
+                    amount: 600,
+                    reference_seed: 7,
+                    deadline: 1_700_000_000,
+                    auto_release_at: 0,
+                    milestone_amounts,
+                    arbiter: [6; 32],
+                    milestone_count: 3,
+                    bump: 255,
+                    stats_bump: 254,
+                    is_multisig: true,
+                    is_native: false,
+                    approval_bump: 253,
+                    _padding: [0; 2],
+                },
+            ),
+            DecodedInstruction::InitializeEscrow {
+                amount: 600,
+                reference_seed: 7,
+                deadline: 1_700_000_000,
+                auto_release_at: 0,
+                milestone_amounts: vec![100, 200, 300],
+                arbiter: [6; 32],
+                bump: 255,
+                stats_bump: 254,
+                approval_bump: 253,
+                is_multisig: true,
+                is_native: false,
+            },
+        ),
+        (
+            instruction_data(
+                BondrInstruction::InitializeFreelancerBadge,
+                &InitializeFreelancerBadge { bump: 252 },
+            ),
+            DecodedInstruction::InitializeFreelancerBadge { bump: 252 },
+        ),
+        (
+            instruction_data(
+                BondrInstruction::InitializeMultisigClient,
+                &InitializeMultisigClient {
+                    members: [[1; 32], [2; 32], [0; 32], [0; 32], [0; 32]],
+                    member_count: 2,
+                    threshold: 2,
+                    bump: 251,
+                },
+            ),
+            DecodedInstruction::InitializeMultisigClient {
+                members: vec![[1; 32], [2; 32]],
+                threshold: 2,
+                bump: 251,
+            },
+        ),
+        (
+            instruction_data(BondrInstruction::ReleasePayment, &ReleasePayment {}),
+            DecodedInstruction::ReleasePayment,
+        ),
+        (
+            instruction_data(
+                BondrInstruction::ClaimPayment,
+                &ClaimPayment {
+                    receiver_stats_bump: 250,
+                },
+            ),
+            DecodedInstruction::ClaimPayment {
+                receiver_stats_bump: 250,
+            },
+        ),
+        (
+            instruction_data(
+                BondrInstruction::ApproveMultisigRelease,
+                &ApproveMultisigRelease {},
+            ),
+            DecodedInstruction::ApproveMultisigRelease,
+        ),
+        (
+            instruction_data(
+                BondrInstruction::UpdateFreelancerBadge,
+                &UpdateFreelancerBadge { value: 12_345 },
+            ),
+            DecodedInstruction::UpdateFreelancerBadge { value: 12_345 },
+        ),
+        (
+            instruction_data(BondrInstruction::MintReputationNft, &MintReputationNft {}),
+            DecodedInstruction::MintReputationNft,
+        ),
+        (
+            instruction_data(BondrInstruction::CancelEscrow, &CancelEscrow {}),
+            DecodedInstruction::CancelEscrow,
+        ),
+        (
+            instruction_data(
+                BondrInstruction::ReleaseMilestone,
+                &ReleaseMilestone { index: 1 },
+            ),
+            DecodedInstruction::ReleaseMilestone { index: 1 },
+        ),
+        (
+            instruction_data(
+                BondrInstruction::ClaimMilestone,
+                &ClaimMilestone {
+                    index: 2,
+                    receiver_stats_bump: 249,
+                },
+            ),
+            DecodedInstruction::ClaimMilestone {
+                index: 2,
+                receiver_stats_bump: 249,
+            },
+        ),
+        (
+            instruction_data(BondrInstruction::OpenDispute, &OpenDispute {}),
+            DecodedInstruction::OpenDispute,
+        ),
+        (
+            instruction_data(
+                BondrInstruction::ResolveDispute,
+                &ResolveDispute {
+                    receiver_bps: 7_500,
+                },
+            ),
+            DecodedInstruction::ResolveDispute {
+                receiver_bps: 7_500,
+            },
+        ),
+        (
+            instruction_data(
+                BondrInstruction::InitializeConfig,
+                &InitializeConfig {
+                    settings: config_settings(),
+                    bump: 248,
+                    treasury_bump: 247,
+                    _padding: [0; 2],
+                    min_reputation_amounts: [1, 2, 0, 0, 0, 0, 0, 0],
+                },
+            ),
+            DecodedInstruction::InitializeConfig {
+                settings: decoded_settings(),
+                bump: 248,
+                treasury_bump: 247,
+                min_reputation_amounts: [1, 2, 0, 0, 0, 0, 0, 0],
+            },
+        ),
+        (
+            instruction_data(
+                BondrInstruction::WithdrawTreasury,
+                &WithdrawTreasury { amount: 9_999 },
+            ),
+            DecodedInstruction::WithdrawTreasury { amount: 9_999 },
+        ),
+        (
+            instruction_data(
+                BondrInstruction::UpdateConfig,
+                &UpdateConfig {
+                    settings: config_settings(),
+                    _padding: [0; 4],
+                    min_reputation_amounts: [3, 4, 0, 0, 0, 0, 0, 0],
+                },
+            ),
+            DecodedInstruction::UpdateConfig {
+                settings: decoded_settings(),
+                min_reputation_amounts: [3, 4, 0, 0, 0, 0, 0, 0],
+            },
+        ),
+        (
+            instruction_data(BondrInstruction::TransferAdmin, &TransferAdmin {}),
+            DecodedInstruction::TransferAdmin,
+        ),
+        (
+            instruction_data(
+                BondrInstruction::SetPause,
+                &SetPause {
+                    paused_instructions: 1 << BondrInstruction::ClaimPayment as u8,
+                    paused: true,
+                    _padding: [0; 3],
+                },
+            ),
+            DecodedInstruction::SetPause {
+                paused_instructions: 1 << BondrInstruction::ClaimPayment as u8,
+                paused: true,
+            },
+        ),
+        (
+            instruction_data(BondrInstruction::MigrateAccount, &MigrateAccount {}),
+            DecodedInstruction::MigrateAccount,
+        ),
+        (
+            instruction_data(
+                BondrInstruction::AddMultisigMember,
+                &AddMultisigMember { member: [4; 32] },
+            ),
+            DecodedInstruction::AddMultisigMember { member: [4; 32] },
+        ),
+        (
+            instruction_data(
+                BondrInstruction::RemoveMultisigMember,
+                &RemoveMultisigMember { member: [2; 32] },
+            ),
+            DecodedInstruction::RemoveMultisigMember { member: [2; 32] },
+        ),
+        (
+            instruction_data(
+                BondrInstruction::RotateMultisigMember,
+                &RotateMultisigMember {
+                    old_member: [1; 32],
+                    new_member: [5; 32],
+                },
+            ),
+            DecodedInstruction::RotateMultisigMember {
+                old_member: [1; 32],
+                new_member: [5; 32],
+            },
+        ),
+        (
+            instruction_data(
+                BondrInstruction::ChangeThreshold,
+                &ChangeThreshold { threshold: 3 },
+            ),
+            DecodedInstruction::ChangeThreshold { threshold: 3 },
+        ),
+    ];
+
+    // one case per instruction
+    assert_eq!(cases.len(), BondrInstruction::ChangeThreshold as usize + 1);
+    for (data, expected) in cases {
+        assert_eq!(decode_instruction(&data), Ok(expected), "{data:?}");
+    }
+}
+
+#[test]
+fn malformed_instruction_data_is_rejected() {
+    assert_eq!(
+        decode_instruction(&[]),
+        Err(DecodeError::InvalidLength {
+            expected: 1,
+            actual: 0,
+        })
+    );
+
+    let unknown = BondrInstruction::ChangeThreshold as u8 + 1;
+    assert_eq!(
+        decode_instruction(&[unknown]),
+        Err(DecodeError::UnknownInstruction(unknown))
+    );
+
+    // the program rejects both short and trailing payloads
+    let mut data = instruction_data(
+        BondrInstruction::WithdrawTreasury,
+        &WithdrawTreasury { amount: 1 },
+    );
+    data.push(0);
+    assert_eq!(
+        decode_instruction(&data),
+        Err(DecodeError::InvalidLength {
+            expected: WithdrawTreasury::LEN,
+            actual: WithdrawTreasury::LEN + 1,
+        })
+    );
+    assert_eq!(
+        decode_instruction(&data[..data.len() - 2]),
+        Err(DecodeError::InvalidLength {
+            expected: WithdrawTreasury::LEN,
+            actual: WithdrawTreasury::LEN - 1,
+        })
+    );
+    assert_eq!(
+        decode_instruction(&[BondrInstruction::ReleasePayment as u8, 0]),
+        Err(DecodeError::InvalidLength {
+            expected: 0,
+            actual: 1,
+        })
+    );
+
+    let mut data = instruction_data(
+        BondrInstruction::SetPause,
+        &SetPause {
+            paused_instructions: 0,
+            paused: false,
+            _padding: [0; 3],
+        },
+    );
+    data[1 + core::mem::offset_of!(SetPause, paused)] = 2;
+    assert_eq!(
+        decode_instruction(&data),
+        Err(DecodeError::InvalidField("paused"))
+    );
+}
+
+fn events() -> Vec<(Vec<u8>, DecodedEvent)> {
+    macro_rules! cases {
+        ($($name:ident { $($field:ident: $value:expr),* $(,)? }),* $(,)?) => {
+            vec![$({
+                let event = $name { $($field: $value),* };
+                (logged(&event), DecodedEvent::$name(event))
+            }),*]
+        };
+    }
+
+    cases![
+        EscrowCreated {
+            escrow: [1; 32],
+            sender: [2; 32],
+            receiver: [3; 32],
+            mint: [4; 32],
+            amount: 1_000,
+            reference_seed: 7,
+            deadline: 1_700_000_000,
+            auto_release_at: -1,
+            milestone_count: 2,
+            has_multisig: true,
+            is_native: false,
+        },
+        PaymentReleased {
+            escrow: [1; 32],
+            released_by: [2; 32],
+        },
+        MultisigApproved {
+            escrow: [1; 32],
+            multisig: [5; 32],
+            member: [6; 32],
+            approvals: 2,
+            threshold: 2,
+            released: true,
+        },
+        PaymentClaimed {
+            escrow: [1; 32],
+            receiver: [3; 32],
+            amount: 985,
+            fee: 15,
+        },
+        EscrowCancelled {
+            escrow: [1; 32],
+            sender: [2; 32],
+            refunded: 1_000,
+        },
+        MilestoneReleased {
+            escrow: [1; 32],
+            index: 3,
+        },
+        MilestoneClaimed {
+            escrow: [1; 32],
+            receiver: [3; 32],
+            amount: 394,
+            fee: 6,
+            index: 1,
+            settled: true,
+        },
+        DisputeOpened {
+            escrow: [1; 32],
+            opened_by: [3; 32],
+        },
+        DisputeResolved {
+            escrow: [1; 32],
+            arbiter: [7; 32],
+            receiver_amount: 750,
+            sender_amount: 250,
+            receiver_bps: 7_500,
+        },
+        BadgeCreated {
+            badge: [8; 32],
+            freelancer: [3; 32],
+        },
+        BadgeUpdated {
+            badge: [8; 32],
+            freelancer: [3; 32],
+            completed_escrows: 21,
+            total_value_completed: u64::MAX,
+            disputes_won: 2,
+            disputes_lost: 1,
+        },
+        NftMinted {
+            freelancer: [3; 32],
+            asset: [9; 32],
+            tier: ReputationTier::Elite as u8,
+        },
+        MultisigCreated {
+            multisig: [5; 32],
+            client: [2; 32],
+            member_count: 3,
+            threshold: 2,
+        },
+        ConfigUpdated {
+            admin: [10; 32],
+            fee_bps: 150,
+        },
+        PauseUpdated {
+            authority: [11; 32],
+            paused_instructions: u32::MAX,
+            paused: true,
+        },
+        TreasuryWithdrawn {
+            mint: [0; 32],
+            destination: [12; 32],
+            amount: 5_000,
+        },
+        AccountMigrated {
+            account: [1; 32],
+            discriminator: AccountDiscriminator::Escrow as u8,
+            from_version: 1,
+            to_version: 2,
+        },
+        MultisigUpdated {
+            multisig: [5; 32],
+            added: [13; 32],
+            removed: [6; 32],
+            member_count: 3,
+            threshold: 2,
+        },
+    ]
+}
+
+#[test]
+fn events_decode_to_what_the_program_logged() {
+    let events = events();
+    // one case per kind
+    assert_eq!(events.len(), EventKind::MultisigUpdated as usize + 1);
+
+    for (data, expected) in events {
+        assert_eq!(decode_event(&data), Ok(expected.clone()));
+
+        // `sol_log_data` base64-encodes the header and the event separately
+        let line = format!(
+            "Program data: {} {}",
+            STANDARD.encode(&data[..2]),
+            STANDARD.encode(&data[2..])
+        );
+        assert_eq!(decode_program_data(&line), Ok(expected.clone()));
+        assert_eq!(decode_program_data(&STANDARD.encode(&data)), Ok(expected));
+    }
+}
+
+#[test]
+fn malformed_events_are_rejected() {
+    let (data, _) = events().swap_remove(0);
+
+    assert_eq!(
+        decode_event(&[EVENT_VERSION]),
+        Err(DecodeError::InvalidLength {
+            expected: 2,
+            actual: 1,
+        })
+    );
+
+    let mut wrong_version = data.clone();
+    wrong_version[0] = EVENT_VERSION + 1;
+    assert_eq!(
+        decode_event(&wrong_version),
+        Err(DecodeError::UnsupportedEventVersion(EVENT_VERSION + 1))
+    );
+
+    let unknown = EventKind::MultisigUpdated as u8 + 1;
+    let mut unknown_kind = data.clone();
+    unknown_kind[1] = unknown;
+    assert_eq!(
+        decode_event(&unknown_kind),
+        Err(DecodeError::UnknownEvent(unknown))
+    );
+
+    assert_eq!(
+        decode_event(&data[..data.len() - 1]),
+        Err(DecodeError::InvalidLength {
+            expected: core::mem::size_of::<EscrowCreated>(),
+            actual: data.len() - 3,
+        })
+    );
+
+    let mut invalid_bool = data.clone();
+    invalid_bool[data.len() - 1] = 2;
+    assert_eq!(
+        decode_event(&invalid_bool),
+        Err(DecodeError::InvalidField("is_native"))
+    );
+
+    assert_eq!(
+        decode_program_data("Program data: not*base64"),
+        Err(DecodeError::InvalidBase64)
+    );
+    assert_eq!(
+        decode_program_data(&format!("Program data: {}=", STANDARD.encode(&data))),
+        Err(DecodeError::InvalidBase64)
+    );
+}
@@ -0,0 +1,277 @@
+//! Disputes: either party freezes the escrow and the arbiter splits the funds.
+
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use bondr_pinocchio::{
+    client::find_badge_pda,
+    errors::BondrError,
+    states::{Escrow, FreelancerBadge, ReputationTier, UserStats},
+};
+use common::*;
+use solana_sdk::{program_error::ProgramError, pubkey::Pubkey};
+
+fn disputable(env: &Env, args: EscrowArgs) -> TestEscrow {
+    env.create_escrow(EscrowArgs {
+        arbiter: Pubkey::new_unique(),
+        ..args
+    })
+}
+
+#[test]
+fn either_party_opens_a_dispute() {
+    let env = Env::new();
+
+    for by_client in [true, false] {
+        let escrow = disputable(&env, EscrowArgs::default());
+        let party = if by_client {
+            escrow.client.wallet
+        } else {
+            escrow.freelancer.wallet
+        };
+
+        env.ok(&env.open_dispute(&escrow, &party));
+        assert!(env.state::<Escrow>(&escrow.address).is_disputed);
+        env.err(
+            &env.open_dispute(&escrow, &party),
+            BondrError::EscrowDisputed,
+        );
+    }
+}
+
+#[test]
+fn open_dispute_checks_the_escrow() {
+    let env = Env::new();
+
+    let escrow = disputable(&env, EscrowArgs::default());
+    env.err(
+        &env.open_dispute(&escrow, &Pubkey::new_unique()),
+        BondrError::UnauthorizedSender,
+    );
+    env.err_with(
+        &unsigned(
+            env.open_dispute(&escrow, &escrow.client.wallet),
+            &escrow.client.wallet,
+        ),
+        ProgramError::MissingRequiredSignature,
+    );
+
+    let escrow = env.create_escrow(EscrowArgs::default());
+    env.err(
+        &env.open_dispute(&escrow, &escrow.client.wallet),
+        BondrError::NoArbiter,
+    );
+
+    let escrow = disputable(&env, EscrowArgs::default());
+    env.ok(&env.release(&escrow));
+    env.err(
+        &env.open_dispute(&escrow, &escrow.freelancer.wallet),
+        BondrError::AlreadyReleased,
+    );
+
+    // nothing left pending once every milestone is released
+    let escrow = disputable(
+        &env,
+        EscrowArgs {
+            milestones: vec![ESCROW_AMOUNT],
+            ..EscrowArgs::default()
+        },
+    );
+    env.ok(&env.release_milestone(&escrow, 0));
+    env.err(
+        &env.open_dispute(&escrow, &escrow.freelancer.wallet),
+        BondrError::AlreadyReleased,
+    );
+}
+
+#[test]
+fn resolve_dispute_splits_the_vault() {
+    let env = Env::new();
+    let escrow = disputable(&env, EscrowArgs::default());
+    let badge = env.create_badge(&escrow.freelancer.wallet);
+    env.ok(&env.open_dispute(&escrow, &escrow.client.wallet));
+
+    env.ok(&env.resolve_dispute(&escrow, 7_000));
+
+    let to_freelancer = ESCROW_AMOUNT * 7 / 10;
+    assert_eq!(
+        env.token_balance(&escrow.freelancer.token),
+        TOKEN_BALANCE + to_freelancer
+    );
+    assert_eq!(
+        env.token_balance(&escrow.client.token),
+        TOKEN_BALANCE - to_freelancer
+    );
+    assert!(!env.exists(&escrow.address));
+    assert!(!env.exists(&escrow.vault));
+
+    // the freelancer won
+    let badge = env.state::<FreelancerBadge>(&badge);
+    assert_eq!((badge.disputes_won, badge.disputes_lost), (1, 0));
+    // and the client's record shows the loss
+    let stats = env.state::<UserStats>(&escrow.client_stats());
+    assert_eq!((stats.disputes_won, stats.disputes_lost), (0, 1));
+}
+
+#[test]
+fn resolve_dispute_refunds_native_escrows_in_lamports() {
+    let env = Env::new();
+    let escrow = disputable(
+        &env,
+        EscrowArgs {
+            native: true,
+            ..EscrowArgs::default()
+        },
+    );
+    let badge = env.create_badge(&escrow.freelancer.wallet);
+    env.ok(&env.open_dispute(&escrow, &escrow.freelancer.wallet));
+    let freelancer_lamports = env.lamports(&escrow.freelancer.wallet);
+
+    env.ok(&env.resolve_dispute(&escrow, 2_500));
+
+    assert_eq!(
+        env.lamports(&escrow.freelancer.wallet),
+        freelancer_lamports + ESCROW_AMOUNT / 4
+    );
+    let badge = env.state::<FreelancerBadge>(&badge);
+    assert_eq!((badge.disputes_won, badge.disputes_lost), (0, 1));
+}
+
+#[test]
+fn resolve_dispute_splits_only_unclaimed_milestones() {
+    let env = Env::new();
+    let escrow = disputable(
+        &env,
+        EscrowArgs {
+            milestones: vec![ESCROW_AMOUNT / 2; 2],
+            ..EscrowArgs::default()
+        },
+    );
+    env.ok(&env.release_milestone(&escrow, 0));
+    env.ok(&env.claim_milestone(&escrow, 0));
+    env.ok(&env.open_dispute(&escrow, &escrow.client.wallet));
+
+    env.ok(&env.resolve_dispute(&escrow, 0));
+    assert_eq!(
+        env.token_balance(&escrow.client.token),
+        TOKEN_BALANCE - ESCROW_AMOUNT / 2
+    );
+}
+
+#[test]
+fn resolve_dispute_pays_out_released_milestones() {
+    let env = Env::new();
+    let escrow = disputable(
+        &env,
+        EscrowArgs {
+            milestones: vec![ESCROW_AMOUNT / 2; 2],
+            ..EscrowArgs::default()
+        },
+    );
+    // released but not yet claimed when the client disputes the rest
+    env.ok(&env.release_milestone(&escrow, 0));
+    env.ok(&env.open_dispute(&escrow, &escrow.client.wallet));
+
+    let foreign_treasury = env.create_token_account(&Pubkey::new_unique(), 0);
+    env.err(
+        &swap_account(
+            env.resolve_dispute(&escrow, 0),
+            &env.treasury_token,
+            &foreign_treasury,
+        ),
+        BondrError::InvalidTreasury,
+    );
+
+    env.ok(&env.resolve_dispute(&escrow, 0));
+    // the released milestone pays the protocol fee, as if it had been claimed
+    let fee = protocol_fee(ESCROW_AMOUNT / 2, ReputationTier::Unranked as usize);
+    assert_eq!(
+        env.token_balance(&escrow.freelancer.token),
+        TOKEN_BALANCE + ESCROW_AMOUNT / 2 - fee
+    );
+    assert_eq!(env.token_balance(&env.treasury_token), fee);
+    assert_eq!(
+        env.token_balance(&escrow.client.token),
+        TOKEN_BALANCE - ESCROW_AMOUNT / 2
+    );
+}
+
+#[test]
+fn resolve_dispute_checks_the_arbiter_and_split() {
+    let env = Env::new();
+    let escrow = disputable(&env, EscrowArgs::default());
+
+    env.err(
+        &env.resolve_dispute(&escrow, 5_000),
+        BondrError::NotDisputed,
+    );
+    env.ok(&env.open_dispute(&escrow, &escrow.client.wallet));
+
+    env.err(
+        &env.resolve_dispute(&escrow, 10_001),
+        BondrError::InvalidBasisPoints,
+    );
+    env.err(
+        &swap_account(
+            env.resolve_dispute(&escrow, 5_000),
+            &escrow.args.arbiter,
+            &escrow.client.wallet,
+        ),
+        BondrError::UnauthorizedArbiter,
+    );
+    env.err_with(
+        &unsigned(env.resolve_dispute(&escrow, 5_000), &escrow.args.arbiter),
+        ProgramError::MissingRequiredSignature,
+    );
+}
+
+#[test]
+fn resolve_dispute_checks_the_parties() {
+    let env = Env::new();
+    let escrow = disputable(&env, EscrowArgs::default());
+    let stranger = env.party();
+    env.ok(&env.open_dispute(&escrow, &escrow.client.wallet));
+
+    let resolve = env.resolve_dispute(&escrow, 5_000);
+    env.err(
+        &swap_account(resolve.clone(), &escrow.client.wallet, &stranger.wallet),
+        BondrError::UnauthorizedSender,
+    );
+    env.err(
+        &swap_account(resolve.clone(), &escrow.freelancer.wallet, &stranger.wallet),
+        BondrError::UnauthorizedReceiver,
+    );
+
+    // reputation accounts must belong to the parties
+    let other_badge = env.create_badge(&stranger.wallet);
+    env.err(
+        &swap_account(
+            resolve.clone(),
+            &find_badge_pda(&escrow.freelancer.wallet).0,
+            &other_badge,
+        ),
+        BondrError::InvalidOwner,
+    );
+    // nor can the freelancer's badge be left out
+    env.create_badge(&escrow.freelancer.wallet);
+    env.err(
+        &swap_account(
+            resolve.clone(),
+            &find_badge_pda(&escrow.freelancer.wallet).0,
+            &Pubkey::new_unique(),
+        ),
+        BondrError::PdaMismatch,
+    );
+
+    let other_stats = env.create_escrow(EscrowArgs::default()).client_stats();
+    env.err(
+        &swap_account(resolve.clone(), &escrow.client_stats(), &other_stats),
+        BondrError::InvalidOwner,
+    );
+    // the client's record can't be left out
+    env.err(
+        &swap_account(resolve, &escrow.client_stats(), &Pubkey::new_unique()),
+        BondrError::InvalidOwner,
+    );
+}
@@ -0,0 +1,845 @@
+//! Single-payment escrows: create, release, claim and cancel.
+
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use bondr_pinocchio::{
+    client::{find_user_stats_pda, PROGRAM_ID},
+    decoder::{DecodedEvent, EscrowCreated, PaymentClaimed, PaymentReleased},
+    errors::BondrError,
+    instructions::InitializeEscrow,
+    states::{DataLen, Escrow, FreelancerBadge, ReputationTier, TransferFee, UserStats},
+};
+use common::*;
+use solana_sdk::{account::Account, program_error::ProgramError, pubkey::Pubkey, rent::Rent};
+
+fn rent(len: usize) -> u64 {
+    Rent::default().minimum_balance(len)
+}
+
+#[test]
+fn initialize_escrow_funds_the_vault() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs::default());
+
+    assert_eq!(env.token_balance(&escrow.vault), ESCROW_AMOUNT);
+    assert_eq!(
+        env.token_balance(&escrow.client.token),
+        TOKEN_BALANCE - ESCROW_AMOUNT
+    );
+
+    let state = env.state::<Escrow>(&escrow.address);
+    assert_eq!(state.sender, escrow.client.wallet.to_bytes());
+    assert_eq!(state.receiver, escrow.freelancer.wallet.to_bytes());
+    assert_eq!(state.mint, env.mint.to_bytes());
+    assert_eq!(state.token_program, TOKEN_PROGRAM.to_bytes());
+    assert_eq!(state.amount, ESCROW_AMOUNT);
+    assert_eq!(state.bump, escrow.bump);
+    assert_eq!(state.created_at, NOW);
+    assert!(!state.is_released && !state.is_native && !state.has_multisig);
+}
+
+#[test]
+fn initialize_native_escrow_holds_lamports() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs {
+        native: true,
+        ..EscrowArgs::default()
+    });
+
+    assert_eq!(
+        env.lamports(&escrow.address),
+        rent(Escrow::LEN) + ESCROW_AMOUNT
+    );
+    assert!(env.state::<Escrow>(&escrow.address).is_native);
+}
+
+#[test]
+fn initialize_escrow_grosses_up_transfer_fees() {
+    let env = Env::new();
+    let fee = TransferFee {
+        maximum_fee: 1_000_000,
+        basis_points: 100,
+    };
+    let mint = Pubkey::new_unique();
+    env.set_account(&mint, fee_mint_account(fee));
+
+    let mut settings = env.settings();
+    settings.allowed_mints[2] = mint.to_bytes();
+    settings.allowed_mint_count = 3;
+    env.ok(&env.update_config(&settings));
+
+    // the same escrow, in the fee-bearing mint
+    let (client, freelancer) = (env.party(), env.party());
+    let (escrow, create) = env.escrow_ix(&client, &freelancer, EscrowArgs::default());
+    let (source, vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+    env.set_account(
+        &source,
+        fee_token_account(&mint, &client.wallet, TOKEN_BALANCE),
+    );
+    env.set_account(&vault, fee_token_account(&mint, &escrow.address, 0));
+    let create = [
+        (env.mint, mint),
+        (TOKEN_PROGRAM, TOKEN_2022),
+        (escrow.vault, vault),
+        (client.token, source),
+    ]
+    .iter()
+    .fold(create, |create, (from, to)| swap_account(create, from, to));
+    env.ok(&create);
+
+    let deposit = fee.calculate_pre_fee_amount(ESCROW_AMOUNT).unwrap();
+    assert!(deposit > ESCROW_AMOUNT);
+    assert_eq!(env.token_balance(&source), TOKEN_BALANCE - deposit);
+    assert_eq!(env.token_balance(&vault), ESCROW_AMOUNT);
+    // the fee is withheld in the vault, outside its balance
+    let withheld = &env.account(&vault).data[170..178];
+    assert_eq!(
+        u64::from_le_bytes(withheld.try_into().unwrap()),
+        deposit - ESCROW_AMOUNT
+    );
+
+    let state = env.state::<Escrow>(&escrow.address);
+    assert_eq!(state.amount, ESCROW_AMOUNT);
+    assert_eq!(state.token_program, TOKEN_2022.to_bytes());
+}
+
+#[test]
+fn initialize_escrow_survives_a_prefunded_address() {
+    let env = Env::new();
+    let (client, freelancer) = (env.party(), env.party());
+    let (escrow, create) = env.escrow_ix(&client, &freelancer, EscrowArgs::default());
+
+    // lamports sent to the PDA before it exists must not block the escrow
+    env.set_account(&escrow.address, Account::new(1, 0, &SYSTEM_PROGRAM));
+
+    env.ok(&create);
+    assert_eq!(env.state::<Escrow>(&escrow.address).amount, ESCROW_AMOUNT);
+    assert_eq!(env.lamports(&escrow.address), rent(Escrow::LEN));
+}
+
+#[test]
+fn parties_can_run_several_escrows() {
+    let env = Env::new();
+    let (client, freelancer) = (env.party(), env.party());
+
+    for reference_seed in 1..=3 {
+        env.create_escrow_between(
+            &client,
+            &freelancer,
+            EscrowArgs {
+                reference_seed,
+                ..EscrowArgs::default()
+            },
+        );
+    }
+    assert_eq!(
+        env.token_balance(&client.token),
+        TOKEN_BALANCE - 3 * ESCROW_AMOUNT
+    );
+}
+
+#[test]
+fn initialize_escrow_rejects_invalid_terms() {
+    let env = Env::new();
+
+    let cases = [
+        (
+            EscrowArgs {
+                amount: 0,
+                ..EscrowArgs::default()
+            },
+            BondrError::InvalidAmountZero,
+        ),
+        (
+            EscrowArgs {
+                milestones: vec![1; 9],
+                amount: 9,
+                ..EscrowArgs::default()
+            },
+            BondrError::InvalidMilestone,
+        ),
+        (
+            EscrowArgs {
+                milestones: vec![ESCROW_AMOUNT, 0],
+                ..EscrowArgs::default()
+            },
+            BondrError::InvalidAmountZero,
+        ),
+        (
+            EscrowArgs {
+                milestones: vec![u64::MAX, 1],
+                ..EscrowArgs::default()
+            },
+            BondrError::AmountTooLarge,
+        ),
+        (
+            EscrowArgs {
+                milestones: vec![1, 2],
+                ..EscrowArgs::default()
+            },
+            BondrError::InvalidAmount,
+        ),
+        (
+            EscrowArgs {
+                deadline: NOW,
+                ..EscrowArgs::default()
+            },
+            BondrError::InvalidDeadline,
+        ),
+        (
+            EscrowArgs {
+                auto_release_at: NOW - 1,
+                ..EscrowArgs::default()
+            },
+            BondrError::InvalidDeadline,
+        ),
+        (
+            EscrowArgs {
+                deadline: NOW + 2 * DAY,
+                auto_release_at: NOW + DAY,
+                ..EscrowArgs::default()
+            },
+            BondrError::InvalidDeadline,
+        ),
+        (
+            EscrowArgs {
+                amount: TOKEN_BALANCE + 1,
+                ..EscrowArgs::default()
+            },
+            BondrError::InsufficientBalance,
+        ),
+        (
+            EscrowArgs {
+                amount: WALLET_LAMPORTS + 1,
+                native: true,
+                ..EscrowArgs::default()
+            },
+            BondrError::InsufficientBalance,
+        ),
+    ];
+
+    for (args, error) in cases {
+        let (_, instruction) = env.escrow_ix(&env.party(), &env.party(), args);
+        env.err(&instruction, error);
+    }
+
+    // flags must be valid bools before the data is read
+    for flag in [
+        core::mem::offset_of!(InitializeEscrow, is_multisig),
+        core::mem::offset_of!(InitializeEscrow, is_native),
+    ] {
+        let (_, mut instruction) = env.escrow_ix(&env.party(), &env.party(), EscrowArgs::default());
+        instruction.data[1 + flag] = 2;
+        env.err(&instruction, BondrError::InvalidInstructionData);
+    }
+}
+
+#[test]
+fn initialize_escrow_rejects_invalid_parties() {
+    let env = Env::new();
+    let (client, freelancer) = (env.party(), env.party());
+
+    let (_, instruction) = env.escrow_ix(&client, &client, EscrowArgs::default());
+    env.err(&instruction, BondrError::SelfTransfer);
+
+    for arbiter in [client.wallet, freelancer.wallet] {
+        let (_, instruction) = env.escrow_ix(
+            &client,
+            &freelancer,
+            EscrowArgs {
+                arbiter,
+                ..EscrowArgs::default()
+            },
+        );
+        env.err(&instruction, BondrError::InvalidArbiter);
+    }
+
+    let (_, instruction) = env.escrow_ix(&client, &freelancer, EscrowArgs::default());
+    env.err_with(
+        &unsigned(instruction, &client.wallet),
+        ProgramError::MissingRequiredSignature,
+    );
+}
+
+#[test]
+fn initialize_escrow_checks_accounts() {
+    let env = Env::new();
+    let (client, freelancer) = (env.party(), env.party());
+
+    // the PDA of another reference seed
+    let (escrow, instruction) = env.escrow_ix(&client, &freelancer, EscrowArgs::default());
+    let (other, _) = env.escrow_ix(
+        &client,
+        &freelancer,
+        EscrowArgs {
+            reference_seed: 2,
+            ..EscrowArgs::default()
+        },
+    );
+    env.err(
+        &swap_account(instruction.clone(), &escrow.address, &other.address),
+        BondrError::PdaMismatch,
+    );
+
+    // a mint outside the allow-list
+    let other_mint = Pubkey::new_unique();
+    env.set_account(&other_mint, mint_account());
+    env.err(
+        &swap_account(instruction.clone(), &env.mint, &other_mint),
+        BondrError::MintNotAllowed,
+    );
+
+    // a vault the escrow doesn't control, or of another mint
+    env.set_account(&escrow.vault, token_account(&env.mint, &client.wallet, 0));
+    env.err(&instruction, BondrError::InvalidOwner);
+
+    env.set_account(
+        &escrow.vault,
+        token_account(&other_mint, &escrow.address, 0),
+    );
+    env.err(&instruction, BondrError::MissingTokenAccounts);
+
+    // native escrows take the system program for the mint and token program
+    let (_, mut instruction) = env.escrow_ix(
+        &client,
+        &freelancer,
+        EscrowArgs {
+            native: true,
+            ..EscrowArgs::default()
+        },
+    );
+    instruction.accounts[8].pubkey = env.mint; // token_mint
+    env.err(&instruction, BondrError::MissingTokenAccounts);
+}
+
+#[test]
+fn initialize_escrow_cannot_overwrite_an_escrow() {
+    let env = Env::new();
+    let (client, freelancer) = (env.party(), env.party());
+    env.create_escrow_between(&client, &freelancer, EscrowArgs::default());
+
+    let (_, instruction) = env.escrow_ix(&client, &freelancer, EscrowArgs::default());
+    env.err_with(&instruction, ProgramError::AccountAlreadyInitialized);
+}
+
+#[test]
+fn release_payment_marks_the_escrow_released() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs::default());
+
+    env.ok(&env.release(&escrow));
+    assert!(env.state::<Escrow>(&escrow.address).is_released);
+
+    env.err(&env.release(&escrow), BondrError::AlreadyReleased);
+}
+
+#[test]
+fn release_payment_is_client_only() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs::default());
+
+    let release = env.release(&escrow);
+    env.err(
+        &swap_account(
+            release.clone(),
+            &escrow.client.wallet,
+            &escrow.freelancer.wallet,
+        ),
+        BondrError::UnauthorizedSender,
+    );
+    env.err_with(
+        &unsigned(release, &escrow.client.wallet),
+        ProgramError::MissingRequiredSignature,
+    );
+}
+
+#[test]
+fn release_payment_rejects_milestone_and_disputed_escrows() {
+    let env = Env::new();
+
+    let escrow = env.create_escrow(EscrowArgs {
+        milestones: vec![ESCROW_AMOUNT / 2; 2],
+        ..EscrowArgs::default()
+    });
+    env.err(
+        &env.release(&escrow),
+        BondrError::MilestoneEscrowNotSupported,
+    );
+
+    let escrow = env.create_escrow(EscrowArgs {
+        arbiter: Pubkey::new_unique(),
+        ..EscrowArgs::default()
+    });
+    env.ok(&env.open_dispute(&escrow, &escrow.freelancer.wallet));
+    env.err(&env.release(&escrow), BondrError::EscrowDisputed);
+}
+
+#[test]
+fn claim_payment_pays_the_freelancer_and_the_treasury() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs::default());
+    let client_lamports = env.lamports(&escrow.client.wallet);
+
+    env.ok(&env.release(&escrow));
+    env.ok(&env.claim(&escrow));
+
+    let fee = protocol_fee(ESCROW_AMOUNT, ReputationTier::Unranked as usize);
+    assert_eq!(
+        env.token_balance(&escrow.freelancer.token),
+        TOKEN_BALANCE + ESCROW_AMOUNT - fee
+    );
+    assert_eq!(env.token_balance(&env.treasury_token), fee);
+
+    // the escrow and its vault are closed, their rent goes back to the client
+    assert!(!env.exists(&escrow.address));
+    assert!(!env.exists(&escrow.vault));
+    assert_eq!(
+        env.lamports(&escrow.client.wallet),
+        client_lamports + rent(Escrow::LEN) + rent(165)
+    );
+}
+
+#[test]
+fn handlers_log_their_events() {
+    let env = Env::new();
+    let (client, freelancer) = (env.party(), env.party());
+    let (escrow, create) = env.escrow_ix(
+        &client,
+        &freelancer,
+        EscrowArgs {
+            deadline: NOW + DAY,
+            ..EscrowArgs::default()
+        },
+    );
+
+    assert_eq!(
+        env.events(&create),
+        [DecodedEvent::EscrowCreated(EscrowCreated {
+            escrow: escrow.address.to_bytes(),
+            sender: client.wallet.to_bytes(),
+            receiver: freelancer.wallet.to_bytes(),
+            mint: env.mint.to_bytes(),
+            amount: ESCROW_AMOUNT,
+            reference_seed: 1,
+            deadline: NOW + DAY,
+            auto_release_at: 0,
+            milestone_count: 0,
+            has_multisig: false,
+            is_native: false,
+        })]
+    );
+    assert_eq!(
+        env.events(&env.release(&escrow)),
+        [DecodedEvent::PaymentReleased(PaymentReleased {
+            escrow: escrow.address.to_bytes(),
+            released_by: client.wallet.to_bytes(),
+        })]
+    );
+
+    let fee = protocol_fee(ESCROW_AMOUNT, ReputationTier::Unranked as usize);
+    assert_eq!(
+        env.events(&env.claim(&escrow)),
+        [DecodedEvent::PaymentClaimed(PaymentClaimed {
+            escrow: escrow.address.to_bytes(),
+            receiver: freelancer.wallet.to_bytes(),
+            amount: ESCROW_AMOUNT - fee,
+            fee,
+        })]
+    );
+}
+
+#[test]
+fn claim_payment_pays_native_escrows_in_lamports() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs {
+        native: true,
+        ..EscrowArgs::default()
+    });
+    let freelancer_lamports = env.lamports(&escrow.freelancer.wallet);
+    let treasury_lamports = env.lamports(&env.treasury);
+
+    env.ok(&env.release(&escrow));
+    env.ok(&env.claim(&escrow));
+
+    let fee = protocol_fee(ESCROW_AMOUNT, ReputationTier::Unranked as usize);
+    assert_eq!(
+        env.lamports(&escrow.freelancer.wallet),
+        freelancer_lamports + ESCROW_AMOUNT - fee - rent(UserStats::LEN)
+    );
+    assert_eq!(env.lamports(&env.treasury), treasury_lamports + fee);
+    assert!(!env.exists(&escrow.address));
+}
+
+#[test]
+fn user_stats_are_created_on_first_use() {
+    let env = Env::new();
+    let (client, freelancer) = (env.party(), env.party());
+    let (client_stats, client_bump) = find_user_stats_pda(&client.wallet);
+    let (freelancer_stats, _) = find_user_stats_pda(&freelancer.wallet);
+
+    let escrows: Vec<TestEscrow> = (1..=2)
+        .map(|reference_seed| {
+            let args = EscrowArgs {
+                reference_seed,
+                ..EscrowArgs::default()
+            };
+            env.create_escrow_between(&client, &freelancer, args)
+        })
+        .collect();
+
+    // the client paid for theirs with the first escrow
+    let stats = env.state::<UserStats>(&client_stats);
+    assert_eq!(stats.user, client.wallet.to_bytes());
+    assert_eq!(stats.bump, client_bump);
+    assert_eq!(env.lamports(&client_stats), rent(UserStats::LEN));
+    assert!(!env.exists(&freelancer_stats));
+
+    for escrow in &escrows {
+        env.ok(&env.release(escrow));
+        env.ok(&env.claim(escrow));
+    }
+    let stats = env.state::<UserStats>(&freelancer_stats);
+    assert_eq!(stats.user, freelancer.wallet.to_bytes());
+    assert_eq!(stats.completed_escrows, 2);
+}
+
+#[test]
+fn user_stats_must_be_the_partys_pda() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs::default());
+    env.ok(&env.release(&escrow));
+    let freelancer_stats = find_user_stats_pda(&escrow.freelancer.wallet).0;
+
+    env.err(
+        &swap_account(env.claim(&escrow), &freelancer_stats, &Pubkey::new_unique()),
+        BondrError::PdaMismatch,
+    );
+    // the client's stats are a real stats account, just not the freelancer's
+    env.err(
+        &swap_account(
+            env.claim(&escrow),
+            &freelancer_stats,
+            &find_user_stats_pda(&escrow.client.wallet).0,
+        ),
+        BondrError::InvalidOwner,
+    );
+
+    let (_, create) = env.escrow_ix(&env.party(), &env.party(), EscrowArgs::default());
+    let stranger_stats = find_user_stats_pda(&Pubkey::new_unique()).0;
+    let client_stats = create.accounts[3].pubkey;
+    env.err(
+        &swap_account(create, &client_stats, &stranger_stats),
+        BondrError::PdaMismatch,
+    );
+}
+
+#[test]
+fn user_stats_survive_a_prefunded_address() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs::default());
+    env.ok(&env.release(&escrow));
+
+    // lamports sent to the PDA before it exists must not block the claim
+    let stats = find_user_stats_pda(&escrow.freelancer.wallet).0;
+    env.set_account(&stats, Account::new(1, 0, &SYSTEM_PROGRAM));
+
+    env.ok(&env.claim(&escrow));
+    assert_eq!(env.state::<UserStats>(&stats).completed_escrows, 1);
+    assert_eq!(env.lamports(&stats), rent(UserStats::LEN));
+}
+
+#[test]
+fn user_stats_keep_the_clients_record() {
+    let mut env = Env::new();
+    let client = env.party();
+    let escrows: Vec<TestEscrow> = (1..=3)
+        .map(|reference_seed| {
+            let args = EscrowArgs {
+                reference_seed,
+                ..EscrowArgs::default()
+            };
+            env.create_escrow_between(&client, &env.party(), args)
+        })
+        .collect();
+
+    env.warp_to(NOW + DAY);
+    env.ok(&env.release(&escrows[0]));
+    env.warp_to(NOW + 3 * DAY);
+    env.ok(&env.release(&escrows[1]));
+    env.ok(&env.cancel(&escrows[2], true));
+
+    let stats = env.state::<UserStats>(&escrows[0].client_stats());
+    assert_eq!(stats.escrows_funded, 3);
+    assert_eq!(stats.total_funded, 3 * ESCROW_AMOUNT);
+    assert_eq!(stats.escrows_released, 2);
+    assert_eq!(stats.total_release_seconds, (4 * DAY) as u64);
+    assert_eq!(stats.escrows_refunded, 1);
+    assert_eq!(stats.completed_escrows, 0);
+}
+
+#[test]
+fn user_stats_of_another_client_are_rejected() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs::default());
+    let other_stats = env.create_escrow(EscrowArgs::default()).client_stats();
+
+    env.err(
+        &swap_account(env.release(&escrow), &escrow.client_stats(), &other_stats),
+        BondrError::InvalidOwner,
+    );
+    env.err(
+        &swap_account(
+            env.cancel(&escrow, true),
+            &escrow.client_stats(),
+            &other_stats,
+        ),
+        BondrError::InvalidOwner,
+    );
+    // nor can the record be left out
+    env.err(
+        &swap_account(
+            env.release(&escrow),
+            &escrow.client_stats(),
+            &Pubkey::new_unique(),
+        ),
+        BondrError::InvalidOwner,
+    );
+}
+
+#[test]
+fn user_stats_must_be_at_the_canonical_bump() {
+    let env = Env::new();
+    let client = env.party();
+    let (stats, bump) = find_user_stats_pda(&client.wallet);
+    let (shadow, shadow_bump) = (0..bump)
+        .rev()
+        .find_map(|b| {
+            let seeds: &[&[u8]] = &[UserStats::SEED.as_bytes(), client.wallet.as_ref(), &[b]];
+            Pubkey::create_program_address(seeds, &PROGRAM_ID)
+                .ok()
+                .map(|shadow| (shadow, b))
+        })
+        .unwrap();
+
+    // a second record can't be created at another bump
+    let (_, mut create) = env.escrow_ix(&client, &env.party(), EscrowArgs::default());
+    create.data[1 + core::mem::offset_of!(InitializeEscrow, stats_bump)] = shadow_bump;
+    env.err(
+        &swap_account(create, &stats, &shadow),
+        BondrError::PdaMismatch,
+    );
+
+    // nor can one be sent the client's refunds
+    let escrow = env.create_escrow_between(&client, &env.party(), EscrowArgs::default());
+    let mut account = env.account(&stats);
+    account.data[core::mem::offset_of!(UserStats, bump)] = shadow_bump;
+    env.set_account(&shadow, account);
+    env.err(
+        &swap_account(env.cancel(&escrow, true), &stats, &shadow),
+        BondrError::PdaMismatch,
+    );
+}
+
+#[test]
+fn claim_payment_credits_the_badge_and_applies_its_discount() {
+    let env = Env::new();
+    let (client, freelancer) = (env.party(), env.party());
+    let badge = env.create_badge(&freelancer.wallet);
+    env.set_state(
+        &badge,
+        &FreelancerBadge {
+            tier: ReputationTier::Professional,
+            ..env.state::<FreelancerBadge>(&badge)
+        },
+    );
+
+    let escrow = env.create_escrow_between(&client, &freelancer, EscrowArgs::default());
+    env.ok(&env.release(&escrow));
+    env.ok(&env.claim(&escrow));
+
+    let fee = protocol_fee(ESCROW_AMOUNT, ReputationTier::Professional as usize);
+    assert_eq!(env.token_balance(&env.treasury_token), fee);
+
+    let badge = env.state::<FreelancerBadge>(&badge);
+    assert_eq!(badge.completed_escrows, 1);
+    assert_eq!(badge.total_value_completed, ESCROW_AMOUNT);
+}
+
+#[test]
+fn escrows_below_the_minimum_build_no_reputation() {
+    let env = Env::new();
+    let (client, freelancer) = (env.party(), env.party());
+    let badge = env.create_badge(&freelancer.wallet);
+
+    let mut minimums = [0; 8];
+    minimums[1] = ESCROW_AMOUNT + 1;
+    env.ok(&env.update_config_with(&env.settings(), minimums));
+
+    let escrow = env.create_escrow_between(&client, &freelancer, EscrowArgs::default());
+    env.ok(&env.release(&escrow));
+    env.ok(&env.claim(&escrow));
+    assert_eq!(env.state::<FreelancerBadge>(&badge).completed_escrows, 0);
+
+    minimums[1] = ESCROW_AMOUNT;
+    env.ok(&env.update_config_with(&env.settings(), minimums));
+
+    let escrow = env.create_escrow_between(&client, &freelancer, EscrowArgs::default());
+    env.ok(&env.release(&escrow));
+    env.ok(&env.claim(&escrow));
+    assert_eq!(env.state::<FreelancerBadge>(&badge).completed_escrows, 1);
+}
+
+#[test]
+fn claim_payment_after_auto_release() {
+    let mut env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs {
+        auto_release_at: NOW + DAY,
+        ..EscrowArgs::default()
+    });
+
+    env.err(&env.claim(&escrow), BondrError::NotReleased);
+
+    env.warp_to(NOW + DAY);
+    env.ok(&env.claim(&escrow));
+    assert!(!env.exists(&escrow.address));
+}
+
+#[test]
+fn claim_payment_checks_the_parties() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs::default());
+    let stranger = env.party();
+    env.ok(&env.release(&escrow));
+
+    let claim = env.claim(&escrow);
+    env.err(
+        &swap_account(claim.clone(), &escrow.client.wallet, &stranger.wallet),
+        BondrError::UnauthorizedSender,
+    );
+    env.err(
+        &swap_account(claim.clone(), &escrow.freelancer.wallet, &stranger.wallet),
+        BondrError::UnauthorizedReceiver,
+    );
+    env.err_with(
+        &unsigned(claim, &escrow.freelancer.wallet),
+        ProgramError::MissingRequiredSignature,
+    );
+}
+
+#[test]
+fn claim_payment_checks_the_vault_and_treasury() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs::default());
+    env.ok(&env.release(&escrow));
+
+    let other_mint = Pubkey::new_unique();
+    env.set_account(&other_mint, mint_account());
+    env.err(
+        &swap_account(env.claim(&escrow), &env.mint, &other_mint),
+        BondrError::MissingTokenAccounts,
+    );
+
+    let foreign_treasury = env.create_token_account(&Pubkey::new_unique(), 0);
+    env.err(
+        &swap_account(env.claim(&escrow), &env.treasury_token, &foreign_treasury),
+        BondrError::InvalidTreasury,
+    );
+}
+
+#[test]
+fn claim_payment_rejects_unreleased_milestone_and_disputed_escrows() {
+    let env = Env::new();
+
+    let escrow = env.create_escrow(EscrowArgs::default());
+    env.err(&env.claim(&escrow), BondrError::NotReleased);
+
+    let escrow = env.create_escrow(EscrowArgs {
+        milestones: vec![ESCROW_AMOUNT],
+        ..EscrowArgs::default()
+    });
+    env.err(&env.claim(&escrow), BondrError::MilestoneEscrowNotSupported);
+
+    let escrow = env.create_escrow(EscrowArgs {
+        arbiter: Pubkey::new_unique(),
+        auto_release_at: NOW + DAY,
+        ..EscrowArgs::default()
+    });
+    env.ok(&env.open_dispute(&escrow, &escrow.client.wallet));
+    env.err(&env.claim(&escrow), BondrError::EscrowDisputed);
+}
+
+#[test]
+fn cancel_escrow_with_the_freelancer_refunds_the_client() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs::default());
+
+    env.ok(&env.cancel(&escrow, true));
+
+    assert_eq!(env.token_balance(&escrow.client.token), TOKEN_BALANCE);
+    assert!(!env.exists(&escrow.address));
+    assert!(!env.exists(&escrow.vault));
+}
+
+#[test]
+fn cancel_escrow_alone_after_the_deadline() {
+    let mut env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs {
+        deadline: NOW + DAY,
+        native: true,
+        ..EscrowArgs::default()
+    });
+    let client_lamports = env.lamports(&escrow.client.wallet);
+
+    env.err(&env.cancel(&escrow, false), BondrError::EscrowNotExpired);
+
+    env.warp_to(NOW + DAY);
+    env.ok(&env.cancel(&escrow, false));
+    assert_eq!(
+        env.lamports(&escrow.client.wallet),
+        client_lamports + ESCROW_AMOUNT + rent(Escrow::LEN)
+    );
+}
+
+#[test]
+fn cancel_escrow_without_a_deadline_needs_the_freelancer() {
+    let mut env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs::default());
+
+    env.warp_to(NOW + 365 * DAY);
+    env.err(&env.cancel(&escrow, false), BondrError::EscrowNotExpired);
+}
+
+#[test]
+fn cancel_escrow_checks_the_parties() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs::default());
+    let stranger = Pubkey::new_unique();
+
+    let cancel = env.cancel(&escrow, true);
+    env.err(
+        &swap_account(cancel.clone(), &escrow.client.wallet, &stranger),
+        BondrError::UnauthorizedSender,
+    );
+    env.err(
+        &swap_account(cancel, &escrow.freelancer.wallet, &stranger),
+        BondrError::UnauthorizedReceiver,
+    );
+}
+
+#[test]
+fn cancel_escrow_rejects_released_and_disputed_escrows() {
+    let env = Env::new();
+
+    let escrow = env.create_escrow(EscrowArgs::default());
+    env.ok(&env.release(&escrow));
+    env.err(&env.cancel(&escrow, true), BondrError::AlreadyReleased);
+
+    let escrow = env.create_escrow(EscrowArgs {
+        arbiter: Pubkey::new_unique(),
+        ..EscrowArgs::default()
+    });
+    env.ok(&env.open_dispute(&escrow, &escrow.freelancer.wallet));
+    env.err(&env.cancel(&escrow, true), BondrError::EscrowDisputed);
+}
@@ -0,0 +1,52 @@
+//! The packed layout events are logged in.
+
+use bondr_pinocchio::events::{
+    DisputeResolved, EscrowCreated, Event, EventKind, MilestoneClaimed, PaymentClaimed,
+    EVENT_VERSION,
+};
+use core::mem::{align_of, offset_of, size_of};
+
+#[test]
+fn events_have_no_padding() {
+    // fields follow each other in declaration order, whatever their alignment
+    assert_eq!(align_of::<EscrowCreated>(), 1);
+    assert_eq!(size_of::<EscrowCreated>(), 4 * 32 + 4 * 8 + 3);
+    assert_eq!(offset_of!(EscrowCreated, amount), 128);
+    assert_eq!(offset_of!(EscrowCreated, milestone_count), 160);
+    assert_eq!(offset_of!(EscrowCreated, is_native), 162);
+
+    assert_eq!(size_of::<PaymentClaimed>(), 2 * 32 + 2 * 8);
+    assert_eq!(offset_of!(PaymentClaimed, fee), 72);
+
+    assert_eq!(size_of::<MilestoneClaimed>(), 2 * 32 + 2 * 8 + 2);
+    assert_eq!(offset_of!(MilestoneClaimed, index), 80);
+
+    assert_eq!(size_of::<DisputeResolved>(), 2 * 32 + 2 * 8 + 2);
+    assert_eq!(offset_of!(DisputeResolved, receiver_bps), 80);
+}
+
+#[test]
+fn events_are_logged_after_their_version_and_kind() {
+    let event = PaymentClaimed {
+        escrow: [1; 32],
+        receiver: [2; 32],
+        amount: 0x0102_0304_0506_0708,
+        fee: 9,
+    };
+    // what `Event::emit` passes to `sol_log_data` after the header
+    let bytes = unsafe {
+        core::slice::from_raw_parts(
+            &event as *const PaymentClaimed as *const u8,
+            size_of::<PaymentClaimed>(),
+        )
+    };
+
+    assert_eq!(EVENT_VERSION, 1);
+    assert_eq!(PaymentClaimed::KIND, EventKind::PaymentClaimed);
+    assert_eq!(PaymentClaimed::KIND as u8, 3);
+
+    let mut expected = [[1; 32], [2; 32]].concat();
+    expected.extend_from_slice(&0x0102_0304_0506_0708u64.to_le_bytes());
+    expected.extend_from_slice(&9u64.to_le_bytes());
+    assert_eq!(bytes, expected);
+}
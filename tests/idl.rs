@@ -0,0 +1,85 @@
+use bondr_pinocchio::instructions::BondrInstruction;
+
+const IDL_SOURCE: &str = include_str!("../src/instructions/idl.rs");
+const CLIENT_ACCOUNTS: &str = include_str!("../src/client/accounts.rs");
+
+/// `(name, writable, signer)`
+type Account = (String, bool, bool);
+
+/// Each variant of the IDL enum with its accounts.
+fn idl_variants() -> Vec<(String, Vec<Account>)> {
+    let body = &IDL_SOURCE[IDL_SOURCE.find("pub enum").unwrap()..];
+    let mut variants = Vec::new();
+    let mut accounts = Vec::new();
+
+    for line in body.lines().skip(1).map(str::trim) {
+        if let Some(attr) = line.strip_prefix("#[account(") {
+            let name = attr.split("name = \"").nth(1).unwrap();
+            let name = &name[..name.find('"').unwrap()];
+            let flags: Vec<&str> = attr.split(", ").collect();
+            accounts.push((
+                name.to_string(),
+                flags.contains(&"writable"),
+                flags.contains(&"signer"),
+            ));
+        } else if !line.is_empty() && line != "}" {
+            let end = line.find(['(', ',']).unwrap();
+            variants.push((line[..end].to_string(), std::mem::take(&mut accounts)));
+        }
+    }
+    variants
+}
+
+/// The same shape, read from the client's `instruction_accounts!` invocation.
+fn client_accounts(variant: &str) -> Vec<Account> {
+    let start = CLIENT_ACCOUNTS
+        .find(&format!(" {variant}Accounts {{"))
+        .unwrap_or_else(|| panic!("no client accounts for {variant}"));
+    let body = &CLIENT_ACCOUNTS[start..];
+    let body = &body[body.find('{').unwrap() + 1..body.find('}').unwrap()];
+
+    body.lines()
+        .filter_map(|line| line.split("//").next()?.trim().strip_suffix(','))
+        .map(|field| {
+            let (name, kind) = field.split_once(": ").unwrap();
+            (
+                name.to_string(),
+                kind.starts_with("writable"),
+                kind.ends_with("signer"),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn idl_variants_follow_instruction_discriminants() {
+    let variants = idl_variants();
+    assert!(BondrInstruction::try_from(&(variants.len() as u8)).is_err());
+
+    for (discriminant, (variant, _)) in variants.iter().enumerate() {
+        let instruction = BondrInstruction::try_from(&(discriminant as u8)).unwrap();
+        assert_eq!(&format!("{instruction:?}"), variant);
+    }
+}
+
+#[test]
+fn idl_accounts_match_client_builders() {
+    for (variant, accounts) in idl_variants() {
+        let mut expected = client_accounts(&variant);
+        // the receiver optionally signs; the IDL marks it `optional_signer`
+        if variant == "CancelEscrow" {
+            expected[1].2 = false;
+        }
+        assert_eq!(accounts, expected, "{variant}");
+    }
+}
+
+#[test]
+fn gated_instructions_take_the_config_where_the_entrypoint_checks_it() {
+    for (discriminant, (variant, accounts)) in idl_variants().iter().enumerate() {
+        let instruction = BondrInstruction::try_from(&(discriminant as u8)).unwrap();
+        if let Some(index) = instruction.config_index() {
+            assert_eq!(accounts[index].0, "config", "{variant}");
+        }
+    }
+}
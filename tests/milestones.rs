@@ -0,0 +1,224 @@
+//! Milestone escrows: released and claimed one milestone at a time.
+
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use bondr_pinocchio::{
+    client::find_user_stats_pda,
+    errors::BondrError,
+    states::{Escrow, FreelancerBadge, MilestoneState, ReputationTier, UserStats},
+};
+use common::*;
+use solana_sdk::{program_error::ProgramError, pubkey::Pubkey};
+
+const MILESTONES: [u64; 2] = [3_000_000, 7_000_000];
+
+fn milestone_args() -> EscrowArgs {
+    EscrowArgs {
+        amount: MILESTONES.iter().sum(),
+        milestones: MILESTONES.to_vec(),
+        ..EscrowArgs::default()
+    }
+}
+
+fn milestone_state(env: &Env, escrow: &TestEscrow, index: usize) -> MilestoneState {
+    env.state::<Escrow>(&escrow.address).milestones[index].state
+}
+
+#[test]
+fn milestones_are_released_and_claimed_in_turn() {
+    let env = Env::new();
+    let (client, freelancer) = (env.party(), env.party());
+    let badge = env.create_badge(&freelancer.wallet);
+    let escrow = env.create_escrow_between(&client, &freelancer, milestone_args());
+    let unranked = ReputationTier::Unranked as usize;
+
+    env.ok(&env.release_milestone(&escrow, 0));
+    assert_eq!(milestone_state(&env, &escrow, 0), MilestoneState::Released);
+    assert_eq!(milestone_state(&env, &escrow, 1), MilestoneState::Pending);
+
+    env.ok(&env.claim_milestone(&escrow, 0));
+    assert_eq!(milestone_state(&env, &escrow, 0), MilestoneState::Claimed);
+    assert_eq!(
+        env.token_balance(&freelancer.token),
+        TOKEN_BALANCE + MILESTONES[0] - protocol_fee(MILESTONES[0], unranked)
+    );
+    assert_eq!(env.token_balance(&escrow.vault), MILESTONES[1]);
+
+    // the client's record counts the escrow once every milestone is out
+    let released = || {
+        env.state::<UserStats>(&escrow.client_stats())
+            .escrows_released
+    };
+    assert_eq!(released(), 0);
+    env.ok(&env.release_milestone(&escrow, 1));
+    assert_eq!(released(), 1);
+
+    // the last claim settles the escrow and credits the badge once
+    env.ok(&env.claim_milestone(&escrow, 1));
+
+    let fees = protocol_fee(MILESTONES[0], unranked) + protocol_fee(MILESTONES[1], unranked);
+    assert_eq!(
+        env.token_balance(&freelancer.token),
+        TOKEN_BALANCE + escrow.args.amount - fees
+    );
+    assert_eq!(env.token_balance(&env.treasury_token), fees);
+    assert!(!env.exists(&escrow.address));
+    assert!(!env.exists(&escrow.vault));
+
+    let badge = env.state::<FreelancerBadge>(&badge);
+    assert_eq!(badge.completed_escrows, 1);
+    assert_eq!(badge.total_value_completed, escrow.args.amount);
+    // and the freelancer's stats, as a single-payment claim would
+    let stats = env.state::<UserStats>(&find_user_stats_pda(&freelancer.wallet).0);
+    assert_eq!(stats.completed_escrows, 1);
+}
+
+#[test]
+fn native_milestones_are_paid_in_lamports() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs {
+        native: true,
+        ..milestone_args()
+    });
+    let freelancer_lamports = env.lamports(&escrow.freelancer.wallet);
+
+    env.ok(&env.release_milestone(&escrow, 1));
+    env.ok(&env.claim_milestone(&escrow, 1));
+
+    let fee = protocol_fee(MILESTONES[1], ReputationTier::Unranked as usize);
+    assert_eq!(
+        env.lamports(&escrow.freelancer.wallet),
+        freelancer_lamports + MILESTONES[1] - fee
+    );
+    assert!(env.exists(&escrow.address));
+}
+
+#[test]
+fn release_milestone_checks_the_escrow() {
+    let env = Env::new();
+    let escrow = env.create_escrow(milestone_args());
+
+    env.err(
+        &swap_account(
+            env.release_milestone(&escrow, 0),
+            &escrow.client.wallet,
+            &escrow.freelancer.wallet,
+        ),
+        BondrError::UnauthorizedSender,
+    );
+    env.err(
+        &env.release_milestone(&escrow, MILESTONES.len() as u8),
+        BondrError::InvalidMilestone,
+    );
+
+    env.ok(&env.release_milestone(&escrow, 0));
+    env.err(
+        &env.release_milestone(&escrow, 0),
+        BondrError::AlreadyReleased,
+    );
+
+    let single = env.create_escrow(EscrowArgs::default());
+    env.err(
+        &env.release_milestone(&single, 0),
+        BondrError::NotMilestoneEscrow,
+    );
+}
+
+#[test]
+fn claim_milestone_needs_a_released_milestone() {
+    let env = Env::new();
+    let escrow = env.create_escrow(milestone_args());
+
+    env.err(&env.claim_milestone(&escrow, 0), BondrError::NotReleased);
+    env.err(
+        &env.claim_milestone(&escrow, MILESTONES.len() as u8),
+        BondrError::InvalidMilestone,
+    );
+
+    env.ok(&env.release_milestone(&escrow, 0));
+    env.ok(&env.claim_milestone(&escrow, 0));
+    env.err(
+        &env.claim_milestone(&escrow, 0),
+        BondrError::AlreadyReleased,
+    );
+
+    let single = env.create_escrow(EscrowArgs::default());
+    env.err(
+        &env.claim_milestone(&single, 0),
+        BondrError::NotMilestoneEscrow,
+    );
+}
+
+#[test]
+fn claim_milestone_checks_the_parties() {
+    let env = Env::new();
+    let escrow = env.create_escrow(milestone_args());
+    let stranger = env.party();
+    env.ok(&env.release_milestone(&escrow, 0));
+
+    let claim = env.claim_milestone(&escrow, 0);
+    env.err(
+        &swap_account(claim.clone(), &escrow.client.wallet, &stranger.wallet),
+        BondrError::UnauthorizedSender,
+    );
+    env.err(
+        &swap_account(claim.clone(), &escrow.freelancer.wallet, &stranger.wallet),
+        BondrError::UnauthorizedReceiver,
+    );
+    env.err_with(
+        &unsigned(claim, &escrow.freelancer.wallet),
+        ProgramError::MissingRequiredSignature,
+    );
+}
+
+#[test]
+fn auto_release_makes_every_milestone_claimable() {
+    let mut env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs {
+        auto_release_at: NOW + DAY,
+        ..milestone_args()
+    });
+
+    env.warp_to(NOW + DAY);
+    env.ok(&env.claim_milestone(&escrow, 1));
+    env.ok(&env.claim_milestone(&escrow, 0));
+    assert!(!env.exists(&escrow.address));
+}
+
+#[test]
+fn disputed_milestones_are_frozen() {
+    let env = Env::new();
+    let escrow = env.create_escrow(EscrowArgs {
+        arbiter: Pubkey::new_unique(),
+        ..milestone_args()
+    });
+    env.ok(&env.release_milestone(&escrow, 0));
+    env.ok(&env.open_dispute(&escrow, &escrow.client.wallet));
+
+    env.err(
+        &env.release_milestone(&escrow, 1),
+        BondrError::EscrowDisputed,
+    );
+    env.err(&env.claim_milestone(&escrow, 0), BondrError::EscrowDisputed);
+}
+
+#[test]
+fn cancel_refunds_only_unclaimed_milestones() {
+    let env = Env::new();
+    let escrow = env.create_escrow(milestone_args());
+
+    // a released milestone belongs to the freelancer until it is claimed
+    env.ok(&env.release_milestone(&escrow, 0));
+    env.err(&env.cancel(&escrow, true), BondrError::AlreadyReleased);
+
+    env.ok(&env.claim_milestone(&escrow, 0));
+    env.ok(&env.cancel(&escrow, true));
+
+    assert_eq!(
+        env.token_balance(&escrow.client.token),
+        TOKEN_BALANCE - MILESTONES[0]
+    );
+    assert!(!env.exists(&escrow.address));
+}
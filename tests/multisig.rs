@@ -0,0 +1,516 @@
+//! Client multisigs and the escrows released by their members' approvals.
+
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use bondr_pinocchio::{
+    constants::MAX_MULTISIG_MEMBERS,
+    errors::BondrError,
+    states::{ClientMultisig, ConfigSettings, Escrow, MultisigApproval, UserStats},
+};
+use common::*;
+use solana_sdk::{program_error::ProgramError, pubkey::Pubkey};
+
+/// A client whose multisig has two more members and needs two approvals.
+struct Team {
+    client: Party,
+    members: [Pubkey; 3],
+    multisig: Pubkey,
+}
+
+fn new_team(env: &Env) -> Team {
+    let client = env.party();
+    let members = [client.wallet, Pubkey::new_unique(), Pubkey::new_unique()];
+    let multisig = env.create_multisig(&client.wallet, &members, 2);
+
+    Team {
+        client,
+        members,
+        multisig,
+    }
+}
+
+fn multisig_escrow(env: &Env, team: &Team) -> TestEscrow {
+    env.create_escrow_between(
+        &team.client,
+        &env.party(),
+        EscrowArgs {
+            multisig: Some(team.multisig),
+            ..EscrowArgs::default()
+        },
+    )
+}
+
+#[test]
+fn initialize_multisig_client_stores_the_members() {
+    let env = Env::new();
+    let team = new_team(&env);
+
+    let state = env.state::<ClientMultisig>(&team.multisig);
+    assert_eq!(state.member_count, 3);
+    assert_eq!(state.threshold, 2);
+    assert_eq!(state.client, team.client.wallet.to_bytes());
+    for (stored, member) in state.members.iter().zip(team.members) {
+        assert_eq!(*stored, member.to_bytes());
+    }
+}
+
+#[test]
+fn initialize_multisig_client_validates_the_members() {
+    let env = Env::new();
+    let client = env.party().wallet;
+    let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    let cases: [(&[Pubkey], u8, BondrError); 5] = [
+        (&[], 0, BondrError::InvalidMultisigConfig),
+        (&[client, a], 0, BondrError::InvalidMultisigConfig),
+        (&[client, a], 3, BondrError::InvalidMultisigConfig),
+        (&[a, b], 1, BondrError::InvalidMultisigConfig), // without the client
+        (&[client, a, a], 2, BondrError::DuplicateMember),
+    ];
+    for (members, threshold, error) in cases {
+        env.err(&env.multisig_ix(&client, members, threshold), error);
+    }
+
+    env.err_with(
+        &unsigned(env.multisig_ix(&client, &[client, a], 1), &client),
+        ProgramError::MissingRequiredSignature,
+    );
+}
+
+#[test]
+fn initialize_multisig_client_respects_the_configured_size() {
+    let env = Env::new();
+    let client = env.party().wallet;
+    let members: Vec<Pubkey> = std::iter::once(client)
+        .chain((1..MAX_MULTISIG_MEMBERS).map(|_| Pubkey::new_unique()))
+        .collect();
+
+    env.ok(&env.update_config(&ConfigSettings {
+        max_multisig_members: 2,
+        ..env.settings()
+    }));
+    env.err(
+        &env.multisig_ix(&client, &members[..3], 2),
+        BondrError::InvalidMultisigConfig,
+    );
+    env.ok(&env.multisig_ix(&client, &members[..2], 2));
+
+    // one multisig per client
+    env.err_with(
+        &env.multisig_ix(&client, &members[..2], 2),
+        ProgramError::AccountAlreadyInitialized,
+    );
+}
+
+#[test]
+fn approvals_release_a_multisig_escrow() {
+    let env = Env::new();
+    let team = new_team(&env);
+    let escrow = multisig_escrow(&env, &team);
+
+    assert!(env.state::<Escrow>(&escrow.address).has_multisig);
+    let (approval, bump) = escrow.approval();
+    let record = env.state::<MultisigApproval>(&approval);
+    assert_eq!(record.multisig, team.multisig.to_bytes());
+    assert_eq!(record.escrow, escrow.address.to_bytes());
+    assert_eq!((record.approver_count, record.bump), (0, bump));
+
+    // the client alone can't release it
+    env.err(&env.release(&escrow), BondrError::MultisigThresholdNotMet);
+
+    env.ok(&env.approve(&escrow, &team.members[1]));
+    assert!(!env.state::<Escrow>(&escrow.address).is_released);
+    let record = env.state::<MultisigApproval>(&approval);
+    assert_eq!(record.approver_count, 1);
+    assert_eq!(record.approvers[0], team.members[1].to_bytes());
+    env.err(&env.claim(&escrow), BondrError::NotReleased);
+    env.err(
+        &env.approve(&escrow, &team.members[1]),
+        BondrError::AlreadyApproved,
+    );
+
+    env.ok(&env.approve(&escrow, &team.members[2]));
+    assert!(env.state::<Escrow>(&escrow.address).is_released);
+    // the release counts for the client that funded it
+    let stats = env.state::<UserStats>(&escrow.client_stats());
+    assert_eq!(stats.escrows_released, 1);
+    // and late approvals don't count it again
+    env.err(
+        &env.approve(&escrow, &team.members[0]),
+        BondrError::AlreadyReleased,
+    );
+
+    // claiming closes the record, its rent going back to the client
+    let client_lamports = env.lamports(&team.client.wallet);
+    let record_lamports = env.lamports(&approval);
+    env.ok(&env.claim(&escrow));
+    assert!(!env.exists(&approval));
+    assert!(env.lamports(&team.client.wallet) >= client_lamports + record_lamports);
+}
+
+#[test]
+fn multisig_escrows_are_approved_independently() {
+    let env = Env::new();
+    let team = new_team(&env);
+    let first = multisig_escrow(&env, &team);
+    let second = multisig_escrow(&env, &team);
+    assert_ne!(first.approval().0, second.approval().0);
+
+    env.ok(&env.approve(&first, &team.members[1]));
+    env.ok(&env.approve(&second, &team.members[2]));
+    env.ok(&env.approve(&second, &team.members[0]));
+    assert!(!env.state::<Escrow>(&first.address).is_released);
+    assert!(env.state::<Escrow>(&second.address).is_released);
+
+    env.ok(&env.claim(&second));
+    env.ok(&env.approve(&first, &team.members[2]));
+    assert!(env.state::<Escrow>(&first.address).is_released);
+    env.ok(&env.claim(&first));
+}
+
+#[test]
+fn multisig_escrows_are_opened_by_members_only() {
+    let env = Env::new();
+    let team = new_team(&env);
+
+    let (_, instruction) = env.escrow_ix(
+        &env.party(),
+        &env.party(),
+        EscrowArgs {
+            multisig: Some(team.multisig),
+            ..EscrowArgs::default()
+        },
+    );
+    env.err(&instruction, BondrError::NotMultisigMember);
+
+    let (_, instruction) = env.escrow_ix(
+        &team.client,
+        &env.party(),
+        EscrowArgs {
+            multisig: Some(team.multisig),
+            milestones: vec![ESCROW_AMOUNT],
+            ..EscrowArgs::default()
+        },
+    );
+    env.err(&instruction, BondrError::MilestoneEscrowNotSupported);
+}
+
+#[test]
+fn approve_multisig_release_checks_the_member_and_escrow() {
+    let env = Env::new();
+    let team = new_team(&env);
+    let escrow = multisig_escrow(&env, &team);
+
+    env.err(
+        &env.approve(&escrow, &Pubkey::new_unique()),
+        BondrError::NotMultisigMember,
+    );
+    env.err_with(
+        &unsigned(env.approve(&escrow, &team.members[1]), &team.members[1]),
+        ProgramError::MissingRequiredSignature,
+    );
+
+    // an escrow created without the multisig
+    let other = env.create_escrow_between(&team.client, &env.party(), EscrowArgs::default());
+    let approve = swap_account(
+        env.approve(&escrow, &team.members[1]),
+        &escrow.address,
+        &other.address,
+    );
+    env.err(&approve, BondrError::MultisigPendingEscrowMismatch);
+
+    // another escrow's approvals
+    let other = multisig_escrow(&env, &team);
+    let approve = swap_account(
+        env.approve(&escrow, &team.members[1]),
+        &escrow.approval().0,
+        &other.approval().0,
+    );
+    env.err(&approve, BondrError::PdaMismatch);
+}
+
+#[test]
+fn approve_multisig_release_rejects_disputed_escrows() {
+    let env = Env::new();
+    let team = new_team(&env);
+    let escrow = env.create_escrow_between(
+        &team.client,
+        &env.party(),
+        EscrowArgs {
+            multisig: Some(team.multisig),
+            arbiter: Pubkey::new_unique(),
+            ..EscrowArgs::default()
+        },
+    );
+    env.ok(&env.open_dispute(&escrow, &escrow.freelancer.wallet));
+
+    env.err(
+        &env.approve(&escrow, &team.members[1]),
+        BondrError::EscrowDisputed,
+    );
+}
+
+#[test]
+fn claim_payment_needs_the_escrows_own_multisig() {
+    let env = Env::new();
+    let team = new_team(&env);
+    let escrow = multisig_escrow(&env, &team);
+    env.ok(&env.approve(&escrow, &team.members[1]));
+    env.ok(&env.approve(&escrow, &team.members[2]));
+
+    let other_team = new_team(&env);
+    env.err(
+        &swap_account(env.claim(&escrow), &team.multisig, &other_team.multisig),
+        BondrError::MultisigPendingEscrowMismatch,
+    );
+}
+
+#[test]
+fn auto_release_skips_the_approvals() {
+    let mut env = Env::new();
+    let team = new_team(&env);
+    let escrow = env.create_escrow_between(
+        &team.client,
+        &env.party(),
+        EscrowArgs {
+            multisig: Some(team.multisig),
+            auto_release_at: NOW + DAY,
+            ..EscrowArgs::default()
+        },
+    );
+
+    env.warp_to(NOW + DAY);
+    env.ok(&env.claim(&escrow));
+    assert!(!env.exists(&escrow.approval().0));
+}
+
+#[test]
+fn members_change_with_the_thresholds_approval() {
+    let env = Env::new();
+    let team = new_team(&env);
+    let [client, first, second] = team.members;
+    let (third, fourth) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    env.ok(&env.add_member(&team.multisig, &third, &[client, first]));
+    env.ok(&env.rotate_member(&team.multisig, &second, &fourth, &[first, third]));
+    env.ok(&env.remove_member(&team.multisig, &first, &[client, fourth]));
+    env.ok(&env.change_threshold(&team.multisig, 3, &[client, third]));
+
+    let state = env.state::<ClientMultisig>(&team.multisig);
+    assert_eq!(
+        state.members[..state.member_count as usize],
+        [client.to_bytes(), fourth.to_bytes(), third.to_bytes()]
+    );
+    assert_eq!(state.members[3], [0; 32]);
+    assert_eq!(state.threshold, 3);
+
+    // approvals follow the new membership
+    let escrow = multisig_escrow(&env, &team);
+    env.err(
+        &env.approve(&escrow, &second),
+        BondrError::NotMultisigMember,
+    );
+    env.ok(&env.approve(&escrow, &fourth));
+    env.ok(&env.approve(&escrow, &third));
+    assert!(!env.state::<Escrow>(&escrow.address).is_released);
+    env.ok(&env.approve(&escrow, &client));
+    assert!(env.state::<Escrow>(&escrow.address).is_released);
+}
+
+#[test]
+fn membership_changes_need_the_threshold() {
+    let env = Env::new();
+    let team = new_team(&env);
+    let [client, first, _] = team.members;
+    let member = Pubkey::new_unique();
+
+    env.err(
+        &env.add_member(&team.multisig, &member, &[client]),
+        BondrError::MultisigThresholdNotMet,
+    );
+    env.err(
+        &env.add_member(&team.multisig, &member, &[client, client]),
+        BondrError::AlreadyApproved,
+    );
+    env.err(
+        &env.add_member(&team.multisig, &member, &[client, member]),
+        BondrError::NotMultisigMember,
+    );
+    env.err_with(
+        &unsigned(
+            env.add_member(&team.multisig, &member, &[client, first]),
+            &first,
+        ),
+        ProgramError::MissingRequiredSignature,
+    );
+    env.err(
+        &env.change_threshold(&team.multisig, 1, &[first]),
+        BondrError::MultisigThresholdNotMet,
+    );
+}
+
+#[test]
+fn membership_changes_keep_the_invariants() {
+    let env = Env::new();
+    let team = new_team(&env);
+    let [client, first, second] = team.members;
+    let approvers = [client, first];
+
+    env.err(
+        &env.add_member(&team.multisig, &second, &approvers),
+        BondrError::DuplicateMember,
+    );
+    env.err(
+        &env.rotate_member(&team.multisig, &second, &first, &approvers),
+        BondrError::DuplicateMember,
+    );
+    env.err(
+        &env.rotate_member(
+            &team.multisig,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &approvers,
+        ),
+        BondrError::NotMultisigMember,
+    );
+    env.err(
+        &env.remove_member(&team.multisig, &Pubkey::new_unique(), &approvers),
+        BondrError::NotMultisigMember,
+    );
+    for threshold in [0, 4] {
+        env.err(
+            &env.change_threshold(&team.multisig, threshold, &approvers),
+            BondrError::InvalidMultisigConfig,
+        );
+    }
+
+    // two approvals need two members
+    env.ok(&env.remove_member(&team.multisig, &second, &approvers));
+    env.err(
+        &env.remove_member(&team.multisig, &first, &approvers),
+        BondrError::InvalidMultisigConfig,
+    );
+
+    // up to the config's cap
+    for _ in 2..MAX_MULTISIG_MEMBERS {
+        env.ok(&env.add_member(&team.multisig, &Pubkey::new_unique(), &approvers));
+    }
+    env.err(
+        &env.add_member(&team.multisig, &Pubkey::new_unique(), &approvers),
+        BondrError::InvalidMultisigConfig,
+    );
+}
+
+#[test]
+fn membership_is_fixed_while_escrows_are_open() {
+    let env = Env::new();
+    let team = new_team(&env);
+    let [client, first, second] = team.members;
+    let open_approvals = || env.state::<ClientMultisig>(&team.multisig).open_approvals;
+
+    let claimed = multisig_escrow(&env, &team);
+    let cancelled = multisig_escrow(&env, &team);
+    let disputed = env.create_escrow_between(
+        &team.client,
+        &env.party(),
+        EscrowArgs {
+            multisig: Some(team.multisig),
+            arbiter: Pubkey::new_unique(),
+            ..EscrowArgs::default()
+        },
+    );
+    assert_eq!(open_approvals(), 3);
+
+    let approvers = [client, first];
+    let changes = || {
+        [
+            env.add_member(&team.multisig, &Pubkey::new_unique(), &approvers),
+            env.remove_member(&team.multisig, &second, &approvers),
+            env.rotate_member(&team.multisig, &second, &Pubkey::new_unique(), &approvers),
+            env.change_threshold(&team.multisig, 3, &approvers),
+        ]
+    };
+    for change in changes() {
+        env.err(&change, BondrError::MultisigBusy);
+    }
+
+    // closing an escrow can't lower another multisig's count
+    let other = new_team(&env);
+    env.err(
+        &swap_account(
+            env.cancel(&cancelled, true),
+            &team.multisig,
+            &other.multisig,
+        ),
+        BondrError::MultisigPendingEscrowMismatch,
+    );
+
+    // every way out gives its escrow back
+    env.ok(&env.approve(&claimed, &first));
+    env.ok(&env.approve(&claimed, &second));
+    env.ok(&env.claim(&claimed));
+    assert_eq!(open_approvals(), 2);
+    env.ok(&env.cancel(&cancelled, true));
+    assert_eq!(open_approvals(), 1);
+    env.ok(&env.open_dispute(&disputed, &disputed.client.wallet));
+    env.err(
+        &env.change_threshold(&team.multisig, 3, &approvers),
+        BondrError::MultisigBusy,
+    );
+    env.ok(&env.resolve_dispute(&disputed, 5_000));
+    assert_eq!(open_approvals(), 0);
+
+    env.ok(&env.change_threshold(&team.multisig, 3, &approvers));
+    assert_eq!(env.state::<ClientMultisig>(&team.multisig).threshold, 3);
+}
+
+#[test]
+fn every_way_out_closes_the_approval_record() {
+    let env = Env::new();
+    let team = new_team(&env);
+
+    let escrow = multisig_escrow(&env, &team);
+    env.ok(&env.approve(&escrow, &team.members[1]));
+    env.ok(&env.cancel(&escrow, true));
+    assert!(!env.exists(&escrow.approval().0));
+
+    let escrow = env.create_escrow_between(
+        &team.client,
+        &env.party(),
+        EscrowArgs {
+            multisig: Some(team.multisig),
+            arbiter: Pubkey::new_unique(),
+            ..EscrowArgs::default()
+        },
+    );
+    env.ok(&env.open_dispute(&escrow, &escrow.client.wallet));
+    env.ok(&env.resolve_dispute(&escrow, 5_000));
+    assert!(!env.exists(&escrow.approval().0));
+}
+
+#[test]
+fn the_approval_record_cannot_be_left_open() {
+    let env = Env::new();
+    let team = new_team(&env);
+    let escrow = multisig_escrow(&env, &team);
+    let other = multisig_escrow(&env, &team);
+    let approval = escrow.approval().0;
+
+    for stand_in in [other.approval().0, Pubkey::new_unique()] {
+        env.err(
+            &swap_account(env.cancel(&escrow, true), &approval, &stand_in),
+            BondrError::PdaMismatch,
+        );
+    }
+
+    env.ok(&env.approve(&escrow, &team.members[1]));
+    env.ok(&env.approve(&escrow, &team.members[2]));
+    env.err(
+        &swap_account(env.claim(&escrow), &approval, &Pubkey::new_unique()),
+        BondrError::PdaMismatch,
+    );
+    env.ok(&env.claim(&escrow));
+    assert!(!env.exists(&approval));
+}
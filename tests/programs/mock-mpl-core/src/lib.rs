@@ -0,0 +1,58 @@
+//! Stand-in for mpl-core in the Mollusk suite. It accepts `CreateV2`, checks the
+//! signers Bondr is expected to provide and creates the asset account holding the
+//! instruction's borsh payload, so tests can read back the minted name and uri.
+
+#![no_std]
+#![allow(unexpected_cfgs)]
+
+use pinocchio::{
+    account_info::AccountInfo,
+    default_panic_handler, no_allocator, program_entrypoint,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+
+program_entrypoint!(process_instruction);
+no_allocator!();
+default_panic_handler!();
+
+const CREATE_V2_DISCRIMINATOR: u8 = 20;
+
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let [asset, _collection, authority, payer, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let (&discriminator, payload) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    if discriminator != CREATE_V2_DISCRIMINATOR {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // the collection's update authority has to sign for an asset minted into it
+    if !asset.is_signer() || !authority.is_signer() || !payer.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    CreateAccount {
+        from: payer,
+        to: asset,
+        lamports: Rent::get()?.minimum_balance(payload.len()),
+        space: payload.len() as u64,
+        owner: program_id,
+    }
+    .invoke()?;
+
+    asset.try_borrow_mut_data()?.copy_from_slice(payload);
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+//! Token-2022 transfer fees: the gross-up on deposits and reading the fee off the mint.
+
+use bondr_pinocchio::states::{transfer_fee_of_mint_data, TransferFee};
+use pinocchio::program_error::ProgramError;
+
+const MINT_CLOSE_AUTHORITY: u16 = 3;
+const TRANSFER_FEE_CONFIG: u16 = 1;
+
+/// A Token-2022 mint padded to the account length, followed by `extensions`.
+fn mint_data(extensions: &[(u16, Vec<u8>)]) -> Vec<u8> {
+    let mut data = vec![0; 165];
+    data[44] = 6; // decimals
+    data[45] = 1; // is_initialized
+    data.push(1); // AccountType::Mint
+
+    for (extension, value) in extensions {
+        data.extend_from_slice(&extension.to_le_bytes());
+        data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+        data.extend_from_slice(value);
+    }
+    data
+}
+
+/// A `TransferFeeConfig` value switching from `older` to `newer` at `newer_epoch`.
+fn fee_config(older: TransferFee, newer: TransferFee, newer_epoch: u64) -> Vec<u8> {
+    let mut value = vec![0; 72]; // authorities and withheld amount
+    for (epoch, fee) in [(0, older), (newer_epoch, newer)] {
+        value.extend_from_slice(&epoch.to_le_bytes());
+        value.extend_from_slice(&fee.maximum_fee.to_le_bytes());
+        value.extend_from_slice(&fee.basis_points.to_le_bytes());
+    }
+    value
+}
+
+fn fee(basis_points: u16, maximum_fee: u64) -> TransferFee {
+    TransferFee {
+        maximum_fee,
+        basis_points,
+    }
+}
+
+#[test]
+fn the_gross_up_leaves_exactly_the_amount_after_the_fee() {
+    for transfer_fee in [
+        fee(1, u64::MAX),
+        fee(100, 3),
+        fee(333, 50),
+        fee(9_999, 1_000),
+    ] {
+        for amount in 0..2_000 {
+            let deposit = transfer_fee.calculate_pre_fee_amount(amount).unwrap();
+            assert_eq!(
+                deposit - transfer_fee.calculate_fee(deposit).unwrap(),
+                amount,
+                "{transfer_fee:?} on {amount}"
+            );
+        }
+    }
+}
+
+#[test]
+fn the_gross_up_rounds_the_fee_up() {
+    // sending 101 would lose 2 to the fee, as 1% of it (1.01) rounds up
+    assert_eq!(fee(100, u64::MAX).calculate_pre_fee_amount(99), Some(100));
+    assert_eq!(fee(100, u64::MAX).calculate_pre_fee_amount(100), Some(102));
+}
+
+#[test]
+fn the_gross_up_without_a_fee_is_the_amount() {
+    assert_eq!(fee(0, 1_000).calculate_pre_fee_amount(12_345), Some(12_345));
+    assert_eq!(fee(0, 1_000).calculate_fee(12_345), Some(0));
+}
+
+#[test]
+fn the_gross_up_stops_at_the_maximum_fee() {
+    assert_eq!(fee(100, 5).calculate_pre_fee_amount(10_000), Some(10_005));
+    assert_eq!(fee(100, 5).calculate_fee(10_005), Some(5));
+    // the whole transfer would be fee, so only the cap is added
+    assert_eq!(
+        fee(10_000, 7).calculate_pre_fee_amount(10_000),
+        Some(10_007)
+    );
+}
+
+#[test]
+fn the_gross_up_overflow_is_none() {
+    assert_eq!(fee(100, u64::MAX).calculate_pre_fee_amount(u64::MAX), None);
+    assert_eq!(fee(10_000, 1).calculate_pre_fee_amount(u64::MAX), None);
+}
+
+#[test]
+fn mints_without_a_transfer_fee_have_none() {
+    assert_eq!(transfer_fee_of_mint_data(&[0; 82], 0), Ok(None));
+    assert_eq!(transfer_fee_of_mint_data(&mint_data(&[]), 0), Ok(None));
+    assert_eq!(
+        transfer_fee_of_mint_data(&mint_data(&[(MINT_CLOSE_AUTHORITY, vec![7; 32])]), 0),
+        Ok(None)
+    );
+}
+
+#[test]
+fn the_transfer_fee_is_read_past_other_extensions() {
+    let data = mint_data(&[
+        (MINT_CLOSE_AUTHORITY, vec![7; 32]),
+        (
+            TRANSFER_FEE_CONFIG,
+            fee_config(fee(50, 1_000), fee(200, 9_000), 10),
+        ),
+    ]);
+
+    assert_eq!(
+        transfer_fee_of_mint_data(&data, 9),
+        Ok(Some(fee(50, 1_000)))
+    );
+    assert_eq!(
+        transfer_fee_of_mint_data(&data, 10),
+        Ok(Some(fee(200, 9_000)))
+    );
+
+    // not a mint
+    let mut account = data.clone();
+    account[165] = 2;
+    assert_eq!(transfer_fee_of_mint_data(&account, 10), Ok(None));
+}
+
+#[test]
+fn malformed_extensions_are_rejected() {
+    let config = fee_config(fee(50, 1_000), fee(50, 1_000), 0);
+
+    let mut truncated = mint_data(&[(TRANSFER_FEE_CONFIG, config.clone())]);
+    truncated.pop();
+    assert_eq!(
+        transfer_fee_of_mint_data(&truncated, 0),
+        Err(ProgramError::InvalidAccountData)
+    );
+
+    let short = mint_data(&[(TRANSFER_FEE_CONFIG, config[..100].to_vec())]);
+    assert_eq!(
+        transfer_fee_of_mint_data(&short, 0),
+        Err(ProgramError::InvalidAccountData)
+    );
+}